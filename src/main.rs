@@ -5,891 +5,12161 @@ fn main() {
     eprintln!("This application is Windows-only.");
 }
 
+/// Entry list logic that needs no registry, kept outside `app` so its tests run anywhere.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+mod entries {
+    use std::collections::HashMap;
+    use std::env;
+
+    pub(crate) const EXPAND_PASSES: usize = 5;
+
+    pub(crate) enum DiffLine {
+        Same(String),
+        Added(String),
+        Removed(String),
+        Moved { entry: String, from: usize, to: usize },
+    }
+
+    /// Splits on `;` outside double quotes, so a quoted entry containing `;` stays whole.
+    pub(crate) fn split_path(path: &str) -> Vec<String> {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+        for ch in path.chars() {
+            match ch {
+                '"' => {
+                    quoted = !quoted;
+                    current.push(ch);
+                }
+                ';' if !quoted => parts.push(std::mem::take(&mut current)),
+                _ => current.push(ch),
+            }
+        }
+        parts.push(current);
+        parts
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    /// Quotes entries containing `;` so they survive `split_path` as a single entry.
+    pub(crate) fn join_path(parts: &[String]) -> String {
+        parts
+            .iter()
+            .map(|part| {
+                if part.contains(';') {
+                    format!("\"{}\"", part.replace('"', ""))
+                } else {
+                    part.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// Expands `%NAME%` tokens, repeating while a value itself contains defined tokens
+    /// (`%FOO%` set to `%BAR%\x`). Passes are capped so self-referential variables stop.
+    pub(crate) fn expand_env_vars(input: &str) -> String {
+        let mut out = expand_env_vars_once(input);
+        for _ in 1..EXPAND_PASSES {
+            if defined_tokens(&out).is_empty() {
+                break;
+            }
+            let next = expand_env_vars_once(&out);
+            if next == out {
+                break;
+            }
+            out = next;
+        }
+        out
+    }
+
+    pub(crate) fn expand_env_vars_once(input: &str) -> String {
+        let chars: Vec<char> = input.chars().collect();
+        let mut out = String::with_capacity(input.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] == '%' {
+                let mut j = i + 1;
+                while j < chars.len() && chars[j] != '%' {
+                    j += 1;
+                }
+                if j < chars.len() && j > i + 1 {
+                    let name: String = chars[i + 1..j].iter().collect();
+                    match env::var(&name) {
+                        Ok(value) => out.push_str(&value),
+                        Err(_) => {
+                            out.push('%');
+                            out.push_str(&name);
+                            out.push('%');
+                        }
+                    }
+                    i = j + 1;
+                    continue;
+                }
+            }
+
+            out.push(chars[i]);
+            i += 1;
+        }
+
+        out
+    }
+
+    /// Turns every `/` into `\`, so a forward-slashed UNC path (`//server/share`) becomes
+    /// `\\server\share` with its leading pair intact.
+    pub(crate) fn normalize_slashes(path: &str) -> String {
+        path.replace('/', "\\")
+    }
+
+    pub(crate) fn normalize_for_compare(path: &str) -> String {
+        let mut normalized = normalize_slashes(&expand_env_vars(path)).trim().to_lowercase();
+        while normalized.ends_with('\\') {
+            normalized.pop();
+        }
+        normalized
+    }
+
+    pub(crate) fn dedupe(parts: &[String]) -> Vec<String> {
+        dedupe_with(parts, false)
+    }
+
+    /// Keeps one entry per `normalize_for_compare` key, at the position of its first
+    /// occurrence. With `prefer_tokens` the surviving spelling is the group's first `%VAR%`
+    /// form when it has one, otherwise its first entry.
+    pub(crate) fn dedupe_with(parts: &[String], prefer_tokens: bool) -> Vec<String> {
+        let mut slots: HashMap<String, usize> = HashMap::new();
+        let mut out: Vec<String> = Vec::with_capacity(parts.len());
+        for part in parts {
+            match slots.entry(normalize_for_compare(part)) {
+                std::collections::hash_map::Entry::Occupied(slot) => {
+                    let kept = &mut out[*slot.get()];
+                    if prefer_tokens && env_tokens(kept).is_empty() && !env_tokens(part).is_empty()
+                    {
+                        kept.clone_from(part);
+                    }
+                }
+                std::collections::hash_map::Entry::Vacant(slot) => {
+                    slot.insert(out.len());
+                    out.push(part.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Line diff of two entry lists based on their longest common subsequence. An entry that
+    /// was both removed and added is reported once as `Moved`.
+    pub(crate) fn diff_entries(old: &[String], new: &[String]) -> Vec<DiffLine> {
+        let (n, m) = (old.len(), new.len());
+        let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        enum Op {
+            Same(usize),
+            Add(usize),
+            Del(usize),
+        }
+        let mut ops = Vec::with_capacity(n.max(m));
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old[i] == new[j] {
+                ops.push(Op::Same(j));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push(Op::Del(i));
+                i += 1;
+            } else {
+                ops.push(Op::Add(j));
+                j += 1;
+            }
+        }
+
+        let mut deleted = ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Del(i) => Some(*i),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut moved_from = vec![None; m];
+        for op in &ops {
+            if let Op::Add(j) = op {
+                if let Some(pos) = deleted.iter().position(|&i| old[i] == new[*j]) {
+                    moved_from[*j] = Some(deleted.remove(pos));
+                }
+            }
+        }
+
+        ops.into_iter()
+            .filter_map(|op| match op {
+                Op::Same(j) => Some(DiffLine::Same(new[j].clone())),
+                Op::Add(j) => Some(match moved_from[j] {
+                    Some(from) => DiffLine::Moved {
+                        entry: new[j].clone(),
+                        from,
+                        to: j,
+                    },
+                    None => DiffLine::Added(new[j].clone()),
+                }),
+                Op::Del(i) => deleted
+                    .contains(&i)
+                    .then(|| DiffLine::Removed(old[i].clone())),
+            })
+            .collect()
+    }
+
+    pub(crate) fn env_tokens(value: &str) -> Vec<String> {
+        let chars: Vec<char> = value.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] == '%' {
+                let mut j = i + 1;
+                while j < chars.len() && chars[j] != '%' {
+                    j += 1;
+                }
+                if j < chars.len() && j > i + 1 {
+                    tokens.push(chars[i + 1..j].iter().collect());
+                    i = j + 1;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        tokens
+    }
+
+    /// Tokens that name a defined variable. Left in an expanded value, they mean the pass
+    /// limit was hit, usually because a variable refers to itself.
+    pub(crate) fn defined_tokens(value: &str) -> Vec<String> {
+        env_tokens(value)
+            .into_iter()
+            .filter(|name| env::var(name).is_ok())
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn parts(items: &[&str]) -> Vec<String> {
+            items.iter().map(|item| item.to_string()).collect()
+        }
+
+        fn describe(diff: &[DiffLine]) -> Vec<String> {
+            diff.iter()
+                .map(|line| match line {
+                    DiffLine::Same(entry) => format!("= {entry}"),
+                    DiffLine::Added(entry) => format!("+ {entry}"),
+                    DiffLine::Removed(entry) => format!("- {entry}"),
+                    DiffLine::Moved { entry, from, to } => format!("~ {entry} {from}->{to}"),
+                })
+                .collect()
+        }
+
+        #[test]
+        fn diff_marks_added_and_removed_entries() {
+            let diff = diff_entries(&parts(&["a", "b", "c"]), &parts(&["a", "c", "d"]));
+            assert_eq!(describe(&diff), ["= a", "- b", "= c", "+ d"]);
+        }
+
+        #[test]
+        fn diff_reports_a_reordered_entry_as_moved() {
+            let diff = diff_entries(&parts(&["a", "b", "c"]), &parts(&["c", "a", "b"]));
+            assert_eq!(describe(&diff), ["~ c 2->0", "= a", "= b"]);
+        }
+
+        #[test]
+        fn diff_of_unchanged_list_is_all_same() {
+            let list = parts(&["a", "b"]);
+            assert_eq!(describe(&diff_entries(&list, &list)), ["= a", "= b"]);
+            assert!(diff_entries(&[], &[]).is_empty());
+        }
+
+        #[test]
+        fn entries_with_spaces_round_trip_unquoted() {
+            let list = parts(&[r"C:\Program Files\Git\cmd", r"C:\Tools"]);
+            assert_eq!(join_path(&list), r"C:\Program Files\Git\cmd;C:\Tools");
+            assert_eq!(split_path(&join_path(&list)), list);
+        }
+
+        #[test]
+        fn entry_with_semicolon_stays_one_entry_through_join_and_split() {
+            let list = parts(&[r"C:\odd;name", r"C:\Tools"]);
+            let joined = join_path(&list);
+            assert_eq!(joined, r#""C:\odd;name";C:\Tools"#);
+            assert_eq!(split_path(&joined), [r#""C:\odd;name""#, r"C:\Tools"]);
+            assert_eq!(split_path(&join_path(&split_path(&joined))), split_path(&joined));
+        }
+
+        #[test]
+        fn dedupe_keeps_the_first_of_each_normalized_group() {
+            env::set_var("PE_TEST_DEDUPE_ROOT", r"C:\TestRoot");
+            let list = parts(&[
+                r"C:\TestRoot\bin",
+                r"C:\Other",
+                r"%PE_TEST_DEDUPE_ROOT%\bin",
+                r"c:/testroot/bin\",
+                r"c:\other",
+            ]);
+            assert_eq!(dedupe(&list), [r"C:\TestRoot\bin", r"C:\Other"]);
+        }
+
+        #[test]
+        fn dedupe_can_prefer_the_tokenized_form() {
+            env::set_var("PE_TEST_TOKEN_ROOT", r"C:\TokenRoot");
+            let list = parts(&[
+                r"C:\TokenRoot\bin",
+                r"C:\Plain",
+                r"%PE_TEST_TOKEN_ROOT%\bin",
+                r"c:\plain\",
+            ]);
+            // The token form takes the literal's slot; groups without one keep the first.
+            assert_eq!(dedupe_with(&list, true), [r"%PE_TEST_TOKEN_ROOT%\bin", r"C:\Plain"]);
+
+            let token_first = parts(&[r"%PE_TEST_TOKEN_ROOT%\bin", r"C:\TokenRoot\bin"]);
+            assert_eq!(dedupe_with(&token_first, true), [r"%PE_TEST_TOKEN_ROOT%\bin"]);
+        }
+    }
+}
+
 #[cfg(target_os = "windows")]
 mod app {
-    use std::collections::{BTreeSet, HashSet};
+    use std::cmp::Ordering as CmpOrdering;
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
     use std::env;
     use std::error::Error;
     use std::ffi::OsStr;
+    use std::fs;
+    use std::io::Write;
     use std::os::windows::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
     use std::process;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex, OnceLock};
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
 
     use eframe::egui::{self, Color32, RichText, ScrollArea, TextEdit};
+    use regex::{Regex, RegexBuilder};
     use rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
-    use windows::core::PCWSTR;
-    use windows::Win32::Foundation::{LPARAM, WPARAM};
-    use windows::Win32::UI::Shell::{IsUserAnAdmin, ShellExecuteW};
+    use serde::{Deserialize, Serialize};
+    use sha2::{Digest, Sha256};
+    use windows::core::{PCWSTR, PWSTR};
+    use windows::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_NOT_ALL_ASSIGNED, ERROR_SHARING_VIOLATION, ERROR_SUCCESS,
+        FALSE, FILETIME, HANDLE, HGLOBAL, HWND, LPARAM, LUID, SYSTEMTIME, TRUE, WAIT_OBJECT_0,
+        WAIT_TIMEOUT, WPARAM,
+    };
+    use windows::Win32::Security::{
+        AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_BACKUP_NAME,
+        SE_PRIVILEGE_ENABLED, SE_RESTORE_NAME, TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES,
+        TOKEN_QUERY,
+    };
+    use windows::Win32::Globalization::{
+        GetLocaleInfoEx, GetUserDefaultUILanguage, LOCALE_NAME_USER_DEFAULT, LOCALE_STHOUSAND,
+    };
+    use windows::Win32::System::Com::{
+        CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE,
+    };
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    use windows::Win32::System::DataExchange::{CloseClipboard, GetClipboardData, OpenClipboard};
+    use windows::Win32::System::Memory::{GlobalLock, GlobalUnlock};
+    use windows::Win32::System::Registry::{
+        RegLoadKeyW, RegNotifyChangeKeyValue, RegQueryInfoKeyW, RegUnLoadKeyW,
+        REG_NOTIFY_CHANGE_LAST_SET,
+    };
+    use windows::Win32::System::SystemInformation::GetLocalTime;
+    use windows::Win32::System::Time::{FileTimeToSystemTime, SystemTimeToTzSpecificLocalTime};
+    use windows::Win32::System::Threading::{
+        CreateEventW, GetCurrentProcess, GetExitCodeProcess, IsWow64Process, OpenProcessToken,
+        SetEvent, WaitForMultipleObjects, WaitForSingleObject, INFINITE,
+    };
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO,
+        BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+    use windows::Win32::UI::Shell::{
+        IsUserAnAdmin, SHGetStockIconInfo, ShellExecuteExW, ShellExecuteW, SEE_MASK_NOCLOSEPROCESS,
+        SHELLEXECUTEINFOW, SHGSI_ICON, SHGSI_SMALLICON, SHSTOCKICONINFO, SIID_SHIELD,
+    };
     use windows::Win32::UI::WindowsAndMessaging::{
-        SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, SW_SHOW, WM_SETTINGCHANGE,
+        DestroyIcon, GetIconInfo, SendMessageTimeoutW, HWND_BROADCAST, ICONINFO,
+        SMTO_ABORTIFHUNG, SW_HIDE, SW_SHOW, WM_SETTINGCHANGE,
     };
     use winreg::enums::{
-        HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, KEY_SET_VALUE, REG_EXPAND_SZ, REG_SZ,
-        RegType,
+        HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, HKEY_USERS, KEY_NOTIFY, KEY_READ, KEY_SET_VALUE,
+        KEY_WOW64_64KEY, REG_DWORD, REG_EXPAND_SZ, REG_SZ, RegType,
     };
     use winreg::{HKEY, RegKey, RegValue};
 
+    use crate::entries::{
+        dedupe, dedupe_with, defined_tokens, diff_entries, env_tokens, expand_env_vars,
+        join_path, normalize_for_compare, normalize_slashes, split_path, DiffLine,
+    };
+
     const USER_ENV_KEY: &str = "Environment";
     const SYSTEM_ENV_KEY: &str = r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment";
+    const HIVE_MOUNT: &str = "PathEditorNative_Hive";
+    const HIVE_ENV_KEY: &str = r"PathEditorNative_Hive\Environment";
+    const REG_FILE_HEADER: &str = "Windows Registry Editor Version 5.00";
+    const APP_TITLE: &str = "PATH Editor Native";
+    const WATCH_DEBOUNCE: Duration = Duration::from_millis(750);
+    const SCAN_TIMEOUT: Duration = Duration::from_secs(3);
+    const TOAST_DURATION: Duration = Duration::from_secs(4);
+    const SCAN_SAMPLE: usize = 12;
+    const SCAN_WORKERS: usize = 4;
+    const BROADCAST_TIMEOUT_MS: u32 = 5000;
+    const HELPER_TIMEOUT_MS: u32 = 60_000;
+    const FOCUSED_PANEL_KEY: &str = "focused_panel";
+    const USER_FILTER_KEY: &str = "user_filter";
+    const SYSTEM_FILTER_KEY: &str = "system_filter";
+    const THEME_KEY: &str = "theme";
+    const LOGGING_KEY: &str = "logging";
+    const DRY_RUN_KEY: &str = "dry_run_default";
+    const CONFIRM_DIFF_KEY: &str = "confirm_diff";
+    const BACKUP_KEY: &str = "backup_on_save";
+    const BACKUP_KEEP_KEY: &str = "backup_keep";
+    const ADD_SCOPE_KEY: &str = "default_add_scope";
+    const BROWSE_TOKENIZE_KEY: &str = "browse_tokenize";
+    const ADD_FRONT_KEY: &str = "add_to_front";
+    const DEDUPE_TOKENS_KEY: &str = "dedupe_prefer_tokens";
+    const DISABLED_KEY: &str = "disabled_entries";
+    const CATEGORY_COLORS_KEY: &str = "category_colors";
+    const CRITICAL_PATHS_KEY: &str = "critical_paths";
+    const CLIPBOARD_CHECK_KEY: &str = "clipboard_check";
+    const LANGUAGE_KEY: &str = "language";
+    /// Standard clipboard format id; spelled out to avoid pulling in the OLE bindings.
+    const CF_UNICODETEXT: u32 = 13;
+    const DEFAULT_BACKUP_KEEP: usize = 10;
+    const MAX_BACKUP_KEEP: usize = 100;
+    const DEFAULT_BACKUP_HOURS: u64 = 24;
+    const MAX_BACKUP_HOURS: u64 = 24 * 30;
+    const STARTUP_BACKUP_KEY: &str = "startup_backup";
+    const STARTUP_BACKUP_HOURS_KEY: &str = "startup_backup_hours";
+    const SESSION_CHANGES_KEY: &str = "session_changes";
+    const SAVE_TOASTS_KEY: &str = "save_toasts";
+    const LAST_USER_PATH_KEY: &str = "last_session_user_path";
+    const LAST_SYSTEM_PATH_KEY: &str = "last_session_system_path";
+    const NOTES_KEY: &str = "entry_notes";
+    const LOG_FILE: &str = "path_editor.log";
+    const LOG_MAX_BYTES: u64 = 1024 * 1024;
+    const LOG_KEEP: usize = 3;
+    const FILESYSTEM_KEY: &str = r"SYSTEM\CurrentControlSet\Control\FileSystem";
+    const LEGACY_MAX_PATH: usize = 260;
+    /// Past this many characters Windows may stop expanding `%VAR%` tokens in a PATH value,
+    /// and several tools truncate it.
+    const LONG_PATH_CHARS: usize = 2047;
+    const PERSONALIZE_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+    const ACCENT: Color32 = Color32::from_rgb(0, 120, 212);
+    const WARNING_COLOR: Color32 = Color32::from_rgb(230, 170, 60);
+    const ERROR_COLOR: Color32 = Color32::from_rgb(230, 120, 100);
+    const ADDED_COLOR: Color32 = Color32::from_rgb(110, 200, 120);
+    const GLOBAL_SEARCH_ID: &str = "global_search";
+    const SEARCH_HIGHLIGHT: Color32 = Color32::from_rgba_premultiplied(0, 60, 106, 110);
+    const UNDO_LIMIT: usize = 50;
+    const HISTORY_LIMIT: usize = 300;
+    const TOKEN_VARS: [&str; 5] = [
+        "SystemRoot",
+        "ProgramFiles",
+        "ProgramFiles(x86)",
+        "LOCALAPPDATA",
+        "USERPROFILE",
+    ];
+    const USER_ONLY_TOKEN_VARS: [&str; 2] = ["LOCALAPPDATA", "USERPROFILE"];
+    static LOGGING_ENABLED: AtomicBool = AtomicBool::new(true);
+    static LOG_LOCK: Mutex<()> = Mutex::new(());
+    /// Set while another profile's NTUSER.DAT is mounted; the User scope then points at it.
+    static HIVE_LOADED: AtomicBool = AtomicBool::new(false);
+    /// Set by `--file`: the User panel then edits this text file instead of the registry.
+    static PATH_FILE: OnceLock<PathBuf> = OnceLock::new();
+    /// Selects the German string table for `tr`; set from the language setting.
+    static GERMAN_UI: AtomicBool = AtomicBool::new(false);
+
+    /// Default contents of the critical folders list in Settings.
+    const CRITICAL_PATHS: [&str; 4] = [
+        r"%SystemRoot%\System32",
+        r"%SystemRoot%",
+        r"%SystemRoot%\System32\Wbem",
+        r"%SystemRoot%\System32\WindowsPowerShell\v1.0",
+    ];
 
     pub fn run() -> eframe::Result<()> {
         let native_options = eframe::NativeOptions {
             viewport: egui::ViewportBuilder::default().with_inner_size([1220.0, 760.0]),
+            // eframe restores the saved geometry and clamps it onto a connected monitor.
+            persist_window: true,
             ..Default::default()
         };
 
         eframe::run_native(
-            "PATH Editor Native",
+            APP_TITLE,
             native_options,
             Box::new(|cc| Box::new(PathEditorApp::new(cc))),
         )
     }
 
-    #[derive(Clone)]
-    struct PathStore {
-        parts: Vec<String>,
-        filter: String,
-        selected: BTreeSet<usize>,
-        reg_type: RegType,
+    const CLI_USAGE: &str = "\
+Usage: path_editor_native [COMMAND]
+
+Without a command the editor window opens.
+
+Commands:
+  --list [--scope <scope>]          Print one PATH entry per line
+  --add <entry> [--scope <scope>]   Append an entry if it is not already present
+  --remove <entry> [--scope <scope>]
+                                    Remove every entry matching <entry>
+  --dedupe [--scope <scope>]        Remove duplicate entries
+  --export <file> [--scope <user|system|both>]
+                                    Write a .reg file (default scope: both)
+  --dry-run                         Print registry writes instead of performing them
+  --file <file>                     Open the editor with the User panel editing a PATH
+                                    text file instead of the registry
+  --help                            Show this help
+
+<scope> is user (default) or system. Writing System PATH requires an elevated prompt.";
+
+    enum CliCommand {
+        List(bool),
+        Add(String, bool),
+        Remove(String, bool),
+        Dedupe(bool),
+        Export(PathBuf, bool, bool),
+        /// Internal: the elevated helper started by the editor to write System PATH, with the
+        /// SHA-256 of the request file and the editor's UI language for its error messages.
+        ApplySystem(PathBuf, String, Language),
+        Help,
     }
 
-    impl PathStore {
-        fn new(raw: String, reg_type: RegType) -> Self {
-            Self {
-                parts: split_path(&raw),
-                filter: String::new(),
-                selected: BTreeSet::new(),
-                reg_type,
-            }
+    /// Runs a headless command when the arguments name one. Returns `None` to launch the GUI.
+    pub fn run_cli(args: &[String]) -> Option<i32> {
+        let first = args.first()?;
+        if !first.starts_with("--") {
+            return None;
         }
 
-        fn visible_indices(&self) -> Vec<usize> {
-            let filter = self.filter.trim().to_lowercase();
-            self.parts
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, part)| {
-                    if filter.is_empty() || part.to_lowercase().contains(&filter) {
-                        Some(idx)
-                    } else {
-                        None
+        if first == "--file" {
+            match args.get(1) {
+                Some(file) => {
+                    let _ = PATH_FILE.set(PathBuf::from(file));
+                    return None;
+                }
+                None => {
+                    unsafe {
+                        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
                     }
-                })
-                .collect()
+                    eprintln!("error: --file needs a value\n\n{CLI_USAGE}");
+                    return Some(2);
+                }
+            }
         }
 
-        fn raw_preview(&self) -> String {
-            join_path(&self.parts)
+        unsafe {
+            let _ = AttachConsole(ATTACH_PARENT_PROCESS);
         }
-    }
 
-    struct AddDialogState {
-        open: bool,
-        is_system: bool,
-        input: String,
-    }
+        let dry_run = args.iter().any(|arg| arg == "--dry-run");
+        let args = args
+            .iter()
+            .filter(|arg| *arg != "--dry-run")
+            .cloned()
+            .collect::<Vec<_>>();
 
-    impl Default for AddDialogState {
-        fn default() -> Self {
-            Self {
-                open: false,
-                is_system: false,
-                input: String::new(),
+        let command = match parse_cli(&args) {
+            Ok(command) => command,
+            Err(err) => {
+                eprintln!("error: {err}\n\n{CLI_USAGE}");
+                return Some(2);
             }
-        }
-    }
+        };
 
-    #[derive(Default)]
-    struct ExpandedDialogState {
-        open: bool,
-        is_system: bool,
+        match execute_cli(command, dry_run) {
+            Ok(()) => Some(0),
+            Err(err) => {
+                eprintln!("error: {err}");
+                Some(1)
+            }
+        }
     }
 
-    struct PathEditorApp {
-        user: PathStore,
-        system: PathStore,
-        status: String,
-        is_admin: bool,
-        add_dialog: AddDialogState,
-        expanded_dialog: ExpandedDialogState,
+    fn parse_scope(value: &str) -> Result<(bool, bool), String> {
+        match value.to_lowercase().as_str() {
+            "user" => Ok((true, false)),
+            "system" => Ok((false, true)),
+            "both" => Ok((true, true)),
+            other => Err(format!("unknown scope '{other}'")),
+        }
     }
 
-    impl PathEditorApp {
-        fn new(cc: &eframe::CreationContext<'_>) -> Self {
-            apply_style(&cc.egui_ctx);
-
-            let (user_raw, user_type) =
-                read_reg_value(HKEY_CURRENT_USER, USER_ENV_KEY, "Path").unwrap_or_else(|_| {
-                    (String::new(), REG_SZ)
-                });
-            let (system_raw, system_type) =
-                read_reg_value(HKEY_LOCAL_MACHINE, SYSTEM_ENV_KEY, "Path").unwrap_or_else(|_| {
-                    (String::new(), REG_SZ)
-                });
-
-            Self {
-                user: PathStore::new(user_raw, user_type),
-                system: PathStore::new(system_raw, system_type),
-                status: "Ready".to_string(),
-                is_admin: is_admin(),
-                add_dialog: AddDialogState::default(),
-                expanded_dialog: ExpandedDialogState::default(),
-            }
+    fn parse_cli(args: &[String]) -> Result<CliCommand, String> {
+        if args.is_empty() {
+            return Err("no command given".to_string());
         }
 
-        fn panel_title(is_system: bool) -> &'static str {
-            if is_system {
-                "System PATH (HKLM)"
+        let mut positional: Option<&str> = None;
+        let mut scope: Option<&str> = None;
+        let mut digest: Option<&str> = None;
+        let mut language: Option<&str> = None;
+        let mut rest = args.iter().skip(1);
+        while let Some(arg) = rest.next() {
+            if arg == "--scope" {
+                scope = Some(rest.next().ok_or("--scope needs a value")?.as_str());
+            } else if arg == "--sha256" {
+                digest = Some(rest.next().ok_or("--sha256 needs a value")?.as_str());
+            } else if arg == "--language" {
+                language = Some(rest.next().ok_or("--language needs a value")?.as_str());
+            } else if positional.is_none() {
+                positional = Some(arg.as_str());
             } else {
-                "User PATH (HKCU)"
+                return Err(format!("unexpected argument '{arg}'"));
             }
         }
 
-        fn open_add_dialog(&mut self, is_system: bool) {
-            self.add_dialog.open = true;
-            self.add_dialog.is_system = is_system;
-            self.add_dialog.input.clear();
-        }
+        let single_scope = |scope: Option<&str>| -> Result<bool, String> {
+            match parse_scope(scope.unwrap_or("user"))? {
+                (true, true) => Err("this command needs a user or system scope".to_string()),
+                (_, is_system) => Ok(is_system),
+            }
+        };
+        let need = |name: &str| {
+            positional
+                .map(str::to_string)
+                .ok_or(format!("{name} needs a value"))
+        };
 
-        fn open_expanded_dialog(&mut self, is_system: bool) {
-            self.expanded_dialog.open = true;
-            self.expanded_dialog.is_system = is_system;
+        match args[0].as_str() {
+            "--help" | "-h" => Ok(CliCommand::Help),
+            "--list" if positional.is_some() && scope.is_some() => {
+                Err("--list takes its scope either as an argument or through --scope".to_string())
+            }
+            "--list" => Ok(CliCommand::List(single_scope(scope.or(positional))?)),
+            "--add" => Ok(CliCommand::Add(need("--add")?, single_scope(scope)?)),
+            "--remove" => Ok(CliCommand::Remove(need("--remove")?, single_scope(scope)?)),
+            "--dedupe" => Ok(CliCommand::Dedupe(single_scope(scope)?)),
+            "--export" => {
+                let file = PathBuf::from(need("--export")?);
+                let (user, system) = parse_scope(scope.unwrap_or("both"))?;
+                Ok(CliCommand::Export(file, user, system))
+            }
+            "--apply-system" => {
+                let file = PathBuf::from(need("--apply-system")?);
+                let digest = digest.ok_or("--apply-system needs --sha256")?;
+                let language = match language {
+                    Some(key) => {
+                        Language::from_key(key).ok_or(format!("unknown language '{key}'"))?
+                    }
+                    None => Language::English,
+                };
+                Ok(CliCommand::ApplySystem(file, digest.to_string(), language))
+            }
+            other => Err(format!("unknown command '{other}'")),
         }
+    }
 
-        fn store(&self, is_system: bool) -> &PathStore {
-            if is_system {
-                &self.system
-            } else {
-                &self.user
+    fn execute_cli(command: CliCommand, dry_run: bool) -> Result<(), Box<dyn Error>> {
+        match command {
+            CliCommand::Help => println!("{CLI_USAGE}"),
+            CliCommand::List(is_system) => {
+                for part in load_store(is_system, "Path").parts {
+                    println!("{part}");
+                }
+            }
+            CliCommand::Add(entry, is_system) => {
+                let mut store = load_store(is_system, "Path");
+                let key = normalize_for_compare(&entry);
+                if store.parts.iter().any(|p| normalize_for_compare(p) == key) {
+                    println!("Already present: {entry}");
+                    return Ok(());
+                }
+                store.parts.push(entry.clone());
+                write_store_cli(is_system, &store, dry_run)?;
+                println!("Added: {entry}");
+            }
+            CliCommand::Remove(entry, is_system) => {
+                let mut store = load_store(is_system, "Path");
+                let key = normalize_for_compare(&entry);
+                let before = store.parts.len();
+                store.parts.retain(|p| normalize_for_compare(p) != key);
+                let removed = before - store.parts.len();
+                if removed == 0 {
+                    return Err(format!("not found: {entry}").into());
+                }
+                write_store_cli(is_system, &store, dry_run)?;
+                println!("Removed {removed} entry/entries");
+            }
+            CliCommand::Dedupe(is_system) => {
+                let mut store = load_store(is_system, "Path");
+                let before = store.parts.len();
+                store.parts = dedupe(&store.parts);
+                let removed = before - store.parts.len();
+                if removed > 0 {
+                    write_store_cli(is_system, &store, dry_run)?;
+                }
+                println!("Dedupe removed {removed} entries");
+            }
+            CliCommand::Export(file, user, system) => {
+                let mut sections = Vec::new();
+                for (include, is_system, key_path) in [
+                    (user, false, format!(r"HKEY_CURRENT_USER\{USER_ENV_KEY}")),
+                    (system, true, format!(r"HKEY_LOCAL_MACHINE\{SYSTEM_ENV_KEY}")),
+                ] {
+                    if include {
+                        let store = load_store(is_system, "Path");
+                        sections.push((
+                            key_path,
+                            store.var_name.clone(),
+                            store.raw_preview(),
+                            store.write_type(),
+                        ));
+                    }
+                }
+                fs::write(&file, encode_reg_file(&build_reg_file(&sections)))?;
+                println!("Exported to {}", file.display());
+            }
+            CliCommand::ApplySystem(file, digest, language) => {
+                GERMAN_UI.store(language.resolve() == Language::German, Ordering::Relaxed);
+                // The editor waits on this process but has no console to read, so the
+                // reason for a failure goes into a file next to the request.
+                if let Err(err) = apply_system_request(&file, &digest) {
+                    let _ = fs::write(helper_error_file(&file), err.to_string());
+                    return Err(err);
+                }
             }
         }
+        Ok(())
+    }
 
-        fn store_mut(&mut self, is_system: bool) -> &mut PathStore {
-            if is_system {
-                &mut self.system
-            } else {
-                &mut self.user
-            }
+    /// A System save handed to the elevated helper, finished by `poll_elevated_save`.
+    struct ElevatedSave {
+        worker: JoinHandle<Result<(), String>>,
+        name: String,
+        value: String,
+        /// The entries that were sent, to compare with what the registry holds afterwards.
+        enabled: Vec<String>,
+        is_path: bool,
+        /// Close the window once the save succeeds ("save and exit").
+        then_exit: bool,
+    }
+
+    /// What the editor asks the elevated helper to write to System PATH.
+    #[derive(Serialize, Deserialize)]
+    struct SystemWriteRequest {
+        name: String,
+        value: String,
+        expand: bool,
+        /// Backups to keep, or `None` when backups before saving are turned off.
+        backup_keep: Option<usize>,
+    }
+
+    fn helper_error_file(request: &Path) -> PathBuf {
+        request.with_extension("error")
+    }
+
+    /// Validates the request, backs up the current value, then writes and broadcasts it.
+    ///
+    /// The request sits in the user's temp folder, where any unelevated process could swap it
+    /// while the UAC prompt is up, so it is only applied if it still matches the digest the
+    /// editor put on the elevated command line.
+    fn apply_system_request(file: &Path, digest: &str) -> Result<(), Box<dyn Error>> {
+        if !is_admin() {
+            return Err(tr(Text::HelperNotAdmin).into());
+        }
+        let bytes = fs::read(file)?;
+        if !sha256_hex(&bytes).eq_ignore_ascii_case(digest) {
+            return Err(tr(Text::RequestChanged).into());
+        }
+        let request: SystemWriteRequest = serde_json::from_slice(&bytes)?;
+        let name = request.name.trim();
+        if name.is_empty() || name.contains(['\\', '=']) {
+            return Err(trf(Text::InvalidVariableName, &[&request.name]).into());
+        }
+        if request.value.encode_utf16().count() >= 32767 {
+            return Err(tr(Text::ValueTooLong).into());
+        }
+        if name.eq_ignore_ascii_case("Path") && split_path(&request.value).is_empty() {
+            return Err(tr(Text::EmptySystemPath).into());
         }
 
-        fn remove_selected(&mut self, is_system: bool) {
-            let store = self.store_mut(is_system);
-            let before = store.parts.len();
-            store.parts = store
-                .parts
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, part)| {
-                    if store.selected.contains(&idx) {
-                        None
-                    } else {
-                        Some(part.clone())
-                    }
-                })
-                .collect();
-            let removed = before.saturating_sub(store.parts.len());
-            store.selected.clear();
-            self.status = format!(
-                "Removed {removed} {} entry/entries",
-                Self::panel_title(is_system)
+        if let Some(keep) = request.backup_keep {
+            // A variable that does not exist yet has nothing to back up.
+            let key = open_env_subkey(HKEY_LOCAL_MACHINE, SYSTEM_ENV_KEY, KEY_READ)?;
+            let backup: Result<(), Box<dyn Error>> = match key.get_raw_value(name) {
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(err) => Err(err.into()),
+                Ok(_) => backup_reg_value(true, name, keep),
+            };
+            backup.map_err(|err| trf(Text::BackupFailed, &[&err]))?;
+        }
+        let vtype = if request.expand { REG_EXPAND_SZ } else { REG_SZ };
+        write_reg_value(HKEY_LOCAL_MACHINE, SYSTEM_ENV_KEY, name, &request.value, vtype)?;
+        broadcast_env_change();
+        Ok(())
+    }
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        Sha256::digest(bytes).iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// Runs this exe elevated with `--apply-system <file> --sha256 <digest>` and waits up to
+    /// `HELPER_TIMEOUT_MS` for it to finish. The UAC prompt is the only window it shows. Blocks,
+    /// so the editor calls it from a worker thread.
+    fn run_elevated_helper(file: &Path, digest: &str) -> Result<(), Box<dyn Error>> {
+        let exe = to_wide(&env::current_exe()?.to_string_lossy());
+        let args = to_wide(&format!(
+            "--apply-system {} --sha256 {digest} --language {}",
+            quote_arg(&file.to_string_lossy()),
+            ui_language().key()
+        ));
+        let verb = to_wide("runas");
+        let mut info = SHELLEXECUTEINFOW {
+            cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+            fMask: SEE_MASK_NOCLOSEPROCESS,
+            lpVerb: PCWSTR(verb.as_ptr()),
+            lpFile: PCWSTR(exe.as_ptr()),
+            lpParameters: PCWSTR(args.as_ptr()),
+            nShow: SW_HIDE.0,
+            ..Default::default()
+        };
+        unsafe { ShellExecuteExW(&mut info) }
+            .map_err(|err| trf(Text::HelperNotStarted, &[&err]))?;
+
+        let mut code = 1u32;
+        let (waited, exited) = unsafe {
+            let waited = WaitForSingleObject(info.hProcess, HELPER_TIMEOUT_MS);
+            let exited = GetExitCodeProcess(info.hProcess, &mut code);
+            let _ = CloseHandle(info.hProcess);
+            (waited, exited)
+        };
+        if waited == WAIT_TIMEOUT {
+            return Err(trf(Text::HelperTimedOut, &[&(HELPER_TIMEOUT_MS / 1000)]).into());
+        }
+        exited?;
+        if code == 0 {
+            return Ok(());
+        }
+        let error_file = helper_error_file(file);
+        let message =
+            fs::read_to_string(&error_file).unwrap_or_else(|_| trf(Text::HelperExitCode, &[&code]));
+        let _ = fs::remove_file(&error_file);
+        Err(message.into())
+    }
+
+    fn write_store_cli(
+        is_system: bool,
+        store: &PathStore,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if dry_run {
+            let value = store.raw_preview();
+            println!(
+                "{}",
+                describe_write(is_system, &store.var_name, Some(&value), &store.write_type())
             );
+            return Ok(());
         }
 
-        fn move_selected(&mut self, is_system: bool, direction: i32) {
-            let store = self.store_mut(is_system);
-            if !store.filter.trim().is_empty() {
-                MessageDialog::new()
-                    .set_level(MessageLevel::Info)
-                    .set_title("Move disabled while filtering")
-                    .set_description("Clear the filter before moving entries.")
-                    .set_buttons(MessageButtons::Ok)
-                    .show();
-                return;
+        let (root, subkey) = env_key(is_system);
+        write_reg_value(
+            root,
+            subkey,
+            &store.var_name,
+            &store.raw_preview(),
+            store.write_type(),
+        )?;
+        if !broadcast_env_change() {
+            eprintln!(
+                "Warning: saved, but the environment change broadcast failed or timed out; \
+                 new processes may not see it until you sign out"
+            );
+        }
+        Ok(())
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum FilterMode {
+        Substring,
+        Regex,
+        Fuzzy,
+    }
+
+    impl FilterMode {
+        const ALL: [FilterMode; 3] = [FilterMode::Substring, FilterMode::Regex, FilterMode::Fuzzy];
+
+        fn label(self) -> &'static str {
+            match self {
+                FilterMode::Substring => tr(Text::FilterSubstring),
+                FilterMode::Regex => tr(Text::Regex),
+                FilterMode::Fuzzy => tr(Text::FilterFuzzy),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum TypeOverride {
+        Auto,
+        Sz,
+        ExpandSz,
+    }
+
+    impl TypeOverride {
+        const ALL: [TypeOverride; 3] = [
+            TypeOverride::Auto,
+            TypeOverride::Sz,
+            TypeOverride::ExpandSz,
+        ];
+
+        fn label(self) -> &'static str {
+            match self {
+                TypeOverride::Auto => tr(Text::TypeAuto),
+                TypeOverride::Sz => "REG_SZ",
+                TypeOverride::ExpandSz => "REG_EXPAND_SZ",
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    struct PathStore {
+        var_name: String,
+        var_names: Vec<String>,
+        parts: Vec<String>,
+        filter: String,
+        filter_mode: FilterMode,
+        selected: BTreeSet<usize>,
+        reg_type: RegType,
+        type_override: TypeOverride,
+        saved_parts: Vec<String>,
+        /// Entries kept in the list but left out of the value written to the registry, keyed by
+        /// text and by which occurrence of that text they are, so identical copies toggle apart.
+        disabled: BTreeSet<(String, usize)>,
+        disabled_synced: bool,
+        saved_type: RegType,
+        /// Earlier `parts` together with the disabled entries at that point, newest last.
+        history: Vec<(Vec<String>, BTreeSet<(String, usize)>)>,
+        cursor: Option<usize>,
+        scroll_offset: Option<f32>,
+        generation: u64,
+        derived: Derived,
+        info_cache: HashMap<String, EntryInfo>,
+        pending_log: Option<usize>,
+        last_write: Option<String>,
+    }
+
+    /// A disabled entry remembered between sessions, see `sync_disabled`.
+    #[derive(Clone, Debug, PartialEq)]
+    struct DisabledEntry {
+        position: usize,
+        /// Enabled entries with the same text at the time, so an enabled twin can be told
+        /// apart from a copy that was re-enabled outside the editor.
+        enabled_copies: usize,
+        value: String,
+    }
+
+    /// Per-entry analysis cached by entry text, so rows never redo it while scrolling.
+    struct EntryInfo {
+        expanded: String,
+        unresolved: Vec<String>,
+        key: String,
+        relative: bool,
+        problem: Option<&'static str>,
+        /// Expanded length in characters when it exceeds the legacy MAX_PATH.
+        too_long: Option<usize>,
+        category: EntryCategory,
+    }
+
+    impl EntryInfo {
+        fn compute(entry: &str) -> Self {
+            let expanded = expand_env_vars(entry);
+            Self {
+                unresolved: unresolved_tokens(&expanded),
+                key: normalize_for_compare(entry),
+                relative: !is_absolute_entry(entry),
+                problem: entry_problem(entry),
+                too_long: Some(expanded.chars().count()).filter(|&len| len > LEGACY_MAX_PATH),
+                category: classify_path(&expanded, &category_roots()),
+                expanded,
+            }
+        }
+    }
+
+    /// Whole-list analysis for one `PathStore::generation`, so it is rebuilt once per edit
+    /// rather than once per frame.
+    #[derive(Default)]
+    struct Derived {
+        generation: Option<u64>,
+        keys: Arc<HashSet<String>>,
+        undefined: usize,
+        bytes: usize,
+    }
+
+    #[derive(Clone)]
+    struct DirScan {
+        executables: usize,
+        /// Every executable in the folder, sorted case-insensitively.
+        names: Vec<String>,
+        /// Comparison key of the folder with symlinks and junctions resolved.
+        canonical: Option<String>,
+    }
+
+    #[derive(Clone)]
+    enum ScanState {
+        Pending(Instant),
+        Done(DirScan),
+        Missing,
+    }
+
+    #[derive(Default)]
+    struct ScanQueue {
+        pending: VecDeque<String>,
+        workers: usize,
+    }
+
+    /// Scans folders for executables on a few worker threads so slow or offline network shares
+    /// never block the UI. Results are cached by expanded path until `clear` is called.
+    #[derive(Clone, Default)]
+    struct DirScanner {
+        results: Arc<Mutex<HashMap<String, ScanState>>>,
+        queue: Arc<Mutex<ScanQueue>>,
+    }
+
+    impl DirScanner {
+        fn get(&self, dir: &str, ctx: &egui::Context) -> ScanState {
+            let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(state) = results.get(dir) {
+                return state.clone();
             }
 
-            let old = store.selected.clone();
-            if old.is_empty() {
+            let state = ScanState::Pending(Instant::now());
+            results.insert(dir.to_string(), state.clone());
+            drop(results);
+            self.enqueue(dir, ctx);
+            state
+        }
+
+        /// Queues a folder, starting another worker while fewer than `SCAN_WORKERS` run. A long
+        /// PATH full of unreachable shares therefore waits in line instead of spawning a thread
+        /// per entry.
+        fn enqueue(&self, dir: &str, ctx: &egui::Context) {
+            let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+            queue.pending.push_back(dir.to_string());
+            if queue.workers >= SCAN_WORKERS {
                 return;
             }
+            let scanner = self.clone();
+            let ctx = ctx.clone();
+            let spawned = thread::Builder::new()
+                .name("dir-scan".to_string())
+                .spawn(move || scanner.work(&ctx));
+            if spawned.is_ok() {
+                queue.workers += 1;
+            }
+        }
 
-            let mut new_selected = old.clone();
-            if direction < 0 {
-                for idx in old.iter().copied() {
-                    if idx > 0 && !old.contains(&(idx - 1)) {
-                        store.parts.swap(idx, idx - 1);
-                        new_selected.remove(&idx);
-                        new_selected.insert(idx - 1);
-                    }
-                }
-            } else {
-                for idx in old.iter().copied().rev() {
-                    if idx + 1 < store.parts.len() && !old.contains(&(idx + 1)) {
-                        store.parts.swap(idx, idx + 1);
-                        new_selected.remove(&idx);
-                        new_selected.insert(idx + 1);
+        /// Worker loop: scans queued folders until the queue is empty, then exits.
+        fn work(&self, ctx: &egui::Context) {
+            loop {
+                let dir = {
+                    let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+                    match queue.pending.pop_front() {
+                        Some(dir) => dir,
+                        None => {
+                            queue.workers -= 1;
+                            return;
+                        }
                     }
+                };
+                // The timeout shown in the UI counts from here, not from when it was queued.
+                if let Some(state @ ScanState::Pending(_)) =
+                    self.results.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&dir)
+                {
+                    *state = ScanState::Pending(Instant::now());
                 }
+                let scanned = scan_directory(Path::new(&dir));
+                self.results
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(dir, scanned);
+                ctx.request_repaint();
             }
-            store.selected = new_selected;
-            self.status = format!("Reordered {}", Self::panel_title(is_system));
         }
 
-        fn apply_dedupe(&mut self, is_system: bool) {
-            let store = self.store_mut(is_system);
-            let before = store.parts.len();
-            store.parts = dedupe(&store.parts);
-            store.selected.clear();
-            self.status = format!(
-                "Dedupe removed {} entries from {}",
-                before.saturating_sub(store.parts.len()),
-                Self::panel_title(is_system)
-            );
+        /// The resolved target of a scanned folder, starting a scan if there is none yet.
+        fn canonical(&self, dir: &str, ctx: &egui::Context) -> Option<String> {
+            match self.results.lock().unwrap_or_else(|e| e.into_inner()).get(dir) {
+                Some(ScanState::Done(scan)) => return scan.canonical.clone(),
+                Some(_) => return None,
+                None => {}
+            }
+            self.get(dir, ctx);
+            None
         }
 
-        fn apply_sort(&mut self, is_system: bool) {
-            let store = self.store_mut(is_system);
-            sort_case_insensitive(&mut store.parts);
-            store.selected.clear();
-            self.status = format!("Sorted {}", Self::panel_title(is_system));
+        fn clear(&self) {
+            self.queue.lock().unwrap_or_else(|e| e.into_inner()).pending.clear();
+            self.results.lock().unwrap_or_else(|e| e.into_inner()).clear();
         }
 
-        fn save_one(&mut self, is_system: bool) {
-            if is_system && !self.is_admin {
-                MessageDialog::new()
-                    .set_level(MessageLevel::Error)
-                    .set_title("Administrator required")
-                    .set_description("Saving System PATH requires running as Administrator.")
-                    .set_buttons(MessageButtons::Ok)
-                    .show();
-                return;
+        fn forget(&self, dir: &str) {
+            self.results.lock().unwrap_or_else(|e| e.into_inner()).remove(dir);
+        }
+    }
+
+    impl PathStore {
+        fn new(var_name: &str, raw: String, reg_type: RegType) -> Self {
+            let parts = split_path(&raw);
+            Self {
+                var_name: var_name.to_string(),
+                var_names: Vec::new(),
+                saved_parts: parts.clone(),
+                disabled: BTreeSet::new(),
+                disabled_synced: false,
+                saved_type: reg_type.clone(),
+                parts,
+                filter: String::new(),
+                filter_mode: FilterMode::Substring,
+                selected: BTreeSet::new(),
+                reg_type,
+                type_override: TypeOverride::Auto,
+                history: Vec::new(),
+                cursor: None,
+                scroll_offset: None,
+                generation: 0,
+                derived: Derived::default(),
+                info_cache: HashMap::new(),
+                pending_log: None,
+                last_write: None,
             }
+        }
 
-            match self.write_path(is_system) {
-                Ok(()) => {
-                    let target = if is_system { "System" } else { "User" };
-                    self.status = format!("Saved {target} PATH and broadcasted change");
-                    MessageDialog::new()
-                        .set_level(MessageLevel::Info)
-                        .set_title("Saved")
-                        .set_description(format!(
-                            "{target} PATH saved. New terminals/apps will see the change."
-                        ))
-                        .set_buttons(MessageButtons::Ok)
-                        .show();
-                }
-                Err(err) => {
-                    MessageDialog::new()
-                        .set_level(MessageLevel::Error)
-                        .set_title("Save failed")
-                        .set_description(err.to_string())
-                        .set_buttons(MessageButtons::Ok)
-                        .show();
-                }
+        fn entry_info(&mut self, idx: usize) -> &EntryInfo {
+            let part = &self.parts[idx];
+            if !self.info_cache.contains_key(part) {
+                self.info_cache.insert(part.clone(), EntryInfo::compute(part));
             }
+            &self.info_cache[part]
         }
 
-        fn save_all(&mut self) {
-            if let Err(err) = self.write_path(false) {
-                MessageDialog::new()
-                    .set_level(MessageLevel::Error)
-                    .set_title("Save failed")
-                    .set_description(err.to_string())
-                    .set_buttons(MessageButtons::Ok)
-                    .show();
+        /// Moves the keyboard cursor `step` rows through the visible entries.
+        fn move_cursor(&mut self, step: isize) {
+            let visible = self.visible_indices();
+            if visible.is_empty() {
+                self.cursor = None;
                 return;
             }
+            let last = visible.len() as isize - 1;
+            let next = match self.cursor.and_then(|c| visible.iter().position(|&v| v == c)) {
+                Some(pos) => (pos as isize + step).clamp(0, last),
+                None if step > 0 => 0,
+                None => last,
+            };
+            self.cursor = Some(visible[next as usize]);
+        }
+
+        fn undefined_count(&mut self) -> usize {
+            self.derived().undefined
+        }
+
+        /// Entries whose text or expansion contains `query` (already lowercased). Unlike the
+        /// filter this never hides anything; rows are only highlighted.
+        fn search_matches(&mut self, query: &str) -> HashSet<usize> {
+            if query.is_empty() {
+                return HashSet::new();
+            }
+            (0..self.parts.len())
+                .filter(|&idx| {
+                    self.parts[idx].to_lowercase().contains(query)
+                        || self.entry_info(idx).expanded.to_lowercase().contains(query)
+                })
+                .collect()
+        }
 
-            if self.is_admin {
-                if let Err(err) = self.write_path(true) {
-                    MessageDialog::new()
-                        .set_level(MessageLevel::Error)
-                        .set_title("Save failed")
-                        .set_description(err.to_string())
-                        .set_buttons(MessageButtons::Ok)
-                        .show();
-                    return;
+        /// The cached analysis of `parts`, recomputed only after the generation has moved on.
+        fn derived(&mut self) -> &Derived {
+            if self.derived.generation != Some(self.generation) {
+                let mut keys = HashSet::new();
+                let mut undefined = 0;
+                for idx in 0..self.parts.len() {
+                    let info = self.entry_info(idx);
+                    keys.insert(info.key.clone());
+                    if !info.unresolved.is_empty() {
+                        undefined += 1;
+                    }
                 }
-                self.status = "Saved User + System PATH and broadcasted change".to_string();
-                MessageDialog::new()
-                    .set_level(MessageLevel::Info)
-                    .set_title("Saved")
-                    .set_description("User and System PATH saved.")
-                    .set_buttons(MessageButtons::Ok)
-                    .show();
-            } else {
-                self.status = "Saved User PATH (System PATH skipped - not admin)".to_string();
-                MessageDialog::new()
-                    .set_level(MessageLevel::Info)
-                    .set_title("Saved")
-                    .set_description("User PATH saved. System PATH was skipped because this process is not elevated.")
-                    .set_buttons(MessageButtons::Ok)
-                    .show();
+                self.derived = Derived {
+                    generation: Some(self.generation),
+                    keys: Arc::new(keys),
+                    undefined,
+                    bytes: encode_utf16_reg(&self.raw_preview()).len(),
+                };
             }
+            &self.derived
         }
 
-        fn write_path(&mut self, is_system: bool) -> Result<(), Box<dyn Error>> {
-            let store = self.store(is_system);
-            let value = join_path(&store.parts);
-            let mut vtype = store.reg_type.clone();
+        fn checkpoint(&mut self) {
+            self.push_history(self.parts.clone());
+        }
 
-            if has_env_token(&value) {
-                vtype = REG_EXPAND_SZ;
-            } else if vtype != REG_SZ && vtype != REG_EXPAND_SZ {
-                vtype = REG_SZ;
+        /// Every edit to `parts` records history, so this is also where the generation moves.
+        fn push_history(&mut self, parts: Vec<String>) {
+            self.generation += 1;
+            self.pending_log.get_or_insert(parts.len());
+            if self.history.len() == UNDO_LIMIT {
+                self.history.remove(0);
             }
+            self.history.push((parts, self.disabled.clone()));
+        }
 
-            if is_system {
-                write_reg_value(
-                    HKEY_LOCAL_MACHINE,
-                    SYSTEM_ENV_KEY,
-                    "Path",
-                    &value,
-                    vtype.clone(),
-                )?;
-                self.system.reg_type = vtype;
-            } else {
-                write_reg_value(
-                    HKEY_CURRENT_USER,
-                    USER_ENV_KEY,
-                    "Path",
-                    &value,
-                    vtype.clone(),
-                )?;
-                self.user.reg_type = vtype;
+        fn undo(&mut self) -> bool {
+            match self.history.pop() {
+                Some((parts, disabled)) => {
+                    self.parts = parts;
+                    self.disabled = disabled;
+                    self.generation += 1;
+                    self.selected.clear();
+                    true
+                }
+                None => false,
             }
+        }
 
-            broadcast_env_change();
+        fn is_path(&self) -> bool {
+            self.var_name.eq_ignore_ascii_case("Path")
+        }
 
-            if is_system {
-                let merged = join_path(
-                    &self
-                        .user
-                        .parts
-                        .iter()
-                        .chain(self.system.parts.iter())
-                        .cloned()
-                        .collect::<Vec<_>>(),
-                );
-                env::set_var("PATH", merged);
+        fn display_name(&self) -> &str {
+            if self.is_path() {
+                "PATH"
             } else {
-                env::set_var("PATH", value);
+                &self.var_name
             }
+        }
 
-            Ok(())
+        /// Compares against the last value read from or written to the registry, so edits
+        /// that are undone by hand count as clean again.
+        fn is_dirty(&self) -> bool {
+            let forced_type = self.type_override != TypeOverride::Auto;
+            self.enabled_parts() != self.saved_parts
+                || self.reg_type != self.saved_type
+                || (forced_type && self.write_type() != self.saved_type)
         }
 
-        fn draw_panel(&mut self, ui: &mut egui::Ui, is_system: bool) {
-            let mut do_add = false;
-            let mut do_browse = false;
-            let mut do_remove = false;
-            let mut do_up = false;
-            let mut do_down = false;
-            let mut do_dedupe = false;
-            let mut do_sort = false;
-            let mut do_expand = false;
-            let mut do_save = false;
+        fn mark_saved(&mut self) {
+            self.saved_parts = self.enabled_parts();
+            self.saved_type = self.reg_type.clone();
+        }
 
-            {
-                let store = self.store_mut(is_system);
+        fn visible_indices(&self) -> Vec<usize> {
+            let filter = self.filter.trim().to_lowercase();
+            let regex = match self.filter_mode {
+                FilterMode::Regex if !filter.is_empty() => match self.filter_regex() {
+                    Ok(regex) => Some(regex),
+                    Err(_) => return (0..self.parts.len()).collect(),
+                },
+                _ => None,
+            };
+            self.parts
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, part)| {
+                    let matched = filter.is_empty()
+                        || match self.filter_mode {
+                            FilterMode::Substring => part.to_lowercase().contains(&filter),
+                            FilterMode::Regex => regex.as_ref().is_some_and(|r| r.is_match(part)),
+                            FilterMode::Fuzzy => fuzzy_score(&filter, part).is_some(),
+                        };
+                    if matched {
+                        Some(idx)
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        }
 
-                ui.group(|ui| {
-                    ui.vertical(|ui| {
-                        ui.heading(Self::panel_title(is_system));
-                        ui.label(
-                            RichText::new("Use filter + multiselect (Ctrl+Click) to edit entries quickly.")
-                                .small()
-                                .color(Color32::from_gray(170)),
-                        );
-                        ui.add_space(8.0);
+        fn filter_regex(&self) -> Result<Regex, regex::Error> {
+            RegexBuilder::new(self.filter.trim())
+                .case_insensitive(true)
+                .build()
+        }
 
-                        ui.horizontal(|ui| {
-                            ui.label("Filter");
-                            ui.add(
-                                TextEdit::singleline(&mut store.filter)
-                                    .hint_text("Type to filter PATH entries")
-                                    .desired_width(f32::INFINITY),
-                            );
-                        });
+        fn filter_error(&self) -> Option<String> {
+            if self.filter_mode != FilterMode::Regex || self.filter.trim().is_empty() {
+                return None;
+            }
+            self.filter_regex().err().map(|err| err.to_string())
+        }
 
-                        ui.add_space(8.0);
+        fn raw_preview(&self) -> String {
+            join_path(&self.enabled_parts())
+        }
 
-                        let visible = store.visible_indices();
+        /// The entries that make up the registry value, i.e. all but the disabled ones.
+        fn enabled_parts(&self) -> Vec<String> {
+            self.parts
+                .iter()
+                .zip(self.disabled_flags())
+                .filter(|(_, disabled)| !*disabled)
+                .map(|(part, _)| part.clone())
+                .collect()
+        }
 
-                        egui::Frame::canvas(ui.style()).show(ui, |ui| {
-                            ui.set_height(300.0);
-                            ScrollArea::vertical()
-                                .id_source(format!("list_{is_system}"))
-                                .auto_shrink([false, false])
+        fn is_enabled(&self, idx: usize) -> bool {
+            !self.disabled.contains(&self.disabled_key(idx))
+        }
+
+        /// The `disabled` key of an entry: its text and how many identical entries precede it.
+        fn disabled_key(&self, idx: usize) -> (String, usize) {
+            let part = &self.parts[idx];
+            let nth = self.parts[..idx].iter().filter(|p| *p == part).count();
+            (part.clone(), nth)
+        }
+
+        /// One flag per entry in `parts`, true where the entry is disabled.
+        fn disabled_flags(&self) -> Vec<bool> {
+            let mut seen: HashMap<&str, usize> = HashMap::new();
+            self.parts
+                .iter()
+                .map(|part| {
+                    let nth = seen.entry(part.as_str()).or_insert(0);
+                    let disabled = self.disabled.contains(&(part.clone(), *nth));
+                    *nth += 1;
+                    disabled
+                })
+                .collect()
+        }
+
+        /// Rebuilds `disabled` from per-entry flags after `parts` was rewritten.
+        fn set_disabled_flags(&mut self, flags: &[bool]) {
+            let mut seen: HashMap<&str, usize> = HashMap::new();
+            let mut disabled = BTreeSet::new();
+            for (part, &flag) in self.parts.iter().zip(flags) {
+                let nth = seen.entry(part.as_str()).or_insert(0);
+                if flag {
+                    disabled.insert((part.clone(), *nth));
+                }
+                *nth += 1;
+            }
+            self.disabled = disabled;
+        }
+
+        /// Entries paired with their disabled flag, so edits can move flags along with text.
+        fn entries(&self) -> Vec<(String, bool)> {
+            self.parts.iter().cloned().zip(self.disabled_flags()).collect()
+        }
+
+        fn set_entries(&mut self, entries: Vec<(String, bool)>) {
+            let (parts, flags): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+            self.parts = parts;
+            self.set_disabled_flags(&flags);
+        }
+
+        /// Runs an edit of `parts` on entries paired with their disabled flags. Every rewrite
+        /// of the list goes through here so a disabled entry stays disabled wherever it ends up.
+        fn edit_entries<R>(&mut self, edit: impl FnOnce(&mut Vec<(String, bool)>) -> R) -> R {
+            let mut entries = self.entries();
+            let result = edit(&mut entries);
+            self.set_entries(entries);
+            result
+        }
+
+        /// Replaces `parts` with a list that keeps, drops or respells entries but never
+        /// reorders them. Each new entry takes the flag of the first unused old entry with the
+        /// same comparison key, so a group merged into one entry stays disabled only when all
+        /// of its copies were.
+        fn replace_parts(&mut self, parts: Vec<String>) {
+            let mut flags: HashMap<String, VecDeque<bool>> = HashMap::new();
+            for (part, disabled) in self.parts.iter().zip(self.disabled_flags()) {
+                flags.entry(normalize_for_compare(part)).or_default().push_back(disabled);
+            }
+            let mut kept = HashMap::new();
+            for part in &parts {
+                *kept.entry(normalize_for_compare(part)).or_insert(0usize) += 1;
+            }
+            let flags = parts
+                .iter()
+                .map(|part| {
+                    let key = normalize_for_compare(part);
+                    let (Some(queue), Some(left)) = (flags.get_mut(&key), kept.get_mut(&key))
+                    else {
+                        return false;
+                    };
+                    *left -= 1;
+                    if *left == 0 {
+                        // The last survivor of a group stands for every copy that was dropped.
+                        queue.drain(..).all(|disabled| disabled)
+                    } else {
+                        queue.pop_front().unwrap_or(false)
+                    }
+                })
+                .collect::<Vec<_>>();
+            self.parts = parts;
+            self.set_disabled_flags(&flags);
+        }
+
+        /// Inserts an enabled copy of an entry right after it.
+        fn duplicate_entry(&mut self, idx: usize) {
+            self.checkpoint();
+            self.edit_entries(|entries| {
+                let copy = entries[idx].0.clone();
+                entries.insert(idx + 1, (copy, false));
+            });
+        }
+
+        /// Flips an entry between enabled and disabled and returns whether it is now enabled.
+        fn toggle_enabled(&mut self, idx: usize) -> bool {
+            self.checkpoint();
+            let key = self.disabled_key(idx);
+            let now_enabled = self.disabled.remove(&key);
+            if !now_enabled {
+                self.disabled.insert(key);
+            }
+            now_enabled
+        }
+
+        /// The disabled entries with their positions, for `restore_disabled` in a later session.
+        fn remembered_disabled(&self) -> Vec<DisabledEntry> {
+            let entries = self.entries();
+            entries
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, disabled))| *disabled)
+                .map(|(position, (value, _))| DisabledEntry {
+                    position,
+                    enabled_copies: entries
+                        .iter()
+                        .filter(|(other, disabled)| !disabled && other == value)
+                        .count(),
+                    value: value.clone(),
+                })
+                .collect()
+        }
+
+        /// Puts remembered disabled entries back into a list freshly read from the registry.
+        /// A text with more copies in the registry than were enabled had one re-enabled
+        /// outside the editor, so that many of its disabled copies are dropped.
+        fn restore_disabled(&mut self, remembered: Vec<DisabledEntry>) {
+            let mut reenabled: HashMap<String, usize> = HashMap::new();
+            for entry in &remembered {
+                let present = self.parts.iter().filter(|part| **part == entry.value).count();
+                let extra = present.saturating_sub(entry.enabled_copies);
+                reenabled.insert(entry.value.clone(), extra);
+            }
+            self.edit_entries(|entries| {
+                for entry in remembered {
+                    match reenabled.get_mut(&entry.value) {
+                        Some(count) if *count > 0 => *count -= 1,
+                        _ => {
+                            let position = entry.position.min(entries.len());
+                            entries.insert(position, (entry.value, true));
+                        }
+                    }
+                }
+            });
+        }
+
+        /// Comparison keys of the selected entries and the cursor entry, for `reselect`.
+        fn selection_keys(&self) -> (HashSet<String>, Option<String>) {
+            let selected = self
+                .selected
+                .iter()
+                .filter_map(|&idx| self.parts.get(idx))
+                .map(|part| normalize_for_compare(part))
+                .collect();
+            let cursor = self
+                .cursor
+                .and_then(|idx| self.parts.get(idx))
+                .map(|part| normalize_for_compare(part));
+            (selected, cursor)
+        }
+
+        /// Restores a selection by entry after `parts` was reordered or deduplicated, so the
+        /// same folders stay selected wherever they moved.
+        fn reselect(&mut self, (selected, cursor): &(HashSet<String>, Option<String>)) {
+            let keys = self
+                .parts
+                .iter()
+                .map(|part| normalize_for_compare(part))
+                .collect::<Vec<_>>();
+            self.selected = (0..keys.len()).filter(|&idx| selected.contains(&keys[idx])).collect();
+            self.cursor = cursor
+                .as_ref()
+                .and_then(|cursor| keys.iter().position(|key| key == cursor));
+        }
+
+        /// Applies a selection change to the visible entries only; filtered-out entries keep
+        /// their current selection state.
+        fn select_visible(&mut self, mode: SelectMode) {
+            for idx in self.visible_indices() {
+                let select = match mode {
+                    SelectMode::All => true,
+                    SelectMode::Invert => !self.selected.contains(&idx),
+                    SelectMode::Clear => false,
+                };
+                if select {
+                    self.selected.insert(idx);
+                } else {
+                    self.selected.remove(&idx);
+                }
+            }
+        }
+
+        fn write_type(&self) -> RegType {
+            match self.type_override {
+                TypeOverride::Sz => return REG_SZ,
+                TypeOverride::ExpandSz => return REG_EXPAND_SZ,
+                TypeOverride::Auto => {}
+            }
+            let value = self.raw_preview();
+            if has_env_token(&value) {
+                REG_EXPAND_SZ
+            } else if self.reg_type != REG_SZ && self.reg_type != REG_EXPAND_SZ {
+                REG_SZ
+            } else {
+                self.reg_type.clone()
+            }
+        }
+    }
+
+    struct AddDialogState {
+        open: bool,
+        is_system: bool,
+        input: String,
+        edit_index: Option<usize>,
+    }
+
+    impl Default for AddDialogState {
+        fn default() -> Self {
+            Self {
+                open: false,
+                is_system: false,
+                input: String::new(),
+                edit_index: None,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum SortOrder {
+        Ascending,
+        Descending,
+        ByLength,
+        Natural,
+        Priority,
+    }
+
+    impl SortOrder {
+        const ALL: [SortOrder; 5] = [
+            SortOrder::Ascending,
+            SortOrder::Descending,
+            SortOrder::ByLength,
+            SortOrder::Natural,
+            SortOrder::Priority,
+        ];
+
+        fn label(self) -> &'static str {
+            match self {
+                SortOrder::Ascending => tr(Text::SortAscending),
+                SortOrder::Descending => tr(Text::SortDescending),
+                SortOrder::ByLength => tr(Text::SortByLength),
+                SortOrder::Natural => tr(Text::SortNatural),
+                SortOrder::Priority => tr(Text::SortByPriority),
+            }
+        }
+    }
+
+    /// Where an entry points after expansion, used to tint rows.
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum EntryCategory {
+        System,
+        ProgramFiles,
+        UserProfile,
+        Network,
+        Other,
+    }
+
+    impl EntryCategory {
+        const ALL: [EntryCategory; 5] = [
+            EntryCategory::System,
+            EntryCategory::ProgramFiles,
+            EntryCategory::UserProfile,
+            EntryCategory::Network,
+            EntryCategory::Other,
+        ];
+
+        fn label(self) -> &'static str {
+            match self {
+                EntryCategory::System => tr(Text::CategoryWindows),
+                EntryCategory::ProgramFiles => tr(Text::CategoryProgramFiles),
+                EntryCategory::UserProfile => tr(Text::CategoryProfile),
+                EntryCategory::Network => tr(Text::CategoryNetwork),
+                EntryCategory::Other => tr(Text::CategoryOther),
+            }
+        }
+
+        fn color(self) -> Option<Color32> {
+            match self {
+                EntryCategory::System => Some(Color32::from_rgb(120, 170, 230)),
+                EntryCategory::ProgramFiles => Some(Color32::from_rgb(180, 140, 220)),
+                EntryCategory::UserProfile => Some(Color32::from_rgb(110, 195, 175)),
+                EntryCategory::Network => Some(Color32::from_rgb(220, 120, 170)),
+                EntryCategory::Other => None,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum SelectMode {
+        All,
+        Invert,
+        Clear,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum EntryAction {
+        OpenInExplorer,
+        Copy,
+        CopyExpanded,
+        CopyPowerShellSession,
+        CopyPowerShellPersistent,
+        Edit,
+        Duplicate,
+        EditNote,
+        ListExecutables,
+        ToggleEnabled,
+        MoveToPosition,
+        Remove,
+        MoveToTop,
+        MoveToBottom,
+        MoveToOtherScope,
+    }
+
+    #[derive(Default)]
+    struct NoteDialogState {
+        open: bool,
+        entry: String,
+        text: String,
+    }
+
+    #[derive(Default)]
+    struct ExecutablesDialogState {
+        open: bool,
+        entry: String,
+        expanded: String,
+        filter: String,
+    }
+
+    #[derive(Default)]
+    struct ExpandedDialogState {
+        open: bool,
+        is_system: bool,
+        filter: String,
+    }
+
+    #[derive(Default)]
+    struct MoveDialogState {
+        open: bool,
+        is_system: bool,
+        index: usize,
+        input: String,
+    }
+
+    struct ResolveMatch {
+        is_system: bool,
+        entry: String,
+        file: PathBuf,
+    }
+
+    #[derive(Default)]
+    struct ResolveDialogState {
+        open: bool,
+        input: String,
+        results: Vec<ResolveMatch>,
+        searched: bool,
+    }
+
+    /// A command name (file stem) provided by more than one PATH folder. The first provider
+    /// is the one Windows runs; the rest are shadowed.
+    struct Shadowed {
+        name: String,
+        providers: Vec<ResolveMatch>,
+    }
+
+    #[derive(Default)]
+    struct ShadowDialogState {
+        open: bool,
+        /// The (scope, entry) list the cached results were computed from.
+        scanned: Vec<(bool, String)>,
+        results: Vec<Shadowed>,
+        worker: Option<JoinHandle<Vec<Shadowed>>>,
+        /// Set to stop the running worker after the folder it is reading.
+        cancel: Arc<AtomicBool>,
+        cancelled: bool,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum ImportMode {
+        Replace,
+        Append,
+        AppendDedupe,
+    }
+
+    struct ImportedSection {
+        key: String,
+        name: String,
+        parts: Vec<String>,
+    }
+
+    impl ImportedSection {
+        fn label(&self) -> String {
+            format!("{} ({})", self.key, self.name)
+        }
+    }
+
+    #[derive(Default)]
+    struct CompareDialogState {
+        open: bool,
+        sources: Vec<ImportedSection>,
+        left: usize,
+        right: usize,
+        target_system: bool,
+    }
+
+    /// Entries matched across two sources with `normalize_for_compare`.
+    struct Comparison {
+        only_left: Vec<String>,
+        only_right: Vec<String>,
+        /// Common entries in left order, with their positions among the common entries of
+        /// each side.
+        common: Vec<(String, usize, usize)>,
+    }
+
+    struct ImportDialogState {
+        open: bool,
+        source: String,
+        sections: Vec<ImportedSection>,
+        section: usize,
+        is_system: bool,
+        mode: ImportMode,
+    }
+
+    impl Default for ImportDialogState {
+        fn default() -> Self {
+            Self {
+                open: false,
+                source: String::new(),
+                sections: Vec::new(),
+                section: 0,
+                is_system: false,
+                mode: ImportMode::Replace,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Theme {
+        Dark,
+        Light,
+        System,
+    }
+
+    impl Theme {
+        const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::System];
+
+        fn label(self) -> &'static str {
+            match self {
+                Theme::Dark => tr(Text::ThemeDark),
+                Theme::Light => tr(Text::ThemeLight),
+                Theme::System => tr(Text::ThemeSystem),
+            }
+        }
+
+        fn key(self) -> &'static str {
+            match self {
+                Theme::Dark => "dark",
+                Theme::Light => "light",
+                Theme::System => "system",
+            }
+        }
+
+        fn from_key(key: &str) -> Option<Self> {
+            Self::ALL.into_iter().find(|theme| theme.key() == key)
+        }
+
+        fn is_light(self, system_light: bool) -> bool {
+            match self {
+                Theme::Dark => false,
+                Theme::Light => true,
+                Theme::System => system_light,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Language {
+        System,
+        English,
+        German,
+    }
+
+    impl Language {
+        const ALL: [Language; 3] = [Language::System, Language::English, Language::German];
+
+        fn label(self) -> &'static str {
+            match self {
+                Language::System => tr(Text::LanguageSystem),
+                Language::English => "English",
+                Language::German => "Deutsch",
+            }
+        }
+
+        fn key(self) -> &'static str {
+            match self {
+                Language::System => "system",
+                Language::English => "en",
+                Language::German => "de",
+            }
+        }
+
+        fn from_key(key: &str) -> Option<Self> {
+            Self::ALL.into_iter().find(|language| language.key() == key)
+        }
+
+        /// The table to use, following the Windows UI language for `System`.
+        fn resolve(self) -> Language {
+            const LANG_GERMAN: u16 = 0x07;
+            let primary = || unsafe { GetUserDefaultUILanguage() } & 0x3ff;
+            match self {
+                Language::System if primary() == LANG_GERMAN => Language::German,
+                Language::System => Language::English,
+                other => other,
+            }
+        }
+    }
+
+    /// Translated UI strings, keyed by where they appear. `{0}`, `{1}`... are placeholders
+    /// filled in by `trf`. Log lines and command-line output stay in English.
+    #[derive(Clone, Copy)]
+    enum Text {
+        // Toolbar and panel header
+        Lists,
+        AllVariables,
+        EffectivePath,
+        Reload,
+        Resolve,
+        Shadowing,
+        Compare,
+        Import,
+        Settings,
+        Filter,
+        Add,
+        Browse,
+        Remove,
+        Open,
+        Replace,
+        Cleanup,
+        Rescan,
+        MoveUp,
+        MoveDown,
+        Dedupe,
+        ReviewDuplicates,
+        RemoveRelative,
+        RemoveInvalid,
+        Sort,
+        NormalizeSlashes,
+        NormalizeCasing,
+        TrimSlashes,
+        TrimWhitespace,
+        Tokenize,
+        ExpandAll,
+        Undo,
+        Expanded,
+        ShowChanges,
+        SavePath,
+
+        // Shared
+        Cancel,
+        Close,
+        Apply,
+        ScopeUser,
+        ScopeSystem,
+        Save,
+        Copied,
+        ScopePath,
+        Dismiss,
+
+        // Other users' hives
+        HiveNotFound,
+        HiveAlreadyOpen,
+        HivePrivileges,
+        HiveInUse,
+        HiveLoadFailed,
+
+        // Startup and panel titles
+        StatusWow64,
+        StatusReady,
+        FromLastSession,
+        FromBackup,
+        PanelFile,
+        PanelSystem,
+        PanelOtherUser,
+        PanelUser,
+        AddedNothing,
+        AddedSomeFolders,
+        AddedFolders,
+        IgnoredDrops,
+
+        // Shadowing report and Effective PATH view
+        ShadowTitle,
+        ShadowScanning,
+        ShadowCancelled,
+        ShadowCount,
+        ShadowRunning,
+        ShadowOrder,
+        ShadowProvider,
+        ShadowShadowed,
+        HiddenFrom,
+        EntryCount,
+        IncludesUnsaved,
+        CheckingShadowed,
+        RescanHint,
+        ShadowStale,
+        EffectiveHint,
+        HiddenCount,
+        HiddenList,
+        ShadowsCount,
+        ShadowsList,
+
+        // Resolve and removal
+        ResolvesTo,
+        NotOnPath,
+        RemovedEntries,
+        RemovedEntry,
+        DeleteNotesTitle,
+        DeleteNotesBody,
+
+        // Entry notes and executables
+        NoteTitle,
+        NoteHint,
+        NoteEmptyHint,
+        NoteRemoved,
+        NoteSaved,
+        ExecutablesTitle,
+        FolderTimedOut,
+        ScanningFolder,
+        FolderUnreadable,
+        NoExecutables,
+        ExecutableCount,
+        ClickToCopyPath,
+        Refresh,
+        RefreshHint,
+
+        // Entry actions
+        EnabledEntry,
+        DisabledEntry,
+        Reordered,
+        FolderNotFoundTitle,
+        FolderNotFoundBody,
+        Opened,
+        OpenFailed,
+        SelectToOpen,
+        OpenedFolders,
+        SomeNotOpenedTitle,
+        SomeNotOpenedBody,
+
+        // Editing commands
+        SelectToMove,
+        MovedEntries,
+        NoRelative,
+        RemovedRelative,
+        ChooseBaseFolder,
+        ResolvedEntry,
+        DedupeRemoved,
+        Sorted,
+        NormalizedSlashes,
+        UppercasedDrives,
+        TrimmedSlashes,
+        TrimmedWhitespace,
+        NothingToTokenize,
+        TokenizeTitle,
+        TokenizeBody,
+        Tokenized,
+        ExpandTitle,
+        ExpandBody,
+        ExpandedEntries,
+        Undid,
+
+        // Saving
+        ConfirmSaveTitle,
+        TypeChange,
+        ChangesTitle,
+        ChangesHint,
+        RegistryReadFailed,
+        SavedScope,
+        SavedScopeToast,
+        SaveFailed,
+        BackupFailed,
+        SavedBoth,
+        SavedUserOnly,
+        SavedAllToast,
+        SavedTitle,
+        ClickToDismiss,
+        SummarySkipped,
+        SummaryEntries,
+        SessionHistory,
+        MessageCount,
+        Clear,
+        DryRunStatus,
+        DryRunTitle,
+        HelperBusy,
+        HelperWaiting,
+        HelperPanicked,
+        BroadcastFailedAgain,
+        BroadcastSent,
+        DiscardTitle,
+        ReloadDiscardBody,
+        Reloaded,
+        NowAdmin,
+        NoLongerAdmin,
+        SwitchDiscardBody,
+        Editing,
+        AppliedToSession,
+        ExitTitle,
+        ExitBody,
+
+        // Banners
+        ExternalChange,
+        ReloadDiscards,
+        ClipboardOffer,
+        FoundOnClipboard,
+        SessionChangedOne,
+        SessionChangedBoth,
+        ViewChanges,
+        SessionChangesTitle,
+        SessionChangesSource,
+
+        // Settings
+        SettingsSaved,
+        SettingsTitle,
+        ThemeLabel,
+        ThemeDark,
+        ThemeLight,
+        ThemeSystem,
+        LanguageLabel,
+        LanguageSystem,
+        AddEntriesTo,
+        AddToFront,
+        BrowseTokenize,
+        DedupeTokens,
+        EntriesLabel,
+        CategoryColors,
+        StartupLabel,
+        ClipboardCheck,
+        ClipboardCheckHint,
+        CriticalLabel,
+        CriticalHint,
+        CriticalHover,
+        ResetDefaults,
+        SavingLabel,
+        ConfirmDiff,
+        DryRunDefault,
+        BackupOnSave,
+        SaveToasts,
+        SaveToastsHint,
+        StartupBackupLabel,
+        StartupBackupEvery,
+        Hours,
+        StartupBackupHint,
+        SessionChangesOption,
+        BackupKeepLabel,
+        BackupKeepDisabled,
+        LoggingLabel,
+        LoggingHint,
+        LoggingDir,
+        LoggingOption,
+        BackupKeepInvalid,
+        BackupHoursInvalid,
+
+        // Export and import
+        RegistryFile,
+        ExportedPath,
+        ExportFailed,
+        PowerShellScript,
+        BatchFile,
+        BatchQuotes,
+        ExportedScope,
+        QuotedSpaces,
+        ExportedState,
+        ImportFailed,
+        UnknownType,
+        ImportedState,
+        RegistryOrText,
+        AllFiles,
+        ImportedInto,
+        StartupBackupFailed,
+        TextFileSource,
+        OneEntryPerLine,
+        NotRegExport,
+        LineError,
+        NoValueFound,
+        Or,
+        UnterminatedEscape,
+        UnterminatedString,
+        InvalidHexByte,
+        OddHexLength,
+        UnsupportedType,
+
+        // Compare
+        RegistrySource,
+        LoadFailed,
+        CompareTitle,
+        LoadFile,
+        OnlyInA,
+        OnlyInB,
+        Common,
+        AddToLive,
+        MovedPosition,
+        PullTarget,
+        AlreadyIn,
+        AddedTo,
+
+        // Panel
+        MoveToUser,
+        MoveToSystem,
+        EntriesBytes,
+        SizeHint,
+        LastChanged,
+        LastChangedHint,
+        LongSystemPath,
+        LongSystemPathHint,
+        MoveOverflow,
+        MoveOverflowHint,
+        PanelHint,
+        Variable,
+        FilterHint,
+        ClearFilter,
+        InvalidRegex,
+        SelectedCount,
+        SelectAll,
+        Invert,
+        Undefined,
+        UndefinedHint,
+        MakeAbsolute,
+        MakeAbsoluteHint,
+        AlsoIn,
+        RemoveFromUser,
+        RemoveFromUserHint,
+        LongPathsOn,
+        LongPathsOff,
+        CharCount,
+        TooLongHint,
+        SameFolder,
+        SameFolderHint,
+        Create,
+        CreateHint,
+        Fix,
+        FixHint,
+        OpenInExplorer,
+        Copy,
+        CopyExpanded,
+        CopyPsSession,
+        CopyPsPersistent,
+        EditEntry,
+        Duplicate,
+        Note,
+        ListExecutables,
+        MoveToTop,
+        MoveToBottom,
+        Disable,
+        Enable,
+        DisableHint,
+        MoveToPosition,
+        MoveToPositionHint,
+        NeedsSelection,
+        OpenHint,
+        RescanCacheHint,
+        MoveScopeHint,
+        ReviewDuplicatesHint,
+        RemoveRelativeHint,
+        RemoveInvalidHint,
+        NormalizeSlashesHint,
+        NormalizeCasingHint,
+        TrimSlashesHint,
+        TrimWhitespaceHint,
+        NoWhitespace,
+        TokenizeHint,
+        ExpandAllHint,
+        NothingToUndo,
+        ShowChangesHint,
+        RawPreview,
+        AutoType,
+        AutoTypeHint,
+        Type,
+        TokensWontExpand,
+        SaveElevatedHint,
+        AsksForAdmin,
+        ScanCacheCleared,
+        CreatedFolder,
+        CreateFailed,
+        FixedEntry,
+        SelectedEntries,
+
+        // Add, move and expanded dialogs
+        AddSystemTitle,
+        AddUserTitle,
+        EditSystemTitle,
+        EditUserTitle,
+        EntryLabel,
+        EntriesDetected,
+        OneDuplicate,
+        ManyDuplicates,
+        OneMissing,
+        ManyMissing,
+        OneUnreachable,
+        ManyUnreachable,
+        BrowseDots,
+        AddToFrontCheck,
+        AddToFrontHint,
+        UpdatedEntries,
+        AddedEntries,
+        AlreadyPresentTitle,
+        AlreadyPresentBody,
+        AlreadyPresent,
+        MoveToPositionTitle,
+        PositionRange,
+        EnterNumber,
+        WillMoveTo,
+        Move,
+        EnterPosition,
+        MovedToPosition,
+        ExpandedSystemTitle,
+        ExpandedUserTitle,
+        ExpandedFilterHint,
+        CopyListingHint,
+        ExpandedListing,
+        CopyResolved,
+        CopyResolvedHint,
+        ResolvedPaths,
+        ShownOf,
+        FolderExists,
+        FolderMissing,
+        FolderUnavailable,
+        CopyExpandedHint,
+        ExpandedPath,
+        CopiedToClipboard,
+
+        // Replace and duplicates
+        ReplaceSystemTitle,
+        ReplaceUserTitle,
+        Find,
+        ReplaceWith,
+        IgnoreCase,
+        Regex,
+        SelectedOnly,
+        NoneSelected,
+        WillChange,
+        NoMatches,
+        ReplacedText,
+        NoDuplicates,
+        DuplicatesTitle,
+        DuplicatesHint,
+        DuplicatesChanged,
+        MergedDuplicates,
+
+        // Cross-scope duplicates, remove invalid, overflow and cleanup
+        NoSharedVariable,
+        NoCrossDuplicates,
+        MergeTitle,
+        MergeHint,
+        AllInSystem,
+        AllInUser,
+        KeepIn,
+        MergeChanged,
+        RemovedFromBoth,
+        NoInvalid,
+        RemoveInvalidTitle,
+        KeepUnc,
+        KeepUncHint,
+        UndefinedKept,
+        WillBeRemoved,
+        RemoveInvalidChanged,
+        RemovedInvalid,
+        OverflowCritical,
+        OverflowTitle,
+        OverflowBody,
+        AlreadyInUser,
+        OverflowChanged,
+        CleanupSystemTitle,
+        CleanupUserTitle,
+        CleanupTrimOption,
+        CleanupNormalizeOption,
+        CleanupDedupeOption,
+        CleanupMissingOption,
+        EntriesBeforeAfter,
+        StepTrim,
+        StepNormalize,
+        StepMissing,
+        CleanedUp,
+
+        // Resolve and import dialogs
+        ResolveTitle,
+        ExecutableName,
+        ResolveButton,
+        NoMatch,
+        Shadowed,
+        FromEntry,
+        ImportTitle,
+        SourceKey,
+        Target,
+        Mode,
+        ReplaceButton,
+        Append,
+        AppendDedupe,
+        ImportButton,
+
+        // Environment variables
+        AdminRequired,
+        SystemEnvAdmin,
+        RenamedVariable,
+        SavedVariable,
+        AddedVariable,
+        DeleteVariableTitle,
+        DeleteVariableBody,
+        DeletedVariable,
+        DeleteFailed,
+        EnvFilterHint,
+        Scope,
+        Name,
+        Value,
+        EditButton,
+        Delete,
+        ReadOnly,
+        ReloadedVariables,
+        EditVariableTitle,
+        AddVariableTitle,
+        FixedScope,
+        Expandable,
+        HiveAdmin,
+        ChooseProfile,
+        ProfileFailed,
+        ClosedHive,
+        DiscardUserBody,
+        RestartFailed,
+
+        // Labels
+        FilterSubstring,
+        FilterFuzzy,
+        TypeAuto,
+        SortAscending,
+        SortDescending,
+        SortByLength,
+        SortNatural,
+        SortByPriority,
+        CategoryWindows,
+        CategoryProgramFiles,
+        CategoryProfile,
+        CategoryNetwork,
+        CategoryOther,
+
+        // Header and status bar
+        Tagline,
+        RestartAdmin,
+        Recheck,
+        RecheckHint,
+        PrivilegesAdmin,
+        PrivilegesStandard,
+        ShadowingHint,
+        MergeButton,
+        MergeButtonHint,
+        CloseOtherUser,
+        OpenOtherUser,
+        OpenOtherUserHint,
+        ExportJson,
+        ImportJson,
+        ExportReg,
+        Both,
+        ExportScript,
+        ScriptTarget,
+        SearchBoth,
+        SearchBothHint,
+        SearchCounts,
+        ClearSearch,
+        SaveAll,
+        ApplyToSession,
+        ApplyToSessionHint,
+        DryRun,
+        DryRunHint,
+        History,
+        HistoryHint,
+        RetryBroadcast,
+        BroadcastFailed,
+        BroadcastFailedHint,
+        UndefinedCount,
+        Wow64View,
+        Wow64Hint,
+        NativeView,
+
+        // Entry checks and tooltips
+        ProblemSemicolon,
+        ProblemQuotes,
+        ProblemWhitespace,
+        ProblemUnc,
+        ProblemExpansion,
+        NotExpanded,
+        RelativeEntry,
+        ScanTimedOut,
+        ScanningFolderDots,
+        FolderExecutables,
+        AndMore,
+        NoEntryChanges,
+        MovedLine,
+        CriticalTitle,
+        CriticalBody,
+        NoSystem32Title,
+        NoSystem32Body,
+        SaveCancelled,
+        ConfirmTitle,
+        ConfirmBody,
+        HelperNotStarted,
+        HelperTimedOut,
+        HelperExitCode,
+        HelperNotAdmin,
+        RequestChanged,
+        InvalidVariableName,
+        ValueTooLong,
+        EmptySystemPath,
+    }
+
+    impl Text {
+        /// English and German text.
+        fn pair(self) -> (&'static str, &'static str) {
+            match self {
+                // Toolbar and panel header
+                Text::Lists => ("Lists", "Listen"),
+                Text::AllVariables => ("All Variables", "Alle Variablen"),
+                Text::EffectivePath => ("Effective PATH", "Wirksamer PATH"),
+                Text::Reload => ("Reload", "Neu laden"),
+                Text::Resolve => ("Resolve...", "Auflösen..."),
+                Text::Shadowing => ("Shadowing...", "Überdeckungen..."),
+                Text::Compare => ("Compare...", "Vergleichen..."),
+                Text::Import => ("Import...", "Importieren..."),
+                Text::Settings => ("Settings...", "Einstellungen..."),
+                Text::Filter => ("Filter", "Filter"),
+                Text::Add => ("Add", "Hinzufügen"),
+                Text::Browse => ("Browse", "Durchsuchen"),
+                Text::Remove => ("Remove", "Entfernen"),
+                Text::Open => ("Open", "Öffnen"),
+                Text::Replace => ("Replace...", "Ersetzen..."),
+                Text::Cleanup => ("Cleanup...", "Bereinigen..."),
+                Text::Rescan => ("Rescan", "Neu scannen"),
+                Text::MoveUp => ("Move Up", "Nach oben"),
+                Text::MoveDown => ("Move Down", "Nach unten"),
+                Text::Dedupe => ("Dedupe", "Duplikate entfernen"),
+                Text::ReviewDuplicates => ("Review Duplicates...", "Duplikate prüfen..."),
+                Text::RemoveRelative => ("Remove Relative", "Relative entfernen"),
+                Text::RemoveInvalid => ("Remove Invalid...", "Ungültige entfernen..."),
+                Text::Sort => ("Sort", "Sortieren"),
+                Text::NormalizeSlashes => ("Normalize Slashes", "Schrägstriche vereinheitlichen"),
+                Text::NormalizeCasing => ("Normalize Casing", "Schreibweise vereinheitlichen"),
+                Text::TrimSlashes => ("Trim Slashes", "Endschrägstriche entfernen"),
+                Text::TrimWhitespace => ("Trim Whitespace", "Leerzeichen an den Rändern entfernen"),
+                Text::Tokenize => ("Tokenize", "Tokenisieren"),
+                Text::ExpandAll => ("Expand All", "Alle erweitern"),
+                Text::Undo => ("Undo", "Rückgängig"),
+                Text::Expanded => ("Expanded", "Erweitert"),
+                Text::ShowChanges => ("Show Changes", "Änderungen anzeigen"),
+                Text::SavePath => ("Save this PATH", "Diesen PATH speichern"),
+
+                // Shared
+                Text::Cancel => ("Cancel", "Abbrechen"),
+                Text::Close => ("Close", "Schließen"),
+                Text::Apply => ("Apply", "Übernehmen"),
+                Text::ScopeUser => ("User", "Benutzer"),
+                Text::ScopeSystem => ("System", "System"),
+                Text::Save => ("Save", "Speichern"),
+                Text::Copied => ("Copied {0}", "{0} kopiert"),
+                Text::ScopePath => ("{0} PATH", "{0}-PATH"),
+                Text::Dismiss => ("Dismiss", "Ausblenden"),
+
+                // Other users' hives
+                Text::HiveNotFound => ("{0} was not found", "{0} wurde nicht gefunden"),
+                Text::HiveAlreadyOpen => (
+                    "Another profile is already open",
+                    "Es ist bereits ein anderes Profil geöffnet",
+                ),
+                Text::HivePrivileges => (
+                    "Could not enable the backup/restore privileges: {0}",
+                    "Die Sicherungs- und Wiederherstellungsrechte konnten nicht aktiviert werden: \
+                     {0}",
+                ),
+                Text::HiveInUse => (
+                    "{0} is in use. The user is probably signed in; edit their PATH from their own \
+                     session instead.",
+                    "{0} wird verwendet. Das Konto ist vermutlich angemeldet; bearbeiten Sie den \
+                     PATH stattdessen in dessen eigener Sitzung.",
+                ),
+                Text::HiveLoadFailed => (
+                    "Failed to load {0}: {1}",
+                    "{0} konnte nicht geladen werden: {1}",
+                ),
+
+                // Startup and panel titles
+                Text::StatusWow64 => (
+                    "Warning: 32-bit build running on 64-bit Windows; editing the 64-bit registry \
+                     view",
+                    "Warnung: 32-Bit-Version unter 64-Bit-Windows; bearbeitet wird die \
+                     64-Bit-Registrierungsansicht",
+                ),
+                Text::StatusReady => ("Ready", "Bereit"),
+                Text::FromLastSession => ("from the last session", "aus der letzten Sitzung"),
+                Text::FromBackup => ("backup {0}", "Sicherung {0}"),
+                Text::PanelFile => ("{0} file ({1})", "{0}-Datei ({1})"),
+                Text::PanelSystem => ("System {0} (HKLM)", "System-{0} (HKLM)"),
+                Text::PanelOtherUser => ("{0}'s {1} (NTUSER.DAT)", "{1} von {0} (NTUSER.DAT)"),
+                Text::PanelUser => ("User {0} (HKCU)", "Benutzer-{0} (HKCU)"),
+                Text::AddedNothing => (
+                    "Already present in {0}; nothing added",
+                    "Bereits in {0} vorhanden; nichts hinzugefügt",
+                ),
+                Text::AddedSomeFolders => (
+                    "Added {0} folder(s) to {1} ({2} already present)",
+                    "{0} Ordner zu {1} hinzugefügt ({2} bereits vorhanden)",
+                ),
+                Text::AddedFolders => (
+                    "Added {0} folder(s) to {1}",
+                    "{0} Ordner zu {1} hinzugefügt",
+                ),
+                Text::IgnoredDrops => (
+                    "Ignored {0} dropped non-folder item(s)",
+                    "{0} abgelegte Elemente ignoriert, die keine Ordner sind",
+                ),
+
+                // Shadowing report and Effective PATH view
+                Text::ShadowTitle => ("Shadowing Report", "Überdeckungsbericht"),
+                Text::ShadowScanning => (
+                    "Scanning PATH folders...",
+                    "PATH-Ordner werden durchsucht...",
+                ),
+                Text::ShadowCancelled => (
+                    "Scan cancelled; results are incomplete",
+                    "Suche abgebrochen; die Ergebnisse sind unvollständig",
+                ),
+                Text::ShadowCount => (
+                    "{0} command(s) are provided by more than one folder",
+                    "{0} Befehl(e) werden von mehr als einem Ordner bereitgestellt",
+                ),
+                Text::ShadowRunning => ("A scan is already running", "Es läuft bereits eine Suche"),
+                Text::ShadowOrder => (
+                    "System PATH is searched before User PATH; PATHEXT decides which files count \
+                     as commands.",
+                    "Der System-PATH wird vor dem Benutzer-PATH durchsucht; PATHEXT legt fest, \
+                     welche Dateien als Befehle gelten.",
+                ),
+                Text::ShadowProvider => ("    {0}  ({1} entry {2})", "    {0}  ({1}-Eintrag {2})"),
+                Text::ShadowShadowed => ("{0}  (shadowed)", "{0}  (überdeckt)"),
+                Text::HiddenFrom => ("{0} (from {1})", "{0} (aus {1})"),
+                Text::EntryCount => ("{0} entries", "{0} Einträge"),
+                Text::IncludesUnsaved => (
+                    "includes unsaved edits",
+                    "enthält ungespeicherte Änderungen",
+                ),
+                Text::CheckingShadowed => (
+                    "Checking for shadowed commands...",
+                    "Überdeckte Befehle werden gesucht...",
+                ),
+                Text::RescanHint => (
+                    "Look for commands provided by more than one folder again",
+                    "Erneut nach Befehlen suchen, die mehrere Ordner bereitstellen",
+                ),
+                Text::ShadowStale => (
+                    "Shadowing info is out of date",
+                    "Die Überdeckungsangaben sind veraltet",
+                ),
+                Text::EffectiveHint => (
+                    "What a newly started process sees: System PATH entries, then User PATH \
+                     entries. Disabled entries are left out; hover an entry for its expansion.",
+                    "Was ein neu gestarteter Prozess sieht: erst die Einträge des System-PATH, \
+                     dann die des Benutzer-PATH. Deaktivierte Einträge fehlen; zeigen Sie auf \
+                     einen Eintrag, um ihn erweitert zu sehen.",
+                ),
+                Text::HiddenCount => ("{0} hidden", "{0} verdeckt"),
+                Text::HiddenList => (
+                    "Provided by an earlier entry:\n{0}",
+                    "Von einem früheren Eintrag bereitgestellt:\n{0}",
+                ),
+                Text::ShadowsCount => ("shadows {0}", "überdeckt {0}"),
+                Text::ShadowsList => (
+                    "Wins over later entries for:\n{0}",
+                    "Hat Vorrang vor späteren Einträgen für:\n{0}",
+                ),
+
+                // Resolve and removal
+                Text::ResolvesTo => ("{0} resolves to {1}", "{0} wird zu {1} aufgelöst"),
+                Text::NotOnPath => (
+                    "{0} was not found on PATH",
+                    "{0} wurde im PATH nicht gefunden",
+                ),
+                Text::RemovedEntries => (
+                    "Removed {0} {1} entry/entries",
+                    "{0} Einträge aus {1} entfernt",
+                ),
+                Text::RemovedEntry => ("Removed {0} from {1}", "{0} aus {1} entfernt"),
+                Text::DeleteNotesTitle => ("Delete notes?", "Notizen löschen?"),
+                Text::DeleteNotesBody => (
+                    "The removed entries had notes:\n\n{0}\n\nDelete these notes too? Keeping them \
+                     brings them back if the entry is added again.",
+                    "Die entfernten Einträge hatten Notizen:\n\n{0}\n\nDiese Notizen ebenfalls \
+                     löschen? Behaltene Notizen erscheinen wieder, wenn der Eintrag erneut \
+                     hinzugefügt wird.",
+                ),
+
+                // Entry notes and executables
+                Text::NoteTitle => ("Entry Note", "Notiz zum Eintrag"),
+                Text::NoteHint => (
+                    "Why is this folder on PATH?",
+                    "Warum steht dieser Ordner im PATH?",
+                ),
+                Text::NoteEmptyHint => (
+                    "Leave empty to remove the note.",
+                    "Leer lassen, um die Notiz zu entfernen.",
+                ),
+                Text::NoteRemoved => ("Note removed", "Notiz entfernt"),
+                Text::NoteSaved => ("Note saved", "Notiz gespeichert"),
+                Text::ExecutablesTitle => ("Executables", "Ausführbare Dateien"),
+                Text::FolderTimedOut => (
+                    "Folder unavailable (scan timed out)",
+                    "Ordner nicht erreichbar (Zeitüberschreitung beim Durchsuchen)",
+                ),
+                Text::ScanningFolder => ("Scanning folder...", "Ordner wird durchsucht..."),
+                Text::FolderUnreadable => (
+                    "Folder does not exist or cannot be read",
+                    "Der Ordner existiert nicht oder kann nicht gelesen werden",
+                ),
+                Text::NoExecutables => (
+                    "No files matching %PATHEXT% in this folder.",
+                    "Keine Dateien in diesem Ordner passen zu %PATHEXT%.",
+                ),
+                Text::ExecutableCount => ("{0} executable(s)", "{0} ausführbare Datei(en)"),
+                Text::ClickToCopyPath => (
+                    "Click to copy the full path",
+                    "Klicken, um den vollständigen Pfad zu kopieren",
+                ),
+                Text::Refresh => ("Refresh", "Aktualisieren"),
+                Text::RefreshHint => (
+                    "List the folder again instead of using the cache",
+                    "Den Ordner neu auflisten, statt den Zwischenspeicher zu verwenden",
+                ),
+
+                // Entry actions
+                Text::EnabledEntry => ("Enabled {0} in {1}", "{0} in {1} aktiviert"),
+                Text::DisabledEntry => ("Disabled {0} in {1}", "{0} in {1} deaktiviert"),
+                Text::Reordered => ("Reordered {0}", "{0} neu geordnet"),
+                Text::FolderNotFoundTitle => ("Folder not found", "Ordner nicht gefunden"),
+                Text::FolderNotFoundBody => (
+                    "{0}\n\nThis folder does not exist.",
+                    "{0}\n\nDieser Ordner existiert nicht.",
+                ),
+                Text::Opened => ("Opened {0}", "{0} geöffnet"),
+                Text::OpenFailed => ("Open failed", "Öffnen fehlgeschlagen"),
+                Text::SelectToOpen => (
+                    "Select an entry to open it in Explorer",
+                    "Wählen Sie einen Eintrag aus, um ihn im Explorer zu öffnen",
+                ),
+                Text::OpenedFolders => (
+                    "Opened {0} folder(s) in Explorer",
+                    "{0} Ordner im Explorer geöffnet",
+                ),
+                Text::SomeNotOpenedTitle => (
+                    "Some folders could not be opened",
+                    "Einige Ordner konnten nicht geöffnet werden",
+                ),
+                Text::SomeNotOpenedBody => (
+                    "These folders do not exist or could not be opened:\n\n{0}",
+                    "Diese Ordner existieren nicht oder konnten nicht geöffnet werden:\n\n{0}",
+                ),
+
+                // Editing commands
+                Text::SelectToMove => (
+                    "Select entries to move them to the other scope",
+                    "Wählen Sie Einträge aus, um sie in den anderen Bereich zu verschieben",
+                ),
+                Text::MovedEntries => (
+                    "Moved {0} entry/entries to {1} ({2} already present)",
+                    "{0} Einträge nach {1} verschoben ({2} bereits vorhanden)",
+                ),
+                Text::NoRelative => (
+                    "No relative entries in {0}",
+                    "Keine relativen Einträge in {0}",
+                ),
+                Text::RemovedRelative => (
+                    "Removed {0} relative {1} entry/entries",
+                    "{0} relative Einträge aus {1} entfernt",
+                ),
+                Text::ChooseBaseFolder => (
+                    "Choose the base folder for this entry",
+                    "Basisordner für diesen Eintrag wählen",
+                ),
+                Text::ResolvedEntry => ("Resolved entry to {0}", "Eintrag aufgelöst zu {0}"),
+                Text::DedupeRemoved => (
+                    "Dedupe removed {0} entries from {1}",
+                    "{0} Duplikate aus {1} entfernt",
+                ),
+                Text::Sorted => ("Sorted {0} ({1})", "{0} sortiert ({1})"),
+                Text::NormalizedSlashes => (
+                    "Normalized slashes in {0} {1} entry/entries",
+                    "Schrägstriche in {0} Einträgen von {1} vereinheitlicht",
+                ),
+                Text::UppercasedDrives => (
+                    "Uppercased drive letters in {0} {1} entry/entries",
+                    "Laufwerksbuchstaben in {0} Einträgen von {1} großgeschrieben",
+                ),
+                Text::TrimmedSlashes => (
+                    "Trimmed trailing slashes from {0} {1} entry/entries",
+                    "Endschrägstriche aus {0} Einträgen von {1} entfernt",
+                ),
+                Text::TrimmedWhitespace => (
+                    "Trimmed whitespace from {0} {1} entry/entries",
+                    "Leerzeichen an den Rändern von {0} Einträgen in {1} entfernt",
+                ),
+                Text::NothingToTokenize => (
+                    "No {0} entries can be tokenized",
+                    "Keine Einträge in {0} lassen sich tokenisieren",
+                ),
+                Text::TokenizeTitle => ("Tokenize entries", "Einträge tokenisieren"),
+                Text::TokenizeBody => (
+                    "{0} entry/entries will be rewritten to use %VAR% tokens. Continue?",
+                    "{0} Einträge werden so umgeschrieben, dass sie %VAR%-Token verwenden. \
+                     Fortfahren?",
+                ),
+                Text::Tokenized => (
+                    "Tokenized {0} {1} entry/entries",
+                    "{0} Einträge in {1} tokenisiert",
+                ),
+                Text::ExpandTitle => ("Expand all tokens", "Alle Token erweitern"),
+                Text::ExpandBody => (
+                    "This replaces %VAR% tokens with literal paths for this machine and user, \
+                     which makes the value less portable. You can undo it before saving.",
+                    "Dadurch werden %VAR%-Token durch feste Pfade für diesen Computer und dieses \
+                     Konto ersetzt, was den Wert weniger übertragbar macht. Vor dem Speichern \
+                     können Sie das rückgängig machen.",
+                ),
+                Text::ExpandedEntries => (
+                    "Expanded {0} {1} entry/entries to literal paths",
+                    "{0} Einträge in {1} zu festen Pfaden erweitert",
+                ),
+                Text::Undid => (
+                    "Undid last change to {0}",
+                    "Letzte Änderung an {0} rückgängig gemacht",
+                ),
+
+                // Saving
+                Text::ConfirmSaveTitle => ("Confirm Save", "Speichern bestätigen"),
+                Text::TypeChange => ("Type: {0} -> {1}", "Typ: {0} -> {1}"),
+                Text::ChangesTitle => ("Changes - {0}", "Änderungen - {0}"),
+                Text::ChangesHint => (
+                    "Registry value on the left, this editor on the right",
+                    "Links der Wert in der Registrierung, rechts der in diesem Editor",
+                ),
+                Text::RegistryReadFailed => (
+                    "Could not read the registry: {0}",
+                    "Die Registrierung konnte nicht gelesen werden: {0}",
+                ),
+                Text::SavedScope => (
+                    "Saved {0} PATH and broadcasted change",
+                    "{0}-PATH gespeichert und Änderung gemeldet",
+                ),
+                Text::SavedScopeToast => (
+                    "{0} PATH saved. New terminals/apps will see the change.",
+                    "{0}-PATH gespeichert. Neue Terminals und Programme sehen die Änderung.",
+                ),
+                Text::SaveFailed => ("Save failed", "Speichern fehlgeschlagen"),
+                Text::BackupFailed => (
+                    "Backup failed, nothing was written: {0}",
+                    "Sicherung fehlgeschlagen, es wurde nichts geschrieben: {0}",
+                ),
+                Text::SavedBoth => (
+                    "Saved User + System PATH and broadcasted change",
+                    "Benutzer- und System-PATH gespeichert und Änderung gemeldet",
+                ),
+                Text::SavedUserOnly => (
+                    "Saved User PATH (System PATH skipped - not admin)",
+                    "Benutzer-PATH gespeichert (System-PATH übersprungen, keine \
+                     Administratorrechte)",
+                ),
+                Text::SavedAllToast => (
+                    "{0}\n{1}\n\nNew terminals/apps will see the change.",
+                    "{0}\n{1}\n\nNeue Terminals und Programme sehen die Änderung.",
+                ),
+                Text::SavedTitle => ("Saved", "Gespeichert"),
+                Text::ClickToDismiss => ("Click to dismiss", "Zum Schließen klicken"),
+                Text::SummarySkipped => (
+                    "{0}: skipped, not running as Administrator",
+                    "{0}: übersprungen, nicht als Administrator ausgeführt",
+                ),
+                Text::SummaryEntries => ("{0}: {1} entries as {2}", "{0}: {1} Einträge als {2}"),
+                Text::SessionHistory => ("Session history", "Sitzungsverlauf"),
+                Text::MessageCount => ("{0} message(s)", "{0} Meldung(en)"),
+                Text::Clear => ("Clear", "Leeren"),
+                Text::DryRunStatus => (
+                    "Dry run: previewed {0} registry write(s), nothing was changed",
+                    "Testlauf: {0} Schreibvorgänge in der Registrierung angezeigt, nichts wurde \
+                     geändert",
+                ),
+                Text::DryRunTitle => ("Dry run", "Testlauf"),
+                Text::HelperBusy => (
+                    "A System PATH save is still waiting for the elevated helper",
+                    "Eine Speicherung des System-PATH wartet noch auf das Hilfsprogramm mit \
+                     Administratorrechten",
+                ),
+                Text::HelperWaiting => (
+                    "Waiting for the elevated helper to save System PATH...",
+                    "Warten, bis das Hilfsprogramm mit Administratorrechten den System-PATH \
+                     speichert...",
+                ),
+                Text::HelperPanicked => (
+                    "the elevated helper thread panicked",
+                    "der Thread für das Hilfsprogramm ist abgestürzt",
+                ),
+                Text::BroadcastFailedAgain => (
+                    "Broadcast failed again; some windows are not responding",
+                    "Benachrichtigung erneut fehlgeschlagen; einige Fenster reagieren nicht",
+                ),
+                Text::BroadcastSent => (
+                    "Broadcast environment change to running applications",
+                    "Umgebungsänderung an laufende Programme gemeldet",
+                ),
+                Text::DiscardTitle => (
+                    "Discard unsaved changes?",
+                    "Ungespeicherte Änderungen verwerfen?",
+                ),
+                Text::ReloadDiscardBody => (
+                    "Reloading re-reads PATH from the registry and discards your unsaved edits.",
+                    "Beim Neuladen wird PATH erneut aus der Registrierung gelesen; Ihre \
+                     ungespeicherten Änderungen gehen verloren.",
+                ),
+                Text::Reloaded => (
+                    "Reloaded User and System PATH from the registry",
+                    "Benutzer- und System-PATH neu aus der Registrierung geladen",
+                ),
+                Text::NowAdmin => (
+                    "Privileges re-checked: now running as Administrator",
+                    "Rechte erneut geprüft: läuft jetzt als Administrator",
+                ),
+                Text::NoLongerAdmin => (
+                    "Privileges re-checked: no longer elevated",
+                    "Rechte erneut geprüft: keine Administratorrechte mehr",
+                ),
+                Text::SwitchDiscardBody => (
+                    "{0} has unsaved edits. Switch variables and discard them?",
+                    "{0} hat ungespeicherte Änderungen. Variable wechseln und die Änderungen \
+                     verwerfen?",
+                ),
+                Text::Editing => ("Editing {0}", "{0} wird bearbeitet"),
+                Text::AppliedToSession => (
+                    "Applied to this session only; the registry was not changed",
+                    "Nur auf diese Sitzung angewendet; die Registrierung wurde nicht geändert",
+                ),
+                Text::ExitTitle => ("Unsaved changes", "Ungespeicherte Änderungen"),
+                Text::ExitBody => (
+                    "PATH has unsaved changes.\n\nYes: save and exit\nNo: discard and \
+                     exit\nCancel: keep editing",
+                    "PATH hat ungespeicherte Änderungen.\n\nJa: speichern und beenden\nNein: \
+                     verwerfen und beenden\nAbbrechen: weiter bearbeiten",
+                ),
+
+                // Banners
+                Text::ExternalChange => (
+                    "PATH changed outside the editor. Reload?",
+                    "PATH wurde außerhalb des Editors geändert. Neu laden?",
+                ),
+                Text::ReloadDiscards => (
+                    "(reloading discards your unsaved edits)",
+                    "(Neuladen verwirft Ihre ungespeicherten Änderungen)",
+                ),
+                Text::ClipboardOffer => (
+                    "Add {0} to User PATH?",
+                    "{0} zum Benutzer-PATH hinzufügen?",
+                ),
+                Text::FoundOnClipboard => (
+                    "(found on the clipboard)",
+                    "(in der Zwischenablage gefunden)",
+                ),
+                Text::SessionChangedOne => (
+                    "{0} PATH changed since this editor last ran",
+                    "{0}-PATH wurde seit dem letzten Start dieses Editors geändert",
+                ),
+                Text::SessionChangedBoth => (
+                    "User and System PATH changed since this editor last ran",
+                    "Benutzer- und System-PATH wurden seit dem letzten Start dieses Editors \
+                     geändert",
+                ),
+                Text::ViewChanges => ("View changes", "Änderungen ansehen"),
+                Text::SessionChangesTitle => (
+                    "Changes Since Last Session",
+                    "Änderungen seit der letzten Sitzung",
+                ),
+                Text::SessionChangesSource => (
+                    "PATH {0} -> registry at startup",
+                    "PATH {0} -> Registrierung beim Start",
+                ),
+
+                // Settings
+                Text::SettingsSaved => ("Settings saved", "Einstellungen gespeichert"),
+                Text::SettingsTitle => ("Settings", "Einstellungen"),
+                Text::ThemeLabel => ("Theme", "Design"),
+                Text::ThemeDark => ("Dark", "Dunkel"),
+                Text::ThemeLight => ("Light", "Hell"),
+                Text::ThemeSystem => ("System", "System"),
+                Text::LanguageLabel => ("Language", "Sprache"),
+                Text::LanguageSystem => ("Windows display language", "Windows-Anzeigesprache"),
+                Text::AddEntriesTo => ("Add new entries to", "Neue Einträge hinzufügen zu"),
+                Text::AddToFront => (
+                    "At the front, ahead of existing entries",
+                    "Am Anfang, vor den vorhandenen Einträgen",
+                ),
+                Text::BrowseTokenize => (
+                    "Replace known folders with %VAR% tokens",
+                    "Bekannte Ordner durch %VAR%-Token ersetzen",
+                ),
+                Text::DedupeTokens => (
+                    "Keep the %VAR% form over an equal literal path",
+                    "Die %VAR%-Form einem gleichwertigen festen Pfad vorziehen",
+                ),
+                Text::EntriesLabel => ("Entries", "Einträge"),
+                Text::CategoryColors => (
+                    "Color by location (Windows, Program Files, profile, network)",
+                    "Nach Ort einfärben (Windows, Programme, Profil, Netzwerk)",
+                ),
+                Text::StartupLabel => ("Startup", "Start"),
+                Text::ClipboardCheck => (
+                    "Offer to add a folder path found on the clipboard",
+                    "Anbieten, einen Ordnerpfad aus der Zwischenablage hinzuzufügen",
+                ),
+                Text::ClipboardCheckHint => (
+                    "Reads the clipboard once when the editor starts",
+                    "Liest die Zwischenablage einmal beim Start des Editors",
+                ),
+                Text::CriticalLabel => ("Critical folders", "Wichtige Ordner"),
+                Text::CriticalHint => (
+                    "One entry per line, %VAR% tokens allowed",
+                    "Ein Eintrag pro Zeile, %VAR%-Token erlaubt",
+                ),
+                Text::CriticalHover => (
+                    "Removing these entries, or saving without them, asks first",
+                    "Vor dem Entfernen dieser Einträge oder dem Speichern ohne sie wird \
+                     nachgefragt",
+                ),
+                Text::ResetDefaults => ("Reset to defaults", "Auf Standard zurücksetzen"),
+                Text::SavingLabel => ("Saving", "Speichern"),
+                Text::ConfirmDiff => (
+                    "Show changes before saving",
+                    "Änderungen vor dem Speichern anzeigen",
+                ),
+                Text::DryRunDefault => ("Start in dry-run mode", "Im Testlaufmodus starten"),
+                Text::BackupOnSave => (
+                    "Back up the registry value before saving",
+                    "Den Registrierungswert vor dem Speichern sichern",
+                ),
+                Text::SaveToasts => (
+                    "Confirm saves with a brief notice instead of a dialog",
+                    "Speichern mit einem kurzen Hinweis statt eines Dialogs bestätigen",
+                ),
+                Text::SaveToastsHint => (
+                    "Errors are always shown in a dialog",
+                    "Fehler werden immer in einem Dialog angezeigt",
+                ),
+                Text::StartupBackupLabel => ("Startup backup", "Sicherung beim Start"),
+                Text::StartupBackupEvery => ("Snapshot PATH every", "PATH sichern alle"),
+                Text::Hours => ("hours", "Stunden"),
+                Text::StartupBackupHint => (
+                    "Taken when the editor starts, even without a save",
+                    "Wird beim Start des Editors erstellt, auch ohne Speichern",
+                ),
+                Text::SessionChangesOption => (
+                    "Tell me when PATH changed since the last session",
+                    "Melden, wenn sich PATH seit der letzten Sitzung geändert hat",
+                ),
+                Text::BackupKeepLabel => ("Backups to keep", "Aufbewahrte Sicherungen"),
+                Text::BackupKeepDisabled => (
+                    "Turn on a backup option to set this",
+                    "Aktivieren Sie eine Sicherungsoption, um dies einzustellen",
+                ),
+                Text::LoggingLabel => ("Logging", "Protokoll"),
+                Text::LoggingHint => (
+                    "Log edits and registry errors to a file",
+                    "Änderungen und Registrierungsfehler in eine Datei protokollieren",
+                ),
+                Text::LoggingDir => (
+                    "Log files are written to {0}",
+                    "Protokolldateien werden in {0} geschrieben",
+                ),
+                Text::LoggingOption => (
+                    "Log edits and registry errors",
+                    "Änderungen und Registrierungsfehler protokollieren",
+                ),
+                Text::BackupKeepInvalid => (
+                    "Backups to keep must be a number from 1 to {0}",
+                    "Die Anzahl aufbewahrter Sicherungen muss zwischen 1 und {0} liegen",
+                ),
+                Text::BackupHoursInvalid => (
+                    "Startup backup hours must be a number from 1 to {0}",
+                    "Die Stunden für die Sicherung beim Start müssen zwischen 1 und {0} liegen",
+                ),
+
+                // Export and import
+                Text::RegistryFile => ("Registry file", "Registrierungsdatei"),
+                Text::ExportedPath => ("Exported PATH to {0}", "PATH nach {0} exportiert"),
+                Text::ExportFailed => ("Export failed", "Export fehlgeschlagen"),
+                Text::PowerShellScript => ("PowerShell script", "PowerShell-Skript"),
+                Text::BatchFile => ("Batch file", "Batchdatei"),
+                Text::BatchQuotes => (
+                    "Some entries contain a double quote, which a batch file can't pass to reg \
+                     add. Export a PowerShell script instead.",
+                    "Einige Einträge enthalten ein doppeltes Anführungszeichen, das eine \
+                     Batchdatei nicht an reg add übergeben kann. Exportieren Sie stattdessen ein \
+                     PowerShell-Skript.",
+                ),
+                Text::ExportedScope => ("Exported {0} to {1}", "{0} nach {1} exportiert"),
+                Text::QuotedSpaces => (
+                    " ({0} entries with spaces are quoted)",
+                    " ({0} Einträge mit Leerzeichen stehen in Anführungszeichen)",
+                ),
+                Text::ExportedState => (
+                    "Exported editor state to {0}",
+                    "Editorzustand nach {0} exportiert",
+                ),
+                Text::ImportFailed => ("Import failed", "Import fehlgeschlagen"),
+                Text::UnknownType => (
+                    "Skipped a scope with unknown type {0}",
+                    "Bereich mit unbekanntem Typ {0} übersprungen",
+                ),
+                Text::ImportedState => (
+                    "Imported editor state from {0} (not saved)",
+                    "Editorzustand aus {0} importiert (nicht gespeichert)",
+                ),
+                Text::RegistryOrText => ("Registry or text file", "Registrierungs- oder Textdatei"),
+                Text::AllFiles => ("All files", "Alle Dateien"),
+                Text::ImportedInto => (
+                    "Imported into {0} ({1} -> {2} entries, not saved)",
+                    "In {0} importiert ({1} -> {2} Einträge, nicht gespeichert)",
+                ),
+                Text::StartupBackupFailed => (
+                    "Startup backup failed: {0}",
+                    "Sicherung beim Start fehlgeschlagen: {0}",
+                ),
+                Text::TextFileSource => ("Text file", "Textdatei"),
+                Text::OneEntryPerLine => ("one entry per line", "ein Eintrag pro Zeile"),
+                Text::NotRegExport => (
+                    "Not a registry export: missing the regedit header line.",
+                    "Kein Registrierungsexport: Die regedit-Kopfzeile fehlt.",
+                ),
+                Text::LineError => ("Line {0}: {1}", "Zeile {0}: {1}"),
+                Text::NoValueFound => (
+                    "No {0} value was found under an Environment key.",
+                    "Unter einem Environment-Schlüssel wurde kein Wert {0} gefunden.",
+                ),
+                Text::Or => (" or ", " oder "),
+                Text::UnterminatedEscape => (
+                    "unterminated escape sequence",
+                    "nicht abgeschlossene Escape-Sequenz",
+                ),
+                Text::UnterminatedString => (
+                    "unterminated string value",
+                    "nicht abgeschlossener Zeichenfolgenwert",
+                ),
+                Text::InvalidHexByte => ("invalid hex byte '{0}'", "ungültiges Hex-Byte '{0}'"),
+                Text::OddHexLength => (
+                    "REG_EXPAND_SZ data has an odd number of bytes",
+                    "REG_EXPAND_SZ-Daten haben eine ungerade Anzahl von Bytes",
+                ),
+                Text::UnsupportedType => (
+                    "unsupported value type in '{0}'",
+                    "nicht unterstützter Werttyp in '{0}'",
+                ),
+
+                // Compare
+                Text::RegistrySource => ("Registry", "Registrierung"),
+                Text::LoadFailed => ("Could not load file", "Datei konnte nicht geladen werden"),
+                Text::CompareTitle => ("Compare PATHs", "PATH-Werte vergleichen"),
+                Text::LoadFile => ("Load file...", "Datei laden..."),
+                Text::OnlyInA => ("Only in A ({0})", "Nur in A ({0})"),
+                Text::OnlyInB => ("Only in B ({0})", "Nur in B ({0})"),
+                Text::Common => ("Common ({0})", "Gemeinsam ({0})"),
+                Text::AddToLive => (
+                    "Add this entry to the live list",
+                    "Diesen Eintrag zur aktuellen Liste hinzufügen",
+                ),
+                Text::MovedPosition => (
+                    "{0}  (#{1} in A, #{2} in B)",
+                    "{0}  (Nr. {1} in A, Nr. {2} in B)",
+                ),
+                Text::PullTarget => ("Add pulls entries into", "Hinzufügen übernimmt Einträge in"),
+                Text::AlreadyIn => ("{0} is already in {1}", "{0} ist bereits in {1}"),
+                Text::AddedTo => ("Added {0} to {1}", "{0} zu {1} hinzugefügt"),
+
+                // Panel
+                Text::MoveToUser => ("Move to User", "Nach Benutzer verschieben"),
+                Text::MoveToSystem => ("Move to System", "Nach System verschieben"),
+                Text::EntriesBytes => ("— {0} entries, {1} bytes", "— {0} Einträge, {1} Bytes"),
+                Text::SizeHint => (
+                    "Size of the stored value: UTF-16 plus terminator",
+                    "Größe des gespeicherten Werts: UTF-16 plus Abschlusszeichen",
+                ),
+                Text::LastChanged => ("· last changed {0}", "· zuletzt geändert {0}"),
+                Text::LastChangedHint => (
+                    "Last write time of the Environment registry key",
+                    "Letzte Schreibzeit des Registrierungsschlüssels Environment",
+                ),
+                Text::LongSystemPath => (
+                    "System PATH is {0} characters, over {1}",
+                    "System-PATH hat {0} Zeichen, mehr als {1}",
+                ),
+                Text::LongSystemPathHint => (
+                    "Windows may stop expanding %VAR% tokens in a PATH this long",
+                    "Windows erweitert %VAR%-Token in einem so langen PATH möglicherweise nicht \
+                     mehr",
+                ),
+                Text::MoveOverflow => (
+                    "Move overflow to User PATH...",
+                    "Überhang in Benutzer-PATH verschieben...",
+                ),
+                Text::MoveOverflowHint => (
+                    "Move trailing entries to the User PATH",
+                    "Die letzten Einträge in den Benutzer-PATH verschieben",
+                ),
+                Text::PanelHint => (
+                    "Use filter + multiselect (Ctrl+Click) to edit entries quickly.",
+                    "Mit Filter und Mehrfachauswahl (Strg+Klick) lassen sich Einträge schnell \
+                     bearbeiten.",
+                ),
+                Text::Variable => ("Variable", "Variable"),
+                Text::FilterHint => (
+                    "Type to filter PATH entries",
+                    "Tippen, um PATH-Einträge zu filtern",
+                ),
+                Text::ClearFilter => ("Clear the filter", "Filter leeren"),
+                Text::InvalidRegex => ("Invalid regex: {0}", "Ungültiger regulärer Ausdruck: {0}"),
+                Text::SelectedCount => ("{0} selected", "{0} ausgewählt"),
+                Text::SelectAll => ("Select All", "Alle auswählen"),
+                Text::Invert => ("Invert", "Umkehren"),
+                Text::Undefined => ("(undefined {0})", "(nicht definiert: {0})"),
+                Text::UndefinedHint => (
+                    "{0} is not defined; this entry is left as literal text",
+                    "{0} ist nicht definiert; dieser Eintrag bleibt wörtlicher Text",
+                ),
+                Text::MakeAbsolute => ("Make Absolute...", "Absolut machen..."),
+                Text::MakeAbsoluteHint => (
+                    "Join this entry onto a base folder you choose",
+                    "Diesen Eintrag an einen gewählten Basisordner anhängen",
+                ),
+                Text::AlsoIn => ("(also in {0})", "(auch in {0})"),
+                Text::RemoveFromUser => ("Remove from User", "Aus Benutzer entfernen"),
+                Text::RemoveFromUserHint => (
+                    "Keep the System copy and drop this User entry",
+                    "Die System-Kopie behalten und diesen Benutzer-Eintrag entfernen",
+                ),
+                Text::LongPathsOn => (
+                    "Long path support is enabled, but older tools may still fail.",
+                    "Unterstützung für lange Pfade ist aktiviert, ältere Werkzeuge können aber \
+                     trotzdem scheitern.",
+                ),
+                Text::LongPathsOff => (
+                    "Long path support (LongPathsEnabled) is off, so many tools will fail.",
+                    "Unterstützung für lange Pfade (LongPathsEnabled) ist aus, daher scheitern \
+                     viele Werkzeuge.",
+                ),
+                Text::CharCount => ("({0} chars)", "({0} Zeichen)"),
+                Text::TooLongHint => (
+                    "Expands to more than the legacy MAX_PATH of {0} characters. {1}",
+                    "Wird länger als das alte MAX_PATH von {0} Zeichen. {1}",
+                ),
+                Text::SameFolder => ("(same folder as #{0})", "(gleicher Ordner wie Nr. {0})"),
+                Text::SameFolderHint => (
+                    "Both entries lead to one folder through a symlink or junction",
+                    "Beide Einträge führen über einen symbolischen Link oder eine Verbindung zum \
+                     selben Ordner",
+                ),
+                Text::Create => ("Create", "Erstellen"),
+                Text::CreateHint => ("Create this folder now", "Diesen Ordner jetzt erstellen"),
+                Text::Fix => ("Fix", "Reparieren"),
+                Text::FixHint => (
+                    "Strip quotes and outer whitespace, and split on stray ';'",
+                    "Anführungszeichen und äußere Leerzeichen entfernen und an verirrten ';' \
+                     aufteilen",
+                ),
+                Text::OpenInExplorer => ("Open in Explorer", "Im Explorer öffnen"),
+                Text::Copy => ("Copy", "Kopieren"),
+                Text::CopyExpanded => ("Copy expanded", "Erweitert kopieren"),
+                Text::CopyPsSession => (
+                    "Copy as PowerShell (session)",
+                    "Als PowerShell kopieren (Sitzung)",
+                ),
+                Text::CopyPsPersistent => (
+                    "Copy as PowerShell (persistent)",
+                    "Als PowerShell kopieren (dauerhaft)",
+                ),
+                Text::EditEntry => ("Edit...", "Bearbeiten..."),
+                Text::Duplicate => ("Duplicate", "Duplizieren"),
+                Text::Note => ("Note...", "Notiz..."),
+                Text::ListExecutables => ("List Executables...", "Programme auflisten..."),
+                Text::MoveToTop => ("Move to Top", "Ganz nach oben"),
+                Text::MoveToBottom => ("Move to Bottom", "Ganz nach unten"),
+                Text::Disable => ("Disable", "Deaktivieren"),
+                Text::Enable => ("Enable", "Aktivieren"),
+                Text::DisableHint => (
+                    "Disabled entries stay in the list but are not written to the registry",
+                    "Deaktivierte Einträge bleiben in der Liste, werden aber nicht in die \
+                     Registrierung geschrieben",
+                ),
+                Text::MoveToPosition => ("Move to Position...", "An Position verschieben..."),
+                Text::MoveToPositionHint => (
+                    "Clear the filter to move by absolute position",
+                    "Leeren Sie den Filter, um nach absoluter Position zu verschieben",
+                ),
+                Text::NeedsSelection => (
+                    "Select one or more entries first",
+                    "Wählen Sie zuerst einen oder mehrere Einträge aus",
+                ),
+                Text::OpenHint => (
+                    "Open the selected folders in Explorer",
+                    "Die ausgewählten Ordner im Explorer öffnen",
+                ),
+                Text::RescanCacheHint => (
+                    "Refresh the cached folder contents shown in entry tooltips",
+                    "Die zwischengespeicherten Ordnerinhalte in den Tooltips aktualisieren",
+                ),
+                Text::MoveScopeHint => (
+                    "Move the selected entries to the other scope",
+                    "Die ausgewählten Einträge in den anderen Bereich verschieben",
+                ),
+                Text::ReviewDuplicatesHint => (
+                    "Choose which spelling of each duplicate to keep",
+                    "Wählen, welche Schreibweise jedes Duplikats erhalten bleibt",
+                ),
+                Text::RemoveRelativeHint => (
+                    "Remove entries that are not absolute paths",
+                    "Einträge entfernen, die keine absoluten Pfade sind",
+                ),
+                Text::RemoveInvalidHint => (
+                    "Remove entries whose folder does not exist",
+                    "Einträge entfernen, deren Ordner nicht existiert",
+                ),
+                Text::NormalizeSlashesHint => (
+                    "Replace / with \\ in the selected entries, or all entries",
+                    "/ durch \\ ersetzen, in den ausgewählten oder allen Einträgen",
+                ),
+                Text::NormalizeCasingHint => (
+                    "Uppercase drive letters (c:\\ -> C:\\) in the selection, or all entries",
+                    "Laufwerksbuchstaben großschreiben (c:\\ -> C:\\), in der Auswahl oder allen \
+                     Einträgen",
+                ),
+                Text::TrimSlashesHint => (
+                    "Strip trailing \\ from the selected entries, or all entries",
+                    "Abschließende \\ entfernen, in den ausgewählten oder allen Einträgen",
+                ),
+                Text::TrimWhitespaceHint => (
+                    "Strip leading and trailing whitespace from all {0} affected entries",
+                    "Führende und abschließende Leerzeichen aus allen {0} betroffenen Einträgen \
+                     entfernen",
+                ),
+                Text::NoWhitespace => (
+                    "No entry starts or ends with whitespace",
+                    "Kein Eintrag beginnt oder endet mit Leerzeichen",
+                ),
+                Text::TokenizeHint => (
+                    "Rewrite literal paths to %SystemRoot%, %ProgramFiles%, ... tokens",
+                    "Wörtliche Pfade in %SystemRoot%-, %ProgramFiles%-... Token umschreiben",
+                ),
+                Text::ExpandAllHint => (
+                    "Replace %VAR% tokens with literal paths",
+                    "%VAR%-Token durch wörtliche Pfade ersetzen",
+                ),
+                Text::NothingToUndo => ("Nothing to undo yet", "Noch nichts rückgängig zu machen"),
+                Text::ShowChangesHint => (
+                    "Compare with the value currently in the registry",
+                    "Mit dem aktuellen Wert in der Registrierung vergleichen",
+                ),
+                Text::RawPreview => ("Raw PATH preview", "PATH-Rohvorschau"),
+                Text::AutoType => ("Auto ({0})", "Automatisch ({0})"),
+                Text::AutoTypeHint => (
+                    "Auto uses REG_EXPAND_SZ when an entry has %VAR% tokens",
+                    "Automatisch verwendet REG_EXPAND_SZ, wenn ein Eintrag %VAR%-Token enthält",
+                ),
+                Text::Type => ("Type", "Typ"),
+                Text::TokensWontExpand => (
+                    "%VAR% tokens will not expand",
+                    "%VAR%-Token werden nicht erweitert",
+                ),
+                Text::SaveElevatedHint => (
+                    "Asks for admin rights once to write this scope; the editor stays open with \
+                     your other edits",
+                    "Fragt einmal nach Administratorrechten, um diesen Bereich zu schreiben; der \
+                     Editor bleibt mit Ihren anderen Änderungen geöffnet",
+                ),
+                Text::AsksForAdmin => ("(asks for admin)", "(fragt nach Administratorrechten)"),
+                Text::ScanCacheCleared => (
+                    "Folder scan cache cleared",
+                    "Zwischenspeicher der Ordnerscans geleert",
+                ),
+                Text::CreatedFolder => ("Created folder {0}", "Ordner {0} erstellt"),
+                Text::CreateFailed => (
+                    "Could not create {0}: {1}",
+                    "{0} konnte nicht erstellt werden: {1}",
+                ),
+                Text::FixedEntry => (
+                    "Fixed entry in {0} into {1} entry/entries",
+                    "Eintrag in {0} repariert, ergibt {1} Einträge",
+                ),
+                Text::SelectedEntries => (
+                    "{0} {1} entry/entries selected",
+                    "{0} Einträge in {1} ausgewählt",
+                ),
+
+                // Add, move and expanded dialogs
+                Text::AddSystemTitle => ("Add System PATH Entry", "System-PATH-Eintrag hinzufügen"),
+                Text::AddUserTitle => ("Add User PATH Entry", "Benutzer-PATH-Eintrag hinzufügen"),
+                Text::EditSystemTitle => (
+                    "Edit System PATH Entry",
+                    "System-PATH-Eintrag bearbeiten",
+                ),
+                Text::EditUserTitle => ("Edit User PATH Entry", "Benutzer-PATH-Eintrag bearbeiten"),
+                Text::EntryLabel => (
+                    "Entry (supports %VAR% tokens, e.g. %SystemRoot%\\System32)",
+                    "Eintrag (unterstützt %VAR%-Token, z. B. %SystemRoot%\\System32)",
+                ),
+                Text::EntriesDetected => ("{0} entries detected", "{0} Einträge erkannt"),
+                Text::OneDuplicate => (
+                    "This entry is already in the list",
+                    "Dieser Eintrag ist bereits in der Liste",
+                ),
+                Text::ManyDuplicates => (
+                    "{0} of {1} entries are already in the list",
+                    "{0} von {1} Einträgen sind bereits in der Liste",
+                ),
+                Text::OneMissing => ("This folder does not exist", "Dieser Ordner existiert nicht"),
+                Text::ManyMissing => (
+                    "{0} of {1} entries do not exist",
+                    "{0} von {1} Einträgen existieren nicht",
+                ),
+                Text::OneUnreachable => (
+                    "This folder is not reachable right now",
+                    "Dieser Ordner ist gerade nicht erreichbar",
+                ),
+                Text::ManyUnreachable => (
+                    "{0} of {1} entries are not reachable right now",
+                    "{0} von {1} Einträgen sind gerade nicht erreichbar",
+                ),
+                Text::BrowseDots => ("Browse...", "Durchsuchen..."),
+                Text::AddToFrontCheck => ("Add to front", "Am Anfang einfügen"),
+                Text::AddToFrontHint => (
+                    "Insert ahead of existing entries so they take precedence",
+                    "Vor den vorhandenen Einträgen einfügen, damit sie Vorrang haben",
+                ),
+                Text::UpdatedEntries => (
+                    "Updated {0} entry/entries in {1}",
+                    "{0} Einträge in {1} aktualisiert",
+                ),
+                Text::AddedEntries => (
+                    "Added {0} entry/entries in {1}",
+                    "{0} Einträge zu {1} hinzugefügt",
+                ),
+                Text::AlreadyPresentTitle => ("Already present", "Bereits vorhanden"),
+                Text::AlreadyPresentBody => (
+                    "{0} is already in {1}.\n\nAdd it again anyway?",
+                    "{0} ist bereits in {1}.\n\nTrotzdem erneut hinzufügen?",
+                ),
+                Text::AlreadyPresent => (
+                    "Already present in {0}: {1}",
+                    "Bereits in {0} vorhanden: {1}",
+                ),
+                Text::MoveToPositionTitle => ("Move to Position", "An Position verschieben"),
+                Text::PositionRange => ("Position (1-{0})", "Position (1-{0})"),
+                Text::EnterNumber => ("Enter a number", "Geben Sie eine Zahl ein"),
+                Text::WillMoveTo => (
+                    "Will move to position {0}",
+                    "Wird an Position {0} verschoben",
+                ),
+                Text::Move => ("Move", "Verschieben"),
+                Text::EnterPosition => (
+                    "Enter a position number",
+                    "Geben Sie eine Positionsnummer ein",
+                ),
+                Text::MovedToPosition => (
+                    "Moved entry to position {0} in {1}",
+                    "Eintrag in {1} an Position {0} verschoben",
+                ),
+                Text::ExpandedSystemTitle => ("Expanded System PATH", "Erweiterter System-PATH"),
+                Text::ExpandedUserTitle => ("Expanded User PATH", "Erweiterter Benutzer-PATH"),
+                Text::ExpandedFilterHint => (
+                    "Filter by entry or expanded path",
+                    "Nach Eintrag oder erweitertem Pfad filtern",
+                ),
+                Text::CopyListingHint => (
+                    "Copy the shown entries with their expansions",
+                    "Die angezeigten Einträge mit ihren Erweiterungen kopieren",
+                ),
+                Text::ExpandedListing => ("expanded listing", "erweiterte Liste"),
+                Text::CopyResolved => ("Copy resolved-only", "Nur aufgelöste kopieren"),
+                Text::CopyResolvedHint => (
+                    "Copy just the expanded paths, one per line",
+                    "Nur die erweiterten Pfade kopieren, einen pro Zeile",
+                ),
+                Text::ResolvedPaths => ("resolved paths", "aufgelöste Pfade"),
+                Text::ShownOf => ("{0} of {1} entries", "{0} von {1} Einträgen"),
+                Text::FolderExists => ("Folder exists", "Ordner existiert"),
+                Text::FolderMissing => ("Folder does not exist", "Ordner existiert nicht"),
+                Text::FolderUnavailable => (
+                    "Folder unavailable (timed out)",
+                    "Ordner nicht verfügbar (Zeitüberschreitung)",
+                ),
+                Text::CopyExpandedHint => (
+                    "Copy the expanded path",
+                    "Den erweiterten Pfad kopieren",
+                ),
+                Text::ExpandedPath => ("expanded path", "erweiterter Pfad"),
+                Text::CopiedToClipboard => (
+                    "Copied {0} to clipboard",
+                    "{0} in die Zwischenablage kopiert",
+                ),
+
+                // Replace and duplicates
+                Text::ReplaceSystemTitle => (
+                    "Find and Replace in System PATH",
+                    "Suchen und Ersetzen im System-PATH",
+                ),
+                Text::ReplaceUserTitle => (
+                    "Find and Replace in User PATH",
+                    "Suchen und Ersetzen im Benutzer-PATH",
+                ),
+                Text::Find => ("Find", "Suchen"),
+                Text::ReplaceWith => ("Replace with", "Ersetzen durch"),
+                Text::IgnoreCase => ("Ignore case", "Groß-/Kleinschreibung ignorieren"),
+                Text::Regex => ("Regex", "Regulärer Ausdruck"),
+                Text::SelectedOnly => ("Selected entries only", "Nur ausgewählte Einträge"),
+                Text::NoneSelected => (
+                    "No entries are selected in this panel",
+                    "In diesem Bereich sind keine Einträge ausgewählt",
+                ),
+                Text::WillChange => (
+                    "{0} entry/entries will change",
+                    "{0} Einträge werden geändert",
+                ),
+                Text::NoMatches => (
+                    "No entries match the search text",
+                    "Kein Eintrag passt zum Suchtext",
+                ),
+                Text::ReplacedText => (
+                    "Replaced text in {0} {1} entry/entries",
+                    "Text in {0} Einträgen von {1} ersetzt",
+                ),
+                Text::NoDuplicates => ("No duplicates in {0}", "Keine Duplikate in {0}"),
+                Text::DuplicatesTitle => ("Review Duplicates - {0}", "Duplikate prüfen - {0}"),
+                Text::DuplicatesHint => (
+                    "Pick the spelling to keep for each group. It stays at the position of the \
+                     group's first entry.",
+                    "Wählen Sie für jede Gruppe die Schreibweise, die erhalten bleibt. Sie bleibt \
+                     an der Position des ersten Eintrags der Gruppe.",
+                ),
+                Text::DuplicatesChanged => (
+                    "The list changed while reviewing; reopen Review Duplicates",
+                    "Die Liste wurde während der Prüfung geändert; öffnen Sie Duplikate prüfen \
+                     erneut",
+                ),
+                Text::MergedDuplicates => (
+                    "Merged duplicates in {0}, removed {1} entry/entries",
+                    "Duplikate in {0} zusammengeführt, {1} Einträge entfernt",
+                ),
+
+                // Cross-scope duplicates, remove invalid, overflow and cleanup
+                Text::NoSharedVariable => (
+                    "The panels show different variables; nothing to merge",
+                    "Die Bereiche zeigen verschiedene Variablen; nichts zusammenzuführen",
+                ),
+                Text::NoCrossDuplicates => (
+                    "No folders are listed in both User and System",
+                    "Kein Ordner steht sowohl in Benutzer als auch in System",
+                ),
+                Text::MergeTitle => ("Merge Across Scopes", "Bereichsübergreifend zusammenführen"),
+                Text::MergeHint => (
+                    "Each folder below is in both User and System PATH. Choose where it stays; it \
+                     is removed from the other scope.",
+                    "Jeder Ordner unten steht im Benutzer- und im System-PATH. Wählen Sie, wo er \
+                     bleibt; aus dem anderen Bereich wird er entfernt.",
+                ),
+                Text::AllInSystem => ("All in System", "Alle in System"),
+                Text::AllInUser => ("All in User", "Alle in Benutzer"),
+                Text::KeepIn => ("Keep in {0}:  {1}", "In {0} behalten:  {1}"),
+                Text::MergeChanged => (
+                    "The lists changed while reviewing; reopen Merge Across Scopes",
+                    "Die Listen wurden während der Prüfung geändert; öffnen Sie \
+                     Bereichsübergreifend zusammenführen erneut",
+                ),
+                Text::RemovedFromBoth => (
+                    "Removed {0} entry/entries from {1} and {2} from {3}",
+                    "{0} Einträge aus {1} und {2} aus {3} entfernt",
+                ),
+                Text::NoInvalid => (
+                    "No invalid entries in {0}",
+                    "Keine ungültigen Einträge in {0}",
+                ),
+                Text::RemoveInvalidTitle => ("Remove Invalid - {0}", "Ungültige entfernen - {0}"),
+                Text::KeepUnc => ("Keep network (UNC) paths", "Netzwerkpfade (UNC) behalten"),
+                Text::KeepUncHint => (
+                    "Network shares may only be offline right now",
+                    "Netzwerkfreigaben sind möglicherweise nur gerade offline",
+                ),
+                Text::UndefinedKept => (
+                    "Entries with undefined %VARIABLES% are kept; fix or remove them separately.",
+                    "Einträge mit nicht definierten %VARIABLEN% bleiben erhalten; reparieren oder \
+                     entfernen Sie sie separat.",
+                ),
+                Text::WillBeRemoved => (
+                    "{0} entry/entries will be removed:",
+                    "{0} Einträge werden entfernt:",
+                ),
+                Text::RemoveInvalidChanged => (
+                    "The list changed while reviewing; reopen Remove Invalid",
+                    "Die Liste wurde während der Prüfung geändert; öffnen Sie Ungültige entfernen \
+                     erneut",
+                ),
+                Text::RemovedInvalid => (
+                    "Removed {0} invalid {1} entry/entries",
+                    "{0} ungültige Einträge aus {1} entfernt",
+                ),
+                Text::OverflowCritical => (
+                    "Only critical folders would be left to move; System PATH cannot be brought \
+                     under the limit this way",
+                    "Es blieben nur kritische Ordner zum Verschieben; so lässt sich der \
+                     System-PATH nicht unter die Grenze bringen",
+                ),
+                Text::OverflowTitle => (
+                    "Move Overflow to User PATH",
+                    "Überhang in Benutzer-PATH verschieben",
+                ),
+                Text::OverflowBody => (
+                    "Moving these {0} trailing entries brings System PATH down to {1} characters. \
+                     Critical folders stay in System PATH.",
+                    "Das Verschieben dieser {0} letzten Einträge verkürzt den System-PATH auf {1} \
+                     Zeichen. Kritische Ordner bleiben im System-PATH.",
+                ),
+                Text::AlreadyInUser => (
+                    "(already in User, only removed)",
+                    "(bereits in Benutzer, wird nur entfernt)",
+                ),
+                Text::OverflowChanged => (
+                    "The list changed while reviewing; reopen Move overflow",
+                    "Die Liste wurde während der Prüfung geändert; öffnen Sie Überhang verschieben \
+                     erneut",
+                ),
+                Text::CleanupSystemTitle => ("Clean Up System PATH", "System-PATH bereinigen"),
+                Text::CleanupUserTitle => ("Clean Up User PATH", "Benutzer-PATH bereinigen"),
+                Text::CleanupTrimOption => (
+                    "Trim whitespace and trailing slashes",
+                    "Leerzeichen und abschließende Schrägstriche entfernen",
+                ),
+                Text::CleanupNormalizeOption => ("Normalize / to \\", "/ zu \\ vereinheitlichen"),
+                Text::CleanupDedupeOption => ("Remove duplicates", "Duplikate entfernen"),
+                Text::CleanupMissingOption => (
+                    "Remove entries whose folder does not exist (includes offline network shares)",
+                    "Einträge entfernen, deren Ordner nicht existiert (auch Netzwerkfreigaben, die \
+                     offline sind)",
+                ),
+                Text::EntriesBeforeAfter => (
+                    "{0} entries -> {1} entries",
+                    "{0} Einträge -> {1} Einträge",
+                ),
+                Text::StepTrim => ("Trim", "Kürzen"),
+                Text::StepNormalize => ("Normalize slashes", "Schrägstriche vereinheitlichen"),
+                Text::StepMissing => ("Remove missing", "Fehlende entfernen"),
+                Text::CleanedUp => (
+                    "Cleaned up {0} ({1} -> {2} entries)",
+                    "{0} bereinigt ({1} -> {2} Einträge)",
+                ),
+
+                // Resolve and import dialogs
+                Text::ResolveTitle => ("Resolve Executable", "Programm auflösen"),
+                Text::ExecutableName => (
+                    "Executable name (PATHEXT is applied when no extension is given)",
+                    "Programmname (ohne Endung wird PATHEXT angewendet)",
+                ),
+                Text::ResolveButton => ("Resolve", "Auflösen"),
+                Text::NoMatch => (
+                    "No match found in User or System PATH.",
+                    "Kein Treffer im Benutzer- oder System-PATH.",
+                ),
+                Text::Shadowed => ("{0}  (shadowed)", "{0}  (verdeckt)"),
+                Text::FromEntry => ("    from {0} entry {1}", "    aus {0}-Eintrag {1}"),
+                Text::ImportTitle => ("Import PATH", "PATH importieren"),
+                Text::SourceKey => ("Source key", "Quellschlüssel"),
+                Text::Target => ("Target", "Ziel"),
+                Text::Mode => ("Mode", "Modus"),
+                Text::ReplaceButton => ("Replace", "Ersetzen"),
+                Text::Append => ("Append", "Anhängen"),
+                Text::AppendDedupe => ("Append and dedupe", "Anhängen und Duplikate entfernen"),
+                Text::ImportButton => ("Import", "Importieren"),
+
+                // Environment variables
+                Text::AdminRequired => (
+                    "Administrator required",
+                    "Administratorrechte erforderlich",
+                ),
+                Text::SystemEnvAdmin => (
+                    "Changing System environment values requires running as Administrator.",
+                    "Zum Ändern von System-Umgebungswerten muss das Programm als Administrator \
+                     laufen.",
+                ),
+                Text::RenamedVariable => ("Renamed {0} to {1}", "{0} in {1} umbenannt"),
+                Text::SavedVariable => ("Saved {0}", "{0} gespeichert"),
+                Text::AddedVariable => ("Added {0}", "{0} hinzugefügt"),
+                Text::DeleteVariableTitle => ("Delete variable?", "Variable löschen?"),
+                Text::DeleteVariableBody => (
+                    "Delete the {0} variable {1}?",
+                    "Die {0}-Variable {1} löschen?",
+                ),
+                Text::DeletedVariable => ("Deleted {0} variable {1}", "{0}-Variable {1} gelöscht"),
+                Text::DeleteFailed => ("Delete failed", "Löschen fehlgeschlagen"),
+                Text::EnvFilterHint => (
+                    "Type to filter by name or value",
+                    "Tippen, um nach Name oder Wert zu filtern",
+                ),
+                Text::Scope => ("Scope", "Bereich"),
+                Text::Name => ("Name", "Name"),
+                Text::Value => ("Value", "Wert"),
+                Text::EditButton => ("Edit", "Bearbeiten"),
+                Text::Delete => ("Delete", "Löschen"),
+                Text::ReadOnly => ("read-only", "schreibgeschützt"),
+                Text::ReloadedVariables => (
+                    "Reloaded environment variables",
+                    "Umgebungsvariablen neu geladen",
+                ),
+                Text::EditVariableTitle => ("Edit Variable", "Variable bearbeiten"),
+                Text::AddVariableTitle => ("Add Variable", "Variable hinzufügen"),
+                Text::FixedScope => (
+                    "An existing variable cannot change scope",
+                    "Eine vorhandene Variable kann den Bereich nicht wechseln",
+                ),
+                Text::Expandable => ("Expandable (REG_EXPAND_SZ)", "Erweiterbar (REG_EXPAND_SZ)"),
+                Text::HiveAdmin => (
+                    "Loading another user's registry hive needs administrator rights. Use \
+                     \"Restart as Admin\" first.",
+                    "Zum Laden der Registrierungsstruktur eines anderen Benutzerkontos sind \
+                     Administratorrechte nötig. Verwenden Sie zuerst \"Als Administrator neu \
+                     starten\".",
+                ),
+                Text::ChooseProfile => (
+                    "Choose the user's profile folder",
+                    "Profilordner des Benutzerkontos auswählen",
+                ),
+                Text::ProfileFailed => (
+                    "Failed to open profile",
+                    "Profil konnte nicht geöffnet werden",
+                ),
+                Text::ClosedHive => (
+                    "Closed {0}'s PATH and unloaded the hive",
+                    "PATH von {0} geschlossen und Struktur entladen",
+                ),
+                Text::DiscardUserBody => (
+                    "{0} has unsaved edits that will be discarded.",
+                    "{0} hat ungespeicherte Änderungen, die verworfen werden.",
+                ),
+                Text::RestartFailed => (
+                    "Failed to restart as Administrator",
+                    "Neustart als Administrator fehlgeschlagen",
+                ),
+
+                // Labels
+                Text::FilterSubstring => ("Substring", "Teilzeichenfolge"),
+                Text::FilterFuzzy => ("Fuzzy", "Unscharf"),
+                Text::TypeAuto => ("Auto", "Automatisch"),
+                Text::SortAscending => ("Ascending", "Aufsteigend"),
+                Text::SortDescending => ("Descending", "Absteigend"),
+                Text::SortByLength => ("By Length", "Nach Länge"),
+                Text::SortNatural => ("Natural", "Natürlich"),
+                Text::SortByPriority => ("By Priority", "Nach Priorität"),
+                Text::CategoryWindows => ("Windows", "Windows"),
+                Text::CategoryProgramFiles => ("Program Files", "Programme"),
+                Text::CategoryProfile => ("User profile", "Benutzerprofil"),
+                Text::CategoryNetwork => ("Network", "Netzwerk"),
+                Text::CategoryOther => ("Other", "Sonstige"),
+
+                // Header and status bar
+                Text::Tagline => (
+                    "Directly edits registry PATH values (User and System).",
+                    "Bearbeitet die PATH-Werte in der Registrierung direkt (Benutzer und System).",
+                ),
+                Text::RestartAdmin => ("Restart as Admin", "Als Administrator neu starten"),
+                Text::Recheck => ("Re-check", "Erneut prüfen"),
+                Text::RecheckHint => (
+                    "Check again whether this window has admin rights",
+                    "Erneut prüfen, ob dieses Fenster Administratorrechte hat",
+                ),
+                Text::PrivilegesAdmin => (
+                    "Privileges unchanged (Administrator)",
+                    "Rechte unverändert (Administrator)",
+                ),
+                Text::PrivilegesStandard => (
+                    "Privileges unchanged (standard)",
+                    "Rechte unverändert (Standard)",
+                ),
+                Text::ShadowingHint => (
+                    "Commands provided by more than one PATH folder",
+                    "Befehle, die von mehr als einem PATH-Ordner bereitgestellt werden",
+                ),
+                Text::MergeButton => (
+                    "Merge Across Scopes...",
+                    "Bereichsübergreifend zusammenführen...",
+                ),
+                Text::MergeButtonHint => (
+                    "Keep each folder listed in both User and System in one",
+                    "Jeden Ordner, der in Benutzer und System steht, nur in einem behalten",
+                ),
+                Text::CloseOtherUser => ("Close Other User", "Anderes Benutzerkonto schließen"),
+                Text::OpenOtherUser => (
+                    "Open other user's PATH...",
+                    "PATH eines anderen Benutzerkontos öffnen...",
+                ),
+                Text::OpenOtherUserHint => (
+                    "Load another profile's NTUSER.DAT into the User panel",
+                    "NTUSER.DAT eines anderen Profils in den Benutzer-Bereich laden",
+                ),
+                Text::ExportJson => ("Export JSON...", "JSON exportieren..."),
+                Text::ImportJson => ("Import JSON...", "JSON importieren..."),
+                Text::ExportReg => ("Export .reg", ".reg exportieren"),
+                Text::Both => ("Both", "Beide"),
+                Text::ExportScript => ("Export script", "Skript exportieren"),
+                Text::ScriptTarget => ("{0} PATH (.{1})", "{0}-PATH (.{1})"),
+                Text::SearchBoth => ("Search both", "Beide durchsuchen"),
+                Text::SearchBothHint => (
+                    "Highlight matching entries in User and System",
+                    "Passende Einträge in Benutzer und System hervorheben",
+                ),
+                Text::SearchCounts => ("User: {0} · System: {1}", "Benutzer: {0} · System: {1}"),
+                Text::ClearSearch => ("Clear the search", "Suche leeren"),
+                Text::SaveAll => ("Save ALL", "ALLES speichern"),
+                Text::ApplyToSession => ("Apply to Session", "Auf Sitzung anwenden"),
+                Text::ApplyToSessionHint => (
+                    "Not saved: sets PATH for this app and the programs it starts, until it exits",
+                    "Nicht gespeichert: setzt PATH für dieses Programm und die von ihm gestarteten \
+                     Programme, bis es beendet wird",
+                ),
+                Text::DryRun => ("Dry run", "Probelauf"),
+                Text::DryRunHint => (
+                    "Preview registry writes without changing anything or broadcasting",
+                    "Schreibvorgänge in der Registrierung nur anzeigen, ohne etwas zu ändern oder \
+                     zu senden",
+                ),
+                Text::History => ("History", "Verlauf"),
+                Text::HistoryHint => (
+                    "Show the messages from this session",
+                    "Die Meldungen dieser Sitzung anzeigen",
+                ),
+                Text::RetryBroadcast => ("Retry broadcast", "Senden wiederholen"),
+                Text::BroadcastFailed => (
+                    "Change broadcast failed",
+                    "Senden der Änderung fehlgeschlagen",
+                ),
+                Text::BroadcastFailedHint => (
+                    "The save succeeded, but WM_SETTINGCHANGE was aborted or timed out. New \
+                     processes may keep the old environment until they are restarted from a \
+                     refreshed Explorer.",
+                    "Das Speichern war erfolgreich, aber WM_SETTINGCHANGE wurde abgebrochen oder \
+                     lief in eine Zeitüberschreitung. Neue Prozesse behalten möglicherweise die \
+                     alte Umgebung, bis sie aus einem aktualisierten Explorer neu gestartet \
+                     werden.",
+                ),
+                Text::UndefinedCount => (
+                    "{0} entries reference undefined variables",
+                    "{0} Einträge verweisen auf nicht definierte Variablen",
+                ),
+                Text::Wow64View => (
+                    "64-bit registry view (WOW64)",
+                    "64-Bit-Registrierungsansicht (WOW64)",
+                ),
+                Text::Wow64Hint => (
+                    "32-bit build: System PATH is still read and written in the 64-bit view, not \
+                     WOW6432Node.",
+                    "32-Bit-Build: Der System-PATH wird trotzdem in der 64-Bit-Ansicht gelesen und \
+                     geschrieben, nicht in WOW6432Node.",
+                ),
+                Text::NativeView => ("64-bit registry view", "64-Bit-Registrierungsansicht"),
+
+                // Entry checks and tooltips
+                Text::ProblemSemicolon => ("contains ';'", "enthält ';'"),
+                Text::ProblemQuotes => ("contains quotes", "enthält Anführungszeichen"),
+                Text::ProblemWhitespace => (
+                    "leading/trailing whitespace",
+                    "führende/abschließende Leerzeichen",
+                ),
+                Text::ProblemUnc => (
+                    "UNC path needs \\\\server\\share",
+                    "UNC-Pfad braucht \\\\server\\freigabe",
+                ),
+                Text::ProblemExpansion => (
+                    "variables do not fully expand",
+                    "Variablen werden nicht vollständig erweitert",
+                ),
+                Text::NotExpanded => (
+                    "%{0}% is not defined and was not expanded",
+                    "%{0}% ist nicht definiert und wurde nicht erweitert",
+                ),
+                Text::RelativeEntry => (
+                    "Relative entry: resolved against the current directory of each process",
+                    "Relativer Eintrag: wird gegen das aktuelle Verzeichnis jedes Prozesses \
+                     aufgelöst",
+                ),
+                Text::ScanTimedOut => (
+                    "Folder unavailable (scan timed out)",
+                    "Ordner nicht verfügbar (Zeitüberschreitung beim Scan)",
+                ),
+                Text::ScanningFolderDots => ("Scanning folder...", "Ordner wird gescannt..."),
+                Text::FolderExecutables => (
+                    "Folder exists, {0} executable(s)",
+                    "Ordner existiert, {0} Programm(e)",
+                ),
+                Text::AndMore => ("  ... and {0} more", "  ... und {0} weitere"),
+                Text::NoEntryChanges => ("No entry changes", "Keine Änderungen an Einträgen"),
+                Text::MovedLine => (
+                    "~ {0}  (moved from #{1} to #{2})",
+                    "~ {0}  (verschoben von Nr. {1} nach Nr. {2})",
+                ),
+                Text::CriticalTitle => (
+                    "Remove critical system folders?",
+                    "Kritische Systemordner entfernen?",
+                ),
+                Text::CriticalBody => (
+                    "These entries are needed by Windows and most programs:\n\n{0}\n\nRemove them \
+                     anyway?",
+                    "Diese Einträge werden von Windows und den meisten Programmen \
+                     benötigt:\n\n{0}\n\nTrotzdem entfernen?",
+                ),
+                Text::NoSystem32Title => (
+                    "System PATH has no System32",
+                    "System-PATH enthält kein System32",
+                ),
+                Text::NoSystem32Body => (
+                    "The System PATH you are about to save does not contain {0}.\n\nWindows tools, \
+                     installers and many programs will stop working until it is restored.\n\nSave \
+                     anyway?",
+                    "Der System-PATH, den Sie speichern wollen, enthält {0} \
+                     nicht.\n\nWindows-Werkzeuge, Installationsprogramme und viele Programme \
+                     funktionieren erst wieder, wenn der Eintrag wiederhergestellt \
+                     ist.\n\nTrotzdem speichern?",
+                ),
+                Text::SaveCancelled => ("Save cancelled", "Speichern abgebrochen"),
+                Text::ConfirmTitle => ("Confirm", "Bestätigen"),
+                Text::ConfirmBody => ("Apply PATH changes?", "PATH-Änderungen übernehmen?"),
+                Text::HelperNotStarted => (
+                    "the elevated helper did not start: {0}",
+                    "der Hilfsprozess mit Administratorrechten ist nicht gestartet: {0}",
+                ),
+                Text::HelperTimedOut => (
+                    "the elevated helper did not finish within {0} seconds",
+                    "der Hilfsprozess mit Administratorrechten ist nicht innerhalb von {0} \
+                     Sekunden fertig geworden",
+                ),
+                Text::HelperExitCode => (
+                    "the elevated helper exited with code {0}",
+                    "der Hilfsprozess mit Administratorrechten wurde mit Code {0} beendet",
+                ),
+                Text::HelperNotAdmin => (
+                    "the helper is not running as Administrator",
+                    "der Hilfsprozess läuft nicht als Administrator",
+                ),
+                Text::RequestChanged => (
+                    "the request file was changed after it was written",
+                    "die Anfragedatei wurde nach dem Schreiben geändert",
+                ),
+                Text::InvalidVariableName => (
+                    "invalid variable name '{0}'",
+                    "ungültiger Variablenname '{0}'",
+                ),
+                Text::ValueTooLong => (
+                    "the value is longer than Windows allows",
+                    "der Wert ist länger, als Windows erlaubt",
+                ),
+                Text::EmptySystemPath => (
+                    "refusing to write an empty System PATH",
+                    "ein leerer System-PATH wird nicht geschrieben",
+                ),
+            }
+        }
+
+        fn get(self, language: Language) -> &'static str {
+            let (english, german) = self.pair();
+            if language == Language::German {
+                german
+            } else {
+                english
+            }
+        }
+    }
+
+    /// Shorthand for `text` in the language chosen in Settings.
+    fn tr(text: Text) -> &'static str {
+        text.get(ui_language())
+    }
+
+    /// `tr` with each `{n}` replaced by `args[n]`, so translations can reorder the values.
+    fn trf(text: Text, args: &[&dyn std::fmt::Display]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(tr(text).to_string(), |out, (n, arg)| {
+                out.replace(&format!("{{{n}}}"), &arg.to_string())
+            })
+    }
+
+    fn scope_label(is_system: bool) -> &'static str {
+        if is_system {
+            tr(Text::ScopeSystem)
+        } else {
+            tr(Text::ScopeUser)
+        }
+    }
+
+    #[derive(Clone)]
+    struct Settings {
+        theme: Theme,
+        logging: bool,
+        dry_run_default: bool,
+        confirm_diff: bool,
+        backup_on_save: bool,
+        backup_keep: usize,
+        add_to_system: bool,
+        add_to_front: bool,
+        browse_tokenize: bool,
+        dedupe_prefer_tokens: bool,
+        category_colors: bool,
+        /// Entries that removal and save prompt for, compared with `normalize_for_compare`.
+        critical_paths: Vec<String>,
+        clipboard_check: bool,
+        language: Language,
+        /// Snapshot both PATH values on startup once the newest backup is this old.
+        startup_backup: bool,
+        startup_backup_hours: u64,
+        /// Compare the registry with the newest backup on startup and report differences.
+        session_changes: bool,
+        /// Report successful saves with a toast instead of a message box.
+        save_toasts: bool,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Self {
+                theme: Theme::Dark,
+                logging: true,
+                dry_run_default: false,
+                confirm_diff: true,
+                backup_on_save: true,
+                backup_keep: DEFAULT_BACKUP_KEEP,
+                add_to_system: false,
+                add_to_front: false,
+                browse_tokenize: false,
+                dedupe_prefer_tokens: true,
+                category_colors: false,
+                critical_paths: CRITICAL_PATHS.iter().map(|p| p.to_string()).collect(),
+                clipboard_check: false,
+                language: Language::System,
+                startup_backup: true,
+                startup_backup_hours: DEFAULT_BACKUP_HOURS,
+                session_changes: true,
+                save_toasts: true,
+            }
+        }
+    }
+
+    impl Settings {
+        /// Reads each setting from eframe storage, keeping the default for anything missing or
+        /// unreadable.
+        fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+            let defaults = Self::default();
+            let Some(storage) = storage else {
+                return defaults;
+            };
+            let flag = |key: &str, default: bool| match storage.get_string(key).as_deref() {
+                Some("on") => true,
+                Some("off") => false,
+                _ => default,
+            };
+            Self {
+                theme: storage
+                    .get_string(THEME_KEY)
+                    .and_then(|key| Theme::from_key(&key))
+                    .unwrap_or(defaults.theme),
+                logging: flag(LOGGING_KEY, defaults.logging),
+                dry_run_default: flag(DRY_RUN_KEY, defaults.dry_run_default),
+                confirm_diff: flag(CONFIRM_DIFF_KEY, defaults.confirm_diff),
+                backup_on_save: flag(BACKUP_KEY, defaults.backup_on_save),
+                backup_keep: storage
+                    .get_string(BACKUP_KEEP_KEY)
+                    .and_then(|value| parse_backup_keep(&value))
+                    .unwrap_or(defaults.backup_keep),
+                add_to_system: storage
+                    .get_string(ADD_SCOPE_KEY)
+                    .is_some_and(|scope| scope == "system"),
+                add_to_front: flag(ADD_FRONT_KEY, defaults.add_to_front),
+                browse_tokenize: flag(BROWSE_TOKENIZE_KEY, defaults.browse_tokenize),
+                dedupe_prefer_tokens: flag(DEDUPE_TOKENS_KEY, defaults.dedupe_prefer_tokens),
+                category_colors: flag(CATEGORY_COLORS_KEY, defaults.category_colors),
+                critical_paths: storage
+                    .get_string(CRITICAL_PATHS_KEY)
+                    .map(|list| parse_critical_paths(&list))
+                    .unwrap_or(defaults.critical_paths),
+                clipboard_check: flag(CLIPBOARD_CHECK_KEY, defaults.clipboard_check),
+                language: storage
+                    .get_string(LANGUAGE_KEY)
+                    .and_then(|key| Language::from_key(&key))
+                    .unwrap_or(defaults.language),
+                startup_backup: flag(STARTUP_BACKUP_KEY, defaults.startup_backup),
+                startup_backup_hours: storage
+                    .get_string(STARTUP_BACKUP_HOURS_KEY)
+                    .and_then(|value| parse_backup_hours(&value))
+                    .unwrap_or(defaults.startup_backup_hours),
+                session_changes: flag(SESSION_CHANGES_KEY, defaults.session_changes),
+                save_toasts: flag(SAVE_TOASTS_KEY, defaults.save_toasts),
+            }
+        }
+
+        fn save(&self, storage: &mut dyn eframe::Storage) {
+            let flag = |value: bool| if value { "on" } else { "off" }.to_string();
+            storage.set_string(THEME_KEY, self.theme.key().to_string());
+            storage.set_string(LOGGING_KEY, flag(self.logging));
+            storage.set_string(DRY_RUN_KEY, flag(self.dry_run_default));
+            storage.set_string(CONFIRM_DIFF_KEY, flag(self.confirm_diff));
+            storage.set_string(BACKUP_KEY, flag(self.backup_on_save));
+            storage.set_string(BACKUP_KEEP_KEY, self.backup_keep.to_string());
+            let scope = if self.add_to_system { "system" } else { "user" };
+            storage.set_string(ADD_SCOPE_KEY, scope.to_string());
+            storage.set_string(ADD_FRONT_KEY, flag(self.add_to_front));
+            storage.set_string(BROWSE_TOKENIZE_KEY, flag(self.browse_tokenize));
+            storage.set_string(DEDUPE_TOKENS_KEY, flag(self.dedupe_prefer_tokens));
+            storage.set_string(CATEGORY_COLORS_KEY, flag(self.category_colors));
+            storage.set_string(CRITICAL_PATHS_KEY, self.critical_paths.join("\n"));
+            storage.set_string(CLIPBOARD_CHECK_KEY, flag(self.clipboard_check));
+            storage.set_string(LANGUAGE_KEY, self.language.key().to_string());
+            storage.set_string(STARTUP_BACKUP_KEY, flag(self.startup_backup));
+            storage.set_string(STARTUP_BACKUP_HOURS_KEY, self.startup_backup_hours.to_string());
+            storage.set_string(SESSION_CHANGES_KEY, flag(self.session_changes));
+            storage.set_string(SAVE_TOASTS_KEY, flag(self.save_toasts));
+        }
+    }
+
+    /// One entry per line; blank lines are ignored.
+    fn parse_critical_paths(text: &str) -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Both scopes as written by "Export JSON" and read back by "Import JSON".
+    #[derive(Serialize, Deserialize)]
+    struct EditorSnapshot {
+        user: ScopeSnapshot,
+        system: ScopeSnapshot,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct ScopeSnapshot {
+        #[serde(default = "default_variable")]
+        variable: String,
+        parts: Vec<String>,
+        /// "REG_SZ" or "REG_EXPAND_SZ", as shown by `reg_type_name`.
+        reg_type: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        disabled: Vec<String>,
+    }
+
+    fn default_variable() -> String {
+        "Path".to_string()
+    }
+
+    impl ScopeSnapshot {
+        fn capture(store: &PathStore) -> Self {
+            Self {
+                variable: store.var_name.clone(),
+                parts: store.parts.clone(),
+                reg_type: reg_type_name(&store.write_type()),
+                disabled: store
+                    .parts
+                    .iter()
+                    .zip(store.disabled_flags())
+                    .filter(|(_, disabled)| *disabled)
+                    .map(|(part, _)| part.clone())
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct SettingsDialogState {
+        open: bool,
+        draft: Settings,
+        backup_keep_text: String,
+        backup_hours_text: String,
+        critical_text: String,
+    }
+
+    /// Another user's NTUSER.DAT mounted under HKEY_USERS. Unloaded again on drop.
+    struct LoadedHive {
+        user: String,
+    }
+
+    impl LoadedHive {
+        fn load(profile: &Path) -> Result<Self, String> {
+            let file = profile.join("NTUSER.DAT");
+            if !file.is_file() {
+                return Err(trf(Text::HiveNotFound, &[&file.display()]));
+            }
+            if HIVE_LOADED.load(Ordering::Relaxed) {
+                return Err(tr(Text::HiveAlreadyOpen).to_string());
+            }
+            for privilege in [SE_RESTORE_NAME, SE_BACKUP_NAME] {
+                enable_privilege(privilege).map_err(|err| trf(Text::HivePrivileges, &[&err]))?;
+            }
+
+            let mount = to_wide(HIVE_MOUNT);
+            let path = to_wide(&file.display().to_string());
+            let result = unsafe {
+                RegLoadKeyW(
+                    windows::Win32::System::Registry::HKEY_USERS,
+                    PCWSTR(mount.as_ptr()),
+                    PCWSTR(path.as_ptr()),
+                )
+            };
+            if result == ERROR_SHARING_VIOLATION {
+                return Err(trf(Text::HiveInUse, &[&file.display()]));
+            }
+            if result != ERROR_SUCCESS {
+                let err = std::io::Error::from_raw_os_error(result.0 as i32);
+                return Err(trf(Text::HiveLoadFailed, &[&file.display(), &err]));
+            }
+
+            HIVE_LOADED.store(true, Ordering::Relaxed);
+            let user = profile
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| profile.display().to_string());
+            log_event(&format!("Loaded hive {}", file.display()));
+            Ok(Self { user })
+        }
+    }
+
+    impl Drop for LoadedHive {
+        fn drop(&mut self) {
+            HIVE_LOADED.store(false, Ordering::Relaxed);
+            let mount = to_wide(HIVE_MOUNT);
+            let result = unsafe {
+                RegUnLoadKeyW(
+                    windows::Win32::System::Registry::HKEY_USERS,
+                    PCWSTR(mount.as_ptr()),
+                )
+            };
+            if result != ERROR_SUCCESS {
+                let err = std::io::Error::from_raw_os_error(result.0 as i32);
+                log_event(&format!("Failed to unload the hive of {}: {err}", self.user));
+            }
+        }
+    }
+
+    /// Background thread that waits on `RegNotifyChangeKeyValue` for both Environment keys.
+    struct RegistryWatcher {
+        stop_event: HANDLE,
+        changed: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl RegistryWatcher {
+        fn start(ctx: egui::Context) -> Option<Self> {
+            let stop_event = unsafe { CreateEventW(None, TRUE, FALSE, PCWSTR::null()) }.ok()?;
+            let changed = Arc::new(AtomicBool::new(false));
+            let raw_stop = stop_event.0 as isize;
+            let flag = Arc::clone(&changed);
+            let thread = thread::Builder::new()
+                .name("registry-watcher".to_string())
+                .spawn(move || watch_environment_keys(HANDLE(raw_stop as _), flag, ctx));
+
+            match thread {
+                Ok(thread) => Some(Self {
+                    stop_event,
+                    changed,
+                    thread: Some(thread),
+                }),
+                Err(_) => {
+                    unsafe {
+                        let _ = CloseHandle(stop_event);
+                    }
+                    None
+                }
+            }
+        }
+
+        fn take_change(&self) -> bool {
+            self.changed.swap(false, Ordering::AcqRel)
+        }
+    }
+
+    impl Drop for RegistryWatcher {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = SetEvent(self.stop_event);
+            }
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            unsafe {
+                let _ = CloseHandle(self.stop_event);
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum View {
+        Path,
+        Variables,
+        Effective,
+    }
+
+    struct EnvVarRow {
+        is_system: bool,
+        name: String,
+        vtype: RegType,
+        value: String,
+    }
+
+    impl EnvVarRow {
+        fn is_editable(&self) -> bool {
+            self.vtype == REG_SZ || self.vtype == REG_EXPAND_SZ
+        }
+    }
+
+    #[derive(Default)]
+    struct EnvEditState {
+        open: bool,
+        is_system: bool,
+        original_name: Option<String>,
+        name: String,
+        value: String,
+        expand: bool,
+    }
+
+    #[derive(Default)]
+    struct EnvBrowserState {
+        rows: Vec<EnvVarRow>,
+        filter: String,
+        edit: EnvEditState,
+    }
+
+    #[derive(Default)]
+    struct ReplaceDialogState {
+        open: bool,
+        is_system: bool,
+        find: String,
+        replace: String,
+        ignore_case: bool,
+        use_regex: bool,
+        selected_only: bool,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    struct CleanupOptions {
+        trim: bool,
+        normalize: bool,
+        dedupe: bool,
+        remove_missing: bool,
+    }
+
+    impl Default for CleanupOptions {
+        fn default() -> Self {
+            Self {
+                trim: true,
+                normalize: true,
+                dedupe: true,
+                remove_missing: false,
+            }
+        }
+    }
+
+    struct CleanupStep {
+        name: Text,
+        changes: Vec<String>,
+    }
+
+    struct CleanupPreview {
+        options: CleanupOptions,
+        result: Vec<String>,
+        steps: Vec<CleanupStep>,
+    }
+
+    #[derive(Default)]
+    struct CleanupDialogState {
+        open: bool,
+        is_system: bool,
+        options: CleanupOptions,
+        preview: Option<CleanupPreview>,
+    }
+
+    #[derive(Default)]
+    struct DuplicatesDialogState {
+        open: bool,
+        is_system: bool,
+        snapshot: Vec<String>,
+        groups: Vec<DuplicateGroup>,
+    }
+
+    #[derive(Default)]
+    struct RemoveInvalidDialogState {
+        open: bool,
+        is_system: bool,
+        snapshot: Vec<String>,
+        skip_unc: bool,
+    }
+
+    #[derive(Default)]
+    struct SplitDialogState {
+        open: bool,
+        snapshot: Vec<String>,
+        indices: Vec<usize>,
+    }
+
+    #[derive(Default)]
+    struct CrossDuplicatesDialogState {
+        open: bool,
+        user_snapshot: Vec<String>,
+        system_snapshot: Vec<String>,
+        duplicates: Vec<CrossDuplicate>,
+    }
+
+    /// One folder listed in both scopes: its indices on each side and which side keeps it.
+    struct CrossDuplicate {
+        user: Vec<usize>,
+        system: Vec<usize>,
+        keep_system: bool,
+    }
+
+    /// Indices of entries sharing one `normalize_for_compare` key, and which one to keep.
+    struct DuplicateGroup {
+        indices: Vec<usize>,
+        keep: usize,
+    }
+
+    #[derive(Default)]
+    struct SaveConfirmState {
+        open: bool,
+        scopes: Vec<bool>,
+        save_all: bool,
+    }
+
+    /// Scopes whose registry PATH differs from the last session, found once at startup.
+    #[derive(Default)]
+    struct SessionChangesState {
+        banner: bool,
+        open: bool,
+        /// (is_system, where the baseline came from, its entries, entries in the registry)
+        scopes: Vec<(bool, String, Vec<String>, Vec<String>)>,
+    }
+
+    #[derive(Default)]
+    struct ChangesDialogState {
+        open: bool,
+        is_system: bool,
+        live: Vec<String>,
+        error: Option<String>,
+    }
+
+    struct PathEditorApp {
+        view: View,
+        env_browser: EnvBrowserState,
+        user: PathStore,
+        system: PathStore,
+        status: String,
+        is_admin: bool,
+        hive: Option<LoadedHive>,
+        elevated_save: Option<ElevatedSave>,
+        /// Header search across both panels; highlights matches without filtering.
+        global_search: String,
+        /// User notes keyed by `normalize_for_compare`, so they follow an entry wherever it
+        /// moves. Kept in eframe storage since the registry has nowhere to put them.
+        notes: BTreeMap<String, String>,
+        note_dialog: NoteDialogState,
+        executables_dialog: ExecutablesDialogState,
+        /// Disabled entries and their positions per scope and variable, see `sync_disabled`.
+        disabled_entries: HashMap<String, Vec<DisabledEntry>>,
+        add_dialog: AddDialogState,
+        expanded_dialog: ExpandedDialogState,
+        move_dialog: MoveDialogState,
+        cross_duplicates_dialog: CrossDuplicatesDialogState,
+        resolve_dialog: ResolveDialogState,
+        shadow_dialog: ShadowDialogState,
+        import_dialog: ImportDialogState,
+        save_confirm: SaveConfirmState,
+        replace_dialog: ReplaceDialogState,
+        scanner: DirScanner,
+        cleanup_dialog: CleanupDialogState,
+        compare_dialog: CompareDialogState,
+        duplicates_dialog: DuplicatesDialogState,
+        remove_invalid_dialog: RemoveInvalidDialogState,
+        split_dialog: SplitDialogState,
+        changes_dialog: ChangesDialogState,
+        session_changes: SessionChangesState,
+        window_title: String,
+        allow_close: bool,
+        watcher: Option<RegistryWatcher>,
+        external_change: bool,
+        /// Folder found on the clipboard at startup, offered for the User PATH.
+        clipboard_offer: Option<String>,
+        /// Transient notices drawn over the window, oldest first.
+        toasts: VecDeque<(String, Instant)>,
+        focused_system: bool,
+        restore_focus: Option<bool>,
+        settings: Settings,
+        settings_dialog: SettingsDialogState,
+        system_light: bool,
+        dry_run: bool,
+        wow64: bool,
+        long_paths: bool,
+        broadcast_failed: bool,
+        panel_rects: [Option<egui::Rect>; 2],
+        history_log: VecDeque<String>,
+        history_open: bool,
+        last_status: String,
+        shield: Option<egui::TextureHandle>,
+    }
+
+    impl PathEditorApp {
+        fn new(cc: &eframe::CreationContext<'_>) -> Self {
+            let settings = Settings::load(cc.storage);
+            let system_light = system_uses_light_theme();
+            apply_style(&cc.egui_ctx, settings.theme.is_light(system_light));
+            LOGGING_ENABLED.store(settings.logging, Ordering::Relaxed);
+            GERMAN_UI.store(settings.language.resolve() == Language::German, Ordering::Relaxed);
+
+            let focused_system = cc
+                .storage
+                .and_then(|storage| storage.get_string(FOCUSED_PANEL_KEY))
+                .is_some_and(|panel| panel == "system");
+
+            let mut user = load_store(false, "Path");
+            let mut system = load_store(true, "Path");
+            if let Some(storage) = cc.storage {
+                user.filter = storage.get_string(USER_FILTER_KEY).unwrap_or_default();
+                system.filter = storage.get_string(SYSTEM_FILTER_KEY).unwrap_or_default();
+            }
+            let clipboard_offer = if settings.clipboard_check {
+                let mut known = (*user.derived().keys).clone();
+                known.extend(system.derived().keys.iter().cloned());
+                read_clipboard_text().and_then(|text| clipboard_folder(&text, &known))
+            } else {
+                None
+            };
+
+            let wow64 = is_wow64();
+            let startup = if wow64 {
+                Text::StatusWow64
+            } else {
+                Text::StatusReady
+            };
+            let mut status = tr(startup).to_string();
+            // Compared with the registry as this editor last saw it, or failing that the newest
+            // backup; checked before the startup backup, which would otherwise become the newest.
+            let session_changes = if settings.session_changes {
+                let sources = [
+                    (false, &user, LAST_USER_PATH_KEY),
+                    (true, &system, LAST_SYSTEM_PATH_KEY),
+                ];
+                let scopes = sources
+                    .into_iter()
+                    .filter(|(is_system, store, _)| {
+                        file_backed(*is_system, &store.var_name).is_none()
+                    })
+                    .filter_map(|(is_system, store, key)| {
+                        let last_session = cc.storage.and_then(|storage| storage.get_string(key));
+                        let (file, parts) = match last_session {
+                            Some(raw) => (tr(Text::FromLastSession).to_string(), split_path(&raw)),
+                            None => latest_backup(is_system)
+                                .map(|(file, parts)| (trf(Text::FromBackup, &[&file]), parts))?,
+                        };
+                        let current = store.saved_parts.clone();
+                        (parts != current).then_some((is_system, file, parts, current))
+                    })
+                    .collect::<Vec<_>>();
+                SessionChangesState {
+                    banner: !scopes.is_empty(),
+                    open: false,
+                    scopes,
+                }
+            } else {
+                SessionChangesState::default()
+            };
+            if settings.startup_backup {
+                let hours = settings.startup_backup_hours;
+                if let Err(err) = startup_backup(hours, settings.backup_keep) {
+                    log_event(&err);
+                    status = err;
+                }
+            }
+
+            Self {
+                view: View::Path,
+                env_browser: EnvBrowserState::default(),
+                user,
+                system,
+                status,
+                is_admin: is_admin(),
+                hive: None,
+                elevated_save: None,
+                global_search: String::new(),
+                notes: cc.storage.map(load_notes).unwrap_or_default(),
+                note_dialog: NoteDialogState::default(),
+                executables_dialog: ExecutablesDialogState::default(),
+                disabled_entries: cc.storage.map(load_disabled).unwrap_or_default(),
+                add_dialog: AddDialogState::default(),
+                expanded_dialog: ExpandedDialogState::default(),
+                move_dialog: MoveDialogState::default(),
+                cross_duplicates_dialog: CrossDuplicatesDialogState::default(),
+                resolve_dialog: ResolveDialogState::default(),
+                shadow_dialog: ShadowDialogState::default(),
+                import_dialog: ImportDialogState::default(),
+                save_confirm: SaveConfirmState::default(),
+                replace_dialog: ReplaceDialogState::default(),
+                scanner: DirScanner::default(),
+                cleanup_dialog: CleanupDialogState::default(),
+                compare_dialog: CompareDialogState::default(),
+                duplicates_dialog: DuplicatesDialogState::default(),
+                remove_invalid_dialog: RemoveInvalidDialogState::default(),
+                split_dialog: SplitDialogState::default(),
+                changes_dialog: ChangesDialogState::default(),
+                session_changes,
+                window_title: APP_TITLE.to_string(),
+                allow_close: false,
+                watcher: RegistryWatcher::start(cc.egui_ctx.clone()),
+                external_change: false,
+                clipboard_offer,
+                toasts: VecDeque::new(),
+                focused_system,
+                restore_focus: Some(focused_system),
+                dry_run: settings.dry_run_default,
+                settings,
+                settings_dialog: SettingsDialogState::default(),
+                system_light,
+                wow64,
+                long_paths: long_paths_enabled(),
+                broadcast_failed: false,
+                panel_rects: [None; 2],
+                history_log: VecDeque::new(),
+                history_open: false,
+                last_status: String::new(),
+                shield: load_shield_icon().map(|image| {
+                    cc.egui_ctx
+                        .load_texture("uac_shield", image, egui::TextureOptions::LINEAR)
+                }),
+            }
+        }
+
+        fn any_dialog_open(&self) -> bool {
+            self.add_dialog.open
+                || self.expanded_dialog.open
+                || self.resolve_dialog.open
+                || self.shadow_dialog.open
+                || self.import_dialog.open
+                || self.save_confirm.open
+                || self.replace_dialog.open
+                || self.cleanup_dialog.open
+                || self.settings_dialog.open
+                || self.compare_dialog.open
+                || self.duplicates_dialog.open
+                || self.cross_duplicates_dialog.open
+                || self.remove_invalid_dialog.open
+                || self.split_dialog.open
+                || self.changes_dialog.open
+                || self.session_changes.open
+                || self.note_dialog.open
+                || self.executables_dialog.open
+                || self.move_dialog.open
+                || self.env_browser.edit.open
+        }
+
+        fn panel_title(&self, is_system: bool) -> String {
+            let store = self.store(is_system);
+            let name = store.display_name();
+            if let Some(file) = file_backed(is_system, &store.var_name) {
+                let file_name = file.file_name().unwrap_or(file.as_os_str()).to_string_lossy();
+                return trf(Text::PanelFile, &[&name, &file_name]);
+            }
+            match &self.hive {
+                _ if is_system => trf(Text::PanelSystem, &[&name]),
+                Some(hive) => trf(Text::PanelOtherUser, &[&hive.user, &name]),
+                None => trf(Text::PanelUser, &[&name]),
+            }
+        }
+
+        fn open_add_dialog(&mut self, is_system: bool) {
+            self.add_dialog.open = true;
+            self.add_dialog.is_system = is_system;
+            self.add_dialog.input.clear();
+            self.add_dialog.edit_index = None;
+        }
+
+        fn browse_entry(&self, is_system: bool, folder: &Path) -> String {
+            let entry = folder.display().to_string();
+            if self.settings.browse_tokenize {
+                tokenize_path(&entry, &token_vars(is_system))
+            } else {
+                entry
+            }
+        }
+
+        /// Adds each folder that is not already present, at the front or the end depending on
+        /// the "Add to front" setting, and reports how many were added.
+        fn add_folders(&mut self, is_system: bool, folders: &[PathBuf]) {
+            let entries = folders
+                .iter()
+                .map(|folder| self.browse_entry(is_system, folder))
+                .collect::<Vec<_>>();
+            let to_front = self.settings.add_to_front;
+            let store = self.store_mut(is_system);
+            let mut existing = store
+                .parts
+                .iter()
+                .map(|p| normalize_for_compare(p))
+                .collect::<HashSet<_>>();
+            let added = entries
+                .into_iter()
+                .filter(|entry| existing.insert(normalize_for_compare(entry)))
+                .collect::<Vec<_>>();
+            let skipped = folders.len() - added.len();
+            if !added.is_empty() {
+                store.checkpoint();
+            }
+            let count = added.len();
+            store.edit_entries(|entries| {
+                let added = added.into_iter().map(|entry| (entry, false));
+                if to_front {
+                    entries.splice(0..0, added);
+                } else {
+                    entries.extend(added);
+                }
+            });
+
+            let title = self.panel_title(is_system);
+            self.status = if count == 0 {
+                trf(Text::AddedNothing, &[&title])
+            } else if skipped > 0 {
+                trf(Text::AddedSomeFolders, &[&count, &title, &skipped])
+            } else {
+                trf(Text::AddedFolders, &[&count, &title])
+            };
+        }
+
+        /// The panel under the pointer, falling back to User PATH.
+        fn drop_target(&self, ctx: &egui::Context) -> bool {
+            let pos = ctx.input(|i| i.pointer.hover_pos());
+            self.view == View::Path
+                && pos.is_some_and(|pos| self.panel_rects[1].is_some_and(|rect| rect.contains(pos)))
+        }
+
+        fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+            if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+                let is_system = self.drop_target(ctx);
+                if let Some(rect) = self.panel_rects[usize::from(is_system)] {
+                    let layer = egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop"));
+                    ctx.layer_painter(layer).rect(
+                        rect,
+                        6.0,
+                        ACCENT.gamma_multiply(0.15),
+                        egui::Stroke::new(2.0, ACCENT),
+                    );
+                }
+            }
+
+            let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+            if dropped.is_empty() {
+                return;
+            }
+            let is_system = self.drop_target(ctx);
+            let (folders, ignored): (Vec<PathBuf>, Vec<PathBuf>) = dropped
+                .into_iter()
+                .filter_map(|file| file.path)
+                .partition(|path| path.is_dir());
+            if !folders.is_empty() {
+                self.add_folders(is_system, &folders);
+            }
+            if !ignored.is_empty() {
+                let note = trf(Text::IgnoredDrops, &[&ignored.len()]);
+                self.status = if folders.is_empty() {
+                    note
+                } else {
+                    format!("{}; {note}", self.status)
+                };
+            }
+        }
+
+        fn open_edit_dialog(&mut self, is_system: bool, idx: usize) {
+            let Some(entry) = self.store(is_system).parts.get(idx).cloned() else {
+                return;
+            };
+            self.add_dialog.open = true;
+            self.add_dialog.is_system = is_system;
+            self.add_dialog.input = entry;
+            self.add_dialog.edit_index = Some(idx);
+        }
+
+        fn open_expanded_dialog(&mut self, is_system: bool) {
+            self.expanded_dialog.open = true;
+            self.expanded_dialog.is_system = is_system;
+        }
+
+        fn open_resolve_dialog(&mut self) {
+            self.resolve_dialog.open = true;
+        }
+
+        /// Scans on a worker thread, reusing the last report while the combined PATH is
+        /// unchanged unless `force` is set.
+        fn open_shadow_dialog(&mut self, ctx: &egui::Context, force: bool) {
+            self.shadow_dialog.open = true;
+            self.start_shadow_scan(ctx, force);
+        }
+
+        /// PATH as a newly started process sees it: System entries, then User entries.
+        fn effective_entries(&self) -> Vec<(bool, String)> {
+            self.path_entries(true)
+                .into_iter()
+                .map(|entry| (true, entry))
+                .chain(self.path_entries(false).into_iter().map(|entry| (false, entry)))
+                .collect()
+        }
+
+        /// Scans the effective PATH in the background unless the cached results already
+        /// cover it. Shared by the Shadowing Report and the Effective PATH view.
+        fn start_shadow_scan(&mut self, ctx: &egui::Context, force: bool) {
+            let entries = self.effective_entries();
+            let dialog = &mut self.shadow_dialog;
+            if !force && dialog.worker.is_none() && dialog.scanned == entries {
+                return;
+            }
+
+            dialog.cancel.store(true, Ordering::Relaxed);
+            dialog.cancel = Arc::new(AtomicBool::new(false));
+            dialog.cancelled = false;
+            dialog.scanned = entries.clone();
+            dialog.results.clear();
+            let ctx = ctx.clone();
+            let cancel = Arc::clone(&dialog.cancel);
+            dialog.worker = thread::Builder::new()
+                .name("shadow-scan".to_string())
+                .spawn(move || {
+                    let results = shadowed_executables(&entries, &cancel);
+                    ctx.request_repaint();
+                    results
+                })
+                .ok();
+        }
+
+        fn draw_shadow_dialog(&mut self, ctx: &egui::Context) {
+            if !self.shadow_dialog.open {
+                return;
+            }
+
+            self.poll_shadow_scan();
+
+            let mut open = self.shadow_dialog.open;
+            let mut rescan = false;
+            let mut cancel = false;
+
+            egui::Window::new(tr(Text::ShadowTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([760.0, 440.0])
+                .show(ctx, |ui| {
+                    let dialog = &self.shadow_dialog;
+                    ui.horizontal(|ui| {
+                        if dialog.worker.is_some() {
+                            ui.spinner();
+                            ui.label(tr(Text::ShadowScanning));
+                            if ui.button(tr(Text::Cancel)).clicked() {
+                                cancel = true;
+                            }
+                        } else if dialog.cancelled {
+                            ui.label(RichText::new(tr(Text::ShadowCancelled)).color(WARNING_COLOR));
+                        } else {
+                            ui.label(trf(Text::ShadowCount, &[&dialog.results.len()]));
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .add_enabled(
+                                    dialog.worker.is_none(),
+                                    egui::Button::new(tr(Text::Rescan)),
+                                )
+                                .on_disabled_hover_text(tr(Text::ShadowRunning))
+                                .clicked()
+                            {
+                                rescan = true;
+                            }
+                        });
+                    });
+                    ui.label(
+                        RichText::new(tr(Text::ShadowOrder))
+                            .small()
+                            .color(Color32::from_gray(170)),
+                    );
+                    ui.add_space(6.0);
+
+                    ScrollArea::vertical()
+                        .id_source("shadow_results")
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            for shadowed in &dialog.results {
+                                ui.label(RichText::new(&shadowed.name).strong());
+                                for (n, found) in shadowed.providers.iter().enumerate() {
+                                    let scope = scope_label(found.is_system);
+                                    let text = trf(
+                                        Text::ShadowProvider,
+                                        &[&found.file.display(), &scope, &found.entry],
+                                    );
+                                    if n == 0 {
+                                        ui.label(RichText::new(text).color(ADDED_COLOR));
+                                    } else {
+                                        ui.label(
+                                            RichText::new(trf(Text::ShadowShadowed, &[&text]))
+                                                .color(Color32::from_gray(150)),
+                                        );
+                                    }
+                                }
+                                ui.add_space(4.0);
+                            }
+                        });
+                });
+
+            open &= !dialog_keys(ctx).1;
+            self.shadow_dialog.open = open;
+            if cancel || !open {
+                self.cancel_shadow_scan();
+            }
+            if rescan {
+                self.open_shadow_dialog(ctx, true);
+            }
+            if self.shadow_dialog.worker.is_some() {
+                ctx.request_repaint_after(Duration::from_millis(200));
+            }
+        }
+
+        fn poll_shadow_scan(&mut self) {
+            if self.shadow_dialog.worker.as_ref().is_some_and(JoinHandle::is_finished) {
+                if let Some(worker) = self.shadow_dialog.worker.take() {
+                    self.shadow_dialog.results = worker.join().unwrap_or_default();
+                }
+            }
+        }
+
+        /// Read-only list of the merged PATH in search order, with the commands each entry
+        /// loses to (or takes from) another entry according to the shadowing scan.
+        fn draw_effective_view(&mut self, ui: &mut egui::Ui) {
+            self.poll_shadow_scan();
+            let ctx = ui.ctx().clone();
+            let entries = self.effective_entries();
+            let dirty = self.has_unsaved_changes();
+            let scan = &self.shadow_dialog;
+            let mut hides: HashMap<(bool, &str), Vec<String>> = HashMap::new();
+            let mut hidden: HashMap<(bool, &str), Vec<String>> = HashMap::new();
+            for shadowed in &scan.results {
+                let Some((first, rest)) = shadowed.providers.split_first() else {
+                    continue;
+                };
+                hides
+                    .entry((first.is_system, first.entry.as_str()))
+                    .or_default()
+                    .push(shadowed.name.clone());
+                for found in rest {
+                    hidden
+                        .entry((found.is_system, found.entry.as_str()))
+                        .or_default()
+                        .push(trf(Text::HiddenFrom, &[&shadowed.name, &first.entry]));
+                }
+            }
+
+            let mut rescan = false;
+            ui.horizontal(|ui| {
+                ui.heading(tr(Text::EffectivePath));
+                ui.label(trf(Text::EntryCount, &[&group_thousands(entries.len())]));
+                if dirty {
+                    ui.label(RichText::new(tr(Text::IncludesUnsaved)).color(WARNING_COLOR));
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if scan.worker.is_some() {
+                        ui.spinner();
+                        ui.label(tr(Text::CheckingShadowed));
+                    } else if ui
+                        .button(tr(Text::Rescan))
+                        .on_hover_text(tr(Text::RescanHint))
+                        .clicked()
+                    {
+                        rescan = true;
+                    }
+                    if scan.scanned != entries && scan.worker.is_none() {
+                        let stale = RichText::new(tr(Text::ShadowStale));
+                        ui.label(stale.color(WARNING_COLOR));
+                    }
+                });
+            });
+            ui.label(
+                RichText::new(tr(Text::EffectiveHint))
+                    .small()
+                    .color(Color32::from_gray(170)),
+            );
+            ui.add_space(6.0);
+
+            ScrollArea::vertical()
+                .id_source("effective_path")
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    egui::Grid::new("effective_path_grid")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (n, (is_system, entry)) in entries.iter().enumerate() {
+                                ui.label(RichText::new(format!("{}", n + 1)).weak());
+                                ui.label(scope_label(*is_system));
+                                let expanded = expand_env_vars(entry);
+                                let response = ui.label(RichText::new(entry).monospace());
+                                if expanded != *entry {
+                                    response.on_hover_text(expanded);
+                                }
+                                ui.horizontal(|ui| {
+                                    let key = (*is_system, entry.as_str());
+                                    if let Some(names) = hidden.get(&key) {
+                                        ui.label(
+                                            RichText::new(trf(Text::HiddenCount, &[&names.len()]))
+                                                .color(WARNING_COLOR),
+                                        )
+                                        .on_hover_text(trf(Text::HiddenList, &[&names.join("\n")]));
+                                    }
+                                    if let Some(names) = hides.get(&key) {
+                                        ui.label(
+                                            RichText::new(trf(Text::ShadowsCount, &[&names.len()]))
+                                                .color(ADDED_COLOR),
+                                        )
+                                        .on_hover_text(
+                                            trf(Text::ShadowsList, &[&names.join("\n")]),
+                                        );
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            if rescan {
+                self.start_shadow_scan(&ctx, true);
+            }
+            if self.shadow_dialog.worker.is_some() {
+                ctx.request_repaint_after(Duration::from_millis(200));
+            }
+        }
+
+        /// Stops a running scan and forgets what it covered, so the next open starts over.
+        fn cancel_shadow_scan(&mut self) {
+            let dialog = &mut self.shadow_dialog;
+            if dialog.worker.take().is_none() {
+                return;
+            }
+            dialog.cancel.store(true, Ordering::Relaxed);
+            dialog.cancelled = true;
+            dialog.scanned.clear();
+        }
+
+        fn run_resolve(&mut self) {
+            let name = self.resolve_dialog.input.trim().to_string();
+            self.resolve_dialog.results.clear();
+            self.resolve_dialog.searched = false;
+            if name.is_empty() {
+                return;
+            }
+
+            // Windows builds a process PATH as System followed by User, so walk them in that order.
+            let system = self.path_entries(true);
+            let user = self.path_entries(false);
+            let entries = system
+                .iter()
+                .map(|p| (true, p.as_str()))
+                .chain(user.iter().map(|p| (false, p.as_str())));
+            self.resolve_dialog.results = resolve_executable(&name, entries);
+            self.resolve_dialog.searched = true;
+            self.status = match self.resolve_dialog.results.first() {
+                Some(found) => trf(Text::ResolvesTo, &[&name, &found.file.display()]),
+                None => trf(Text::NotOnPath, &[&name]),
+            };
+        }
+
+        fn store(&self, is_system: bool) -> &PathStore {
+            if is_system {
+                &self.system
+            } else {
+                &self.user
+            }
+        }
+
+        fn store_mut(&mut self, is_system: bool) -> &mut PathStore {
+            if is_system {
+                &mut self.system
+            } else {
+                &mut self.user
+            }
+        }
+
+        fn remove_selected(&mut self, is_system: bool) {
+            let store = self.store(is_system);
+            if store.selected.is_empty() {
+                return;
+            }
+            let remaining = store
+                .parts
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, part)| {
+                    if store.selected.contains(&idx) {
+                        None
+                    } else {
+                        Some(part.clone())
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !confirm_critical_removal(&store.parts, &remaining, &self.settings.critical_paths) {
+                return;
+            }
+
+            let dropped = store
+                .selected
+                .iter()
+                .filter_map(|&idx| store.parts.get(idx).cloned())
+                .collect::<Vec<_>>();
+            let store = self.store_mut(is_system);
+            store.checkpoint();
+            let before = store.parts.len();
+            let selected = store.selected.clone();
+            store.edit_entries(|entries| {
+                let mut idx = 0;
+                entries.retain(|_| {
+                    let keep = !selected.contains(&idx);
+                    idx += 1;
+                    keep
+                });
+            });
+            let removed = before.saturating_sub(store.parts.len());
+            store.selected.clear();
+            self.status = trf(
+                Text::RemovedEntries,
+                &[&removed, &self.panel_title(is_system)],
+            );
+            self.offer_note_cleanup(&dropped);
+        }
+
+        fn remove_entry(&mut self, is_system: bool, idx: usize) {
+            let critical = self.settings.critical_paths.clone();
+            let store = self.store_mut(is_system);
+            if idx >= store.parts.len() {
+                return;
+            }
+            let mut remaining = store.parts.clone();
+            remaining.remove(idx);
+            if !confirm_critical_removal(&store.parts, &remaining, &critical) {
+                return;
+            }
+            store.checkpoint();
+            let (removed, _) = store.edit_entries(|entries| entries.remove(idx));
+            store.selected = store
+                .selected
+                .iter()
+                .filter(|&&sel| sel != idx)
+                .map(|&sel| if sel > idx { sel - 1 } else { sel })
+                .collect();
+            self.status = trf(
+                Text::RemovedEntry,
+                &[&removed, &self.panel_title(is_system)],
+            );
+            self.offer_note_cleanup(&[removed]);
+        }
+
+        /// Asks whether to delete the notes of removed entries that are now in neither scope.
+        fn offer_note_cleanup(&mut self, removed: &[String]) {
+            let present = self
+                .user
+                .parts
+                .iter()
+                .chain(&self.system.parts)
+                .map(|p| normalize_for_compare(p))
+                .collect::<HashSet<_>>();
+            let orphaned = removed
+                .iter()
+                .map(|p| normalize_for_compare(p))
+                .filter(|key| self.notes.contains_key(key) && !present.contains(key))
+                .collect::<BTreeSet<_>>();
+            if orphaned.is_empty() {
+                return;
+            }
+            let listing = orphaned
+                .iter()
+                .map(|key| format!("{key}: {}", self.notes[key]))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let delete = matches!(
+                MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title(tr(Text::DeleteNotesTitle))
+                    .set_description(trf(Text::DeleteNotesBody, &[&listing]))
+                    .set_buttons(MessageButtons::YesNo)
+                    .show(),
+                MessageDialogResult::Yes
+            );
+            if delete {
+                for key in &orphaned {
+                    self.notes.remove(key);
+                }
+            }
+        }
+
+        fn draw_note_dialog(&mut self, ctx: &egui::Context) {
+            if !self.note_dialog.open {
+                return;
+            }
+
+            let mut open = self.note_dialog.open;
+            let mut save = false;
+            let mut cancelled = false;
+
+            egui::Window::new(tr(Text::NoteTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let dialog = &mut self.note_dialog;
+                    ui.label(RichText::new(&dialog.entry).monospace());
+                    ui.add(
+                        TextEdit::singleline(&mut dialog.text)
+                            .hint_text(tr(Text::NoteHint))
+                            .desired_width(420.0),
+                    );
+                    ui.label(
+                        RichText::new(tr(Text::NoteEmptyHint))
+                            .small()
+                            .color(Color32::from_gray(170)),
+                    );
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Save)).clicked() {
+                            save = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            save |= submit;
+            cancelled |= dismiss;
+            self.note_dialog.open = open && !save && !cancelled;
+            if save {
+                let key = normalize_for_compare(&self.note_dialog.entry);
+                // Tabs and line breaks would break the one-line-per-note storage format.
+                let note = self
+                    .note_dialog
+                    .text
+                    .replace(['\t', '\r', '\n'], " ")
+                    .trim()
+                    .to_string();
+                if note.is_empty() {
+                    self.notes.remove(&key);
+                    self.status = tr(Text::NoteRemoved).to_string();
+                } else {
+                    self.notes.insert(key, note);
+                    self.status = tr(Text::NoteSaved).to_string();
+                }
+            }
+        }
+
+        fn draw_executables_dialog(&mut self, ctx: &egui::Context) {
+            if !self.executables_dialog.open {
+                return;
+            }
+
+            let mut open = self.executables_dialog.open;
+            let mut refresh = false;
+            let mut close = false;
+            let mut copied: Option<String> = None;
+            let scan = self.scanner.get(&self.executables_dialog.expanded, ctx);
+
+            egui::Window::new(tr(Text::ExecutablesTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([460.0, 360.0])
+                .show(ctx, |ui| {
+                    let dialog = &mut self.executables_dialog;
+                    ui.label(RichText::new(&dialog.entry).monospace());
+                    if dialog.expanded != dialog.entry {
+                        ui.label(
+                            RichText::new(format!("= {}", dialog.expanded))
+                                .monospace()
+                                .small()
+                                .color(Color32::from_gray(170)),
+                        );
+                    }
+                    ui.add_space(4.0);
+                    match &scan {
+                        ScanState::Pending(started) if started.elapsed() >= SCAN_TIMEOUT => {
+                            ui.label(RichText::new(tr(Text::FolderTimedOut)).color(WARNING_COLOR));
+                        }
+                        ScanState::Pending(_) => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label(tr(Text::ScanningFolder));
+                            });
+                            ctx.request_repaint_after(Duration::from_millis(250));
+                        }
+                        ScanState::Missing => {
+                            ui.label(RichText::new(tr(Text::FolderUnreadable)).color(ERROR_COLOR));
+                        }
+                        ScanState::Done(found) if found.names.is_empty() => {
+                            ui.label(tr(Text::NoExecutables));
+                        }
+                        ScanState::Done(found) => {
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    TextEdit::singleline(&mut dialog.filter)
+                                        .hint_text(tr(Text::Filter))
+                                        .desired_width(200.0),
+                                );
+                                ui.label(trf(Text::ExecutableCount, &[&found.executables]));
+                            });
+                            let filter = dialog.filter.trim().to_lowercase();
+                            egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                                for name in &found.names {
+                                    if !filter.is_empty() && !name.to_lowercase().contains(&filter)
+                                    {
+                                        continue;
+                                    }
+                                    let label = ui
+                                        .add(
+                                            egui::Label::new(RichText::new(name).monospace())
+                                                .sense(egui::Sense::click()),
+                                        )
+                                        .on_hover_text(tr(Text::ClickToCopyPath));
+                                    if label.clicked() {
+                                        copied = Some(
+                                            Path::new(&dialog.expanded)
+                                                .join(name)
+                                                .display()
+                                                .to_string(),
+                                        );
+                                    }
+                                }
+                            });
+                        }
+                    }
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Close)).clicked() {
+                            close = true;
+                        }
+                        if ui
+                            .button(tr(Text::Refresh))
+                            .on_hover_text(tr(Text::RefreshHint))
+                            .clicked()
+                        {
+                            refresh = true;
+                        }
+                    });
+                });
+
+            self.executables_dialog.open = open && !close && !dialog_keys(ctx).1;
+            if refresh {
+                self.scanner.forget(&self.executables_dialog.expanded);
+                ctx.request_repaint();
+            }
+            if let Some(path) = copied {
+                ctx.output_mut(|o| o.copied_text = path.clone());
+                self.status = trf(Text::Copied, &[&path]);
+            }
+        }
+
+        fn run_entry_action(
+            &mut self,
+            ctx: &egui::Context,
+            is_system: bool,
+            idx: usize,
+            action: EntryAction,
+        ) {
+            let Some(entry) = self.store(is_system).parts.get(idx).cloned() else {
+                return;
+            };
+            match action {
+                EntryAction::OpenInExplorer => self.open_entry(&entry),
+                EntryAction::Copy => {
+                    ctx.output_mut(|o| o.copied_text = entry.clone());
+                    self.status = trf(Text::Copied, &[&entry]);
+                }
+                EntryAction::CopyExpanded => {
+                    let expanded = expand_env_vars(&entry);
+                    ctx.output_mut(|o| o.copied_text = expanded.clone());
+                    self.status = trf(Text::Copied, &[&expanded]);
+                }
+                EntryAction::CopyPowerShellSession | EntryAction::CopyPowerShellPersistent => {
+                    let persistent = action == EntryAction::CopyPowerShellPersistent;
+                    let name = self.store(is_system).var_name.clone();
+                    let snippet = powershell_snippet(&entry, &name, is_system, persistent);
+                    ctx.output_mut(|o| o.copied_text = snippet.clone());
+                    self.status = trf(Text::Copied, &[&snippet]);
+                }
+                EntryAction::Edit => self.open_edit_dialog(is_system, idx),
+                EntryAction::ToggleEnabled => {
+                    let enabled = self.store_mut(is_system).toggle_enabled(idx);
+                    let text = if enabled {
+                        Text::EnabledEntry
+                    } else {
+                        Text::DisabledEntry
+                    };
+                    self.status = trf(text, &[&entry, &self.panel_title(is_system)]);
+                }
+                EntryAction::MoveToPosition => {
+                    self.move_dialog = MoveDialogState {
+                        open: true,
+                        is_system,
+                        index: idx,
+                        input: (idx + 1).to_string(),
+                    };
+                }
+                EntryAction::Duplicate => {
+                    let store = self.store_mut(is_system);
+                    store.duplicate_entry(idx);
+                    store.selected.clear();
+                    store.selected.insert(idx + 1);
+                    store.cursor = Some(idx + 1);
+                    self.open_edit_dialog(is_system, idx + 1);
+                }
+                EntryAction::EditNote => {
+                    let text = self
+                        .notes
+                        .get(&normalize_for_compare(&entry))
+                        .cloned()
+                        .unwrap_or_default();
+                    self.note_dialog = NoteDialogState {
+                        open: true,
+                        entry,
+                        text,
+                    };
+                }
+                EntryAction::ListExecutables => {
+                    let expanded = self.store_mut(is_system).entry_info(idx).expanded.clone();
+                    self.executables_dialog = ExecutablesDialogState {
+                        open: true,
+                        entry,
+                        expanded,
+                        filter: String::new(),
+                    };
+                }
+                EntryAction::Remove => self.remove_entry(is_system, idx),
+                EntryAction::MoveToTop | EntryAction::MoveToBottom => {
+                    let store = self.store_mut(is_system);
+                    store.checkpoint();
+                    let target = store.edit_entries(|entries| {
+                        let entry = entries.remove(idx);
+                        let target = if action == EntryAction::MoveToTop {
+                            0
+                        } else {
+                            entries.len()
+                        };
+                        entries.insert(target, entry);
+                        target
+                    });
+                    store.selected.clear();
+                    store.selected.insert(target);
+                    self.status = trf(Text::Reordered, &[&self.panel_title(is_system)]);
+                }
+                EntryAction::MoveToOtherScope => self.move_to_other_scope(is_system, &[idx]),
+            }
+        }
+
+        fn open_entry(&mut self, entry: &str) {
+            let expanded = expand_env_vars(entry);
+            if !Path::new(&expanded).is_dir() {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title(tr(Text::FolderNotFoundTitle))
+                    .set_description(trf(Text::FolderNotFoundBody, &[&expanded]))
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+                return;
+            }
+            match open_in_explorer(&expanded) {
+                Ok(()) => self.status = trf(Text::Opened, &[&expanded]),
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::OpenFailed))
+                        .set_description(err.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn open_selected(&mut self, is_system: bool) {
+            let store = self.store(is_system);
+            let entries = store
+                .selected
+                .iter()
+                .filter_map(|&idx| store.parts.get(idx).cloned())
+                .collect::<Vec<_>>();
+            if entries.is_empty() {
+                self.status = tr(Text::SelectToOpen).to_string();
+                return;
+            }
+            if entries.len() == 1 {
+                self.open_entry(&entries[0]);
+                return;
+            }
+
+            let mut opened = 0;
+            let mut missing = Vec::new();
+            for entry in &entries {
+                let expanded = expand_env_vars(entry);
+                if Path::new(&expanded).is_dir() && open_in_explorer(&expanded).is_ok() {
+                    opened += 1;
+                } else {
+                    missing.push(expanded);
+                }
+            }
+            self.status = trf(Text::OpenedFolders, &[&opened]);
+            if !missing.is_empty() {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title(tr(Text::SomeNotOpenedTitle))
+                    .set_description(trf(Text::SomeNotOpenedBody, &[&missing.join("\n")]))
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+            }
+        }
+
+        fn move_selected_to_other_scope(&mut self, is_system: bool) {
+            let indices = self
+                .store(is_system)
+                .selected
+                .iter()
+                .copied()
+                .collect::<Vec<_>>();
+            if indices.is_empty() {
+                self.status = tr(Text::SelectToMove).to_string();
+                return;
+            }
+            self.move_to_other_scope(is_system, &indices);
+        }
+
+        fn move_to_other_scope(&mut self, is_system: bool, indices: &[usize]) {
+            let store = self.store_mut(is_system);
+            let moving = store
+                .entries()
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, _)| indices.contains(idx))
+                .map(|(_, entry)| entry)
+                .collect::<Vec<_>>();
+            if moving.is_empty() {
+                return;
+            }
+            store.checkpoint();
+            store.edit_entries(|entries| {
+                let mut idx = 0;
+                entries.retain(|_| {
+                    let keep = !indices.contains(&idx);
+                    idx += 1;
+                    keep
+                });
+            });
+            store.selected.clear();
+
+            let target = self.store_mut(!is_system);
+            target.checkpoint();
+            let mut existing = target
+                .parts
+                .iter()
+                .map(|p| normalize_for_compare(p))
+                .collect::<HashSet<_>>();
+            let added = target.edit_entries(|entries| {
+                let before = entries.len();
+                entries.extend(
+                    moving
+                        .iter()
+                        .filter(|(part, _)| existing.insert(normalize_for_compare(part)))
+                        .cloned(),
+                );
+                entries.len() - before
+            });
+            if added == 0 {
+                target.history.pop();
+            }
+
+            self.status = trf(
+                Text::MovedEntries,
+                &[&moving.len(), &self.panel_title(!is_system), &(moving.len() - added)],
+            );
+        }
+
+        fn remove_relative(&mut self, is_system: bool) {
+            let store = self.store_mut(is_system);
+            let before = store.parts.len();
+            if store.parts.iter().all(|part| is_absolute_entry(part)) {
+                self.status = trf(Text::NoRelative, &[&self.panel_title(is_system)]);
+                return;
+            }
+            store.checkpoint();
+            store.edit_entries(|entries| entries.retain(|(part, _)| is_absolute_entry(part)));
+            store.selected.clear();
+            let removed = before - store.parts.len();
+            self.status = trf(
+                Text::RemovedRelative,
+                &[&removed, &self.panel_title(is_system)],
+            );
+        }
+
+        fn make_entry_absolute(&mut self, is_system: bool, idx: usize) {
+            let Some(base) = FileDialog::new()
+                .set_title(tr(Text::ChooseBaseFolder))
+                .pick_folder()
+            else {
+                return;
+            };
+            let store = self.store_mut(is_system);
+            let Some(part) = store.parts.get(idx) else {
+                return;
+            };
+            let resolved = lexically_normalize(&base.join(part)).display().to_string();
+            store.checkpoint();
+            store.edit_entries(|entries| entries[idx].0.clone_from(&resolved));
+            self.status = trf(Text::ResolvedEntry, &[&resolved]);
+        }
+
+        fn move_selected(&mut self, is_system: bool, direction: i32) {
+            let store = self.store_mut(is_system);
+            if store.selected.is_empty() {
+                return;
+            }
+
+            let visible = store.visible_indices();
+            let before = store.parts.clone();
+            let selected = store.selected.clone();
+            let moved =
+                store.edit_entries(|entries| move_entries(entries, &selected, &visible, direction));
+            if store.parts == before {
+                return;
+            }
+            store.push_history(before);
+            store.selected = moved;
+            self.status = trf(Text::Reordered, &[&self.panel_title(is_system)]);
+        }
+
+        fn apply_dedupe(&mut self, is_system: bool) {
+            let prefer_tokens = self.settings.dedupe_prefer_tokens;
+            let store = self.store_mut(is_system);
+            let before = store.parts.len();
+            let keep = store.selection_keys();
+            store.checkpoint();
+            store.replace_parts(dedupe_with(&store.parts, prefer_tokens));
+            store.reselect(&keep);
+            let removed = before.saturating_sub(store.parts.len());
+            self.status = trf(
+                Text::DedupeRemoved,
+                &[&removed, &self.panel_title(is_system)],
+            );
+        }
+
+        fn apply_sort(&mut self, is_system: bool, order: SortOrder) {
+            let store = self.store_mut(is_system);
+            let keep = store.selection_keys();
+            store.checkpoint();
+            // The sorts are stable, so identical entries keep their order and their flags.
+            match order {
+                SortOrder::Ascending => sort_case_insensitive(&mut store.parts),
+                SortOrder::Descending => sort_descending(&mut store.parts),
+                SortOrder::ByLength => sort_by_length(&mut store.parts),
+                SortOrder::Natural => sort_natural(&mut store.parts),
+                SortOrder::Priority => sort_by_priority(&mut store.parts, &category_roots()),
+            }
+            store.reselect(&keep);
+            let order = order.label();
+            self.status = trf(Text::Sorted, &[&self.panel_title(is_system), &order]);
+        }
+
+        /// Rewrites the selected entries (or every entry when nothing is selected) and
+        /// returns how many actually changed.
+        fn rewrite_entries(&mut self, is_system: bool, rewrite: impl Fn(&str) -> String) -> usize {
+            let store = self.store_mut(is_system);
+            store.checkpoint();
+            let selected = store.selected.clone();
+            let changed = store.edit_entries(|entries| {
+                let mut changed = 0;
+                for (idx, (part, _)) in entries.iter_mut().enumerate() {
+                    if !selected.is_empty() && !selected.contains(&idx) {
+                        continue;
+                    }
+                    let updated = rewrite(part);
+                    if updated != *part {
+                        *part = updated;
+                        changed += 1;
+                    }
+                }
+                changed
+            });
+            if changed == 0 {
+                store.history.pop();
+            }
+            changed
+        }
+
+        fn apply_normalize_slashes(&mut self, is_system: bool) {
+            let changed = self.rewrite_entries(is_system, normalize_slashes);
+            self.status = trf(
+                Text::NormalizedSlashes,
+                &[&changed, &self.panel_title(is_system)],
+            );
+        }
+
+        fn apply_normalize_casing(&mut self, is_system: bool) {
+            let changed = self.rewrite_entries(is_system, normalize_drive_case);
+            self.status = trf(
+                Text::UppercasedDrives,
+                &[&changed, &self.panel_title(is_system)],
+            );
+        }
+
+        fn apply_trim_slashes(&mut self, is_system: bool) {
+            let changed = self.rewrite_entries(is_system, trim_trailing_slashes);
+            self.status = trf(
+                Text::TrimmedSlashes,
+                &[&changed, &self.panel_title(is_system)],
+            );
+        }
+
+        /// Trims every entry regardless of the selection; spaces inside a path are left alone.
+        fn apply_trim_whitespace(&mut self, is_system: bool) {
+            let store = self.store_mut(is_system);
+            let trimmed: Vec<String> =
+                store.parts.iter().map(|part| part.trim().to_string()).collect();
+            let changed = store.parts.iter().zip(&trimmed).filter(|(a, b)| a != b).count();
+            if changed > 0 {
+                store.checkpoint();
+                store.edit_entries(|entries| {
+                    for ((part, _), trimmed) in entries.iter_mut().zip(trimmed) {
+                        *part = trimmed;
+                    }
+                });
+            }
+            self.status = trf(
+                Text::TrimmedWhitespace,
+                &[&changed, &self.panel_title(is_system)],
+            );
+        }
+
+        fn apply_tokenize(&mut self, is_system: bool) {
+            let vars = token_vars(is_system);
+            let store = self.store(is_system);
+            let pending = store
+                .parts
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| store.selected.is_empty() || store.selected.contains(idx))
+                .filter(|(_, part)| tokenize_path(part, &vars) != **part)
+                .count();
+            if pending == 0 {
+                self.status = trf(Text::NothingToTokenize, &[&self.panel_title(is_system)]);
+                return;
+            }
+
+            let confirmed = matches!(
+                MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title(tr(Text::TokenizeTitle))
+                    .set_description(trf(Text::TokenizeBody, &[&pending]))
+                    .set_buttons(MessageButtons::OkCancel)
+                    .show(),
+                MessageDialogResult::Ok
+            );
+            if !confirmed {
+                return;
+            }
+
+            let changed = self.rewrite_entries(is_system, |part| tokenize_path(part, &vars));
+            self.status = trf(Text::Tokenized, &[&changed, &self.panel_title(is_system)]);
+        }
+
+        fn apply_expand_all(&mut self, is_system: bool) {
+            let confirmed = matches!(
+                MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title(tr(Text::ExpandTitle))
+                    .set_description(tr(Text::ExpandBody))
+                    .set_buttons(MessageButtons::OkCancel)
+                    .show(),
+                MessageDialogResult::Ok
+            );
+            if !confirmed {
+                return;
+            }
+
+            let changed = self.rewrite_entries(is_system, expand_env_vars);
+            let store = self.store_mut(is_system);
+            if !has_env_token(&store.raw_preview()) {
+                store.reg_type = REG_SZ;
+            }
+            self.status = trf(
+                Text::ExpandedEntries,
+                &[&changed, &self.panel_title(is_system)],
+            );
+        }
+
+        fn apply_undo(&mut self, is_system: bool) {
+            if self.store_mut(is_system).undo() {
+                self.status = trf(Text::Undid, &[&self.panel_title(is_system)]);
+            }
+        }
+
+        fn request_save_one(&mut self, is_system: bool) {
+            if !self.settings.confirm_diff {
+                self.save_one(is_system);
+                return;
+            }
+            self.save_confirm = SaveConfirmState {
+                open: true,
+                scopes: vec![is_system],
+                save_all: false,
+            };
+        }
+
+        fn request_save_all(&mut self) {
+            if !self.settings.confirm_diff {
+                self.save_all();
+                return;
+            }
+            let mut scopes = vec![false];
+            if self.is_admin {
+                scopes.push(true);
+            }
+            self.save_confirm = SaveConfirmState {
+                open: true,
+                scopes,
+                save_all: true,
+            };
+        }
+
+        fn draw_save_confirm_dialog(&mut self, ctx: &egui::Context) {
+            if !self.save_confirm.open {
+                return;
+            }
+
+            let mut open = self.save_confirm.open;
+            let mut confirmed = false;
+            let mut cancelled = false;
+
+            egui::Window::new(tr(Text::ConfirmSaveTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([820.0, 480.0])
+                .show(ctx, |ui| {
+                    if self.save_confirm.save_all {
+                        for is_system in [false, true] {
+                            let text = if is_system && !self.is_admin {
+                                RichText::new(self.save_summary(true)).color(WARNING_COLOR)
+                            } else {
+                                RichText::new(self.save_summary(is_system))
+                            };
+                            ui.label(text.strong());
+                        }
+                        ui.separator();
+                    }
+                    ScrollArea::vertical()
+                        .id_source("save_diff")
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            for &is_system in &self.save_confirm.scopes {
+                                let store = self.store(is_system);
+                                ui.heading(self.panel_title(is_system));
+                                if store.write_type() != store.saved_type {
+                                    ui.label(
+                                        RichText::new(trf(
+                                            Text::TypeChange,
+                                            &[
+                                                &reg_type_name(&store.saved_type),
+                                                &reg_type_name(&store.write_type()),
+                                            ],
+                                        ))
+                                        .color(WARNING_COLOR),
+                                    );
+                                }
+                                let diff =
+                                    diff_entries(&store.saved_parts, &store.enabled_parts());
+                                draw_diff(ui, &diff);
+                                ui.add_space(8.0);
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Save)).clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            confirmed |= submit;
+            cancelled |= dismiss;
+            self.save_confirm.open = open && !confirmed && !cancelled;
+            if confirmed {
+                if self.save_confirm.save_all {
+                    self.save_all();
+                } else if let Some(&is_system) = self.save_confirm.scopes.first() {
+                    self.save_one(is_system);
+                }
+            }
+        }
+
+        /// Re-reads the scope's value from its backend so the dialog diffs against what is
+        /// stored right now, not what was loaded.
+        fn open_changes_dialog(&mut self, is_system: bool) {
+            let name = self.store(is_system).var_name.clone();
+            let (live, error) = match path_backend(is_system, &name).read(is_system, &name) {
+                Ok((raw, _)) => (split_path(&raw), None),
+                Err(err) => (Vec::new(), Some(err.to_string())),
+            };
+            self.changes_dialog = ChangesDialogState {
+                open: true,
+                is_system,
+                live,
+                error,
+            };
+        }
+
+        fn draw_changes_dialog(&mut self, ctx: &egui::Context) {
+            if !self.changes_dialog.open {
+                return;
+            }
+
+            let mut open = self.changes_dialog.open;
+            let mut refresh = false;
+            let is_system = self.changes_dialog.is_system;
+            let title = trf(Text::ChangesTitle, &[&self.panel_title(is_system)]);
+            let current = self.store(is_system).enabled_parts();
+            let diff = diff_entries(&self.changes_dialog.live, &current);
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([820.0, 480.0])
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new(tr(Text::ChangesHint))
+                                .small()
+                                .color(Color32::from_gray(170)),
+                        );
+                        if ui.button(tr(Text::Refresh)).clicked() {
+                            refresh = true;
+                        }
+                    });
+                    if let Some(err) = &self.changes_dialog.error {
+                        ui.label(
+                            RichText::new(trf(Text::RegistryReadFailed, &[err])).color(ERROR_COLOR),
+                        );
+                    }
+                    ui.separator();
+                    ScrollArea::vertical()
+                        .id_source("live_diff")
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| draw_diff(ui, &diff));
+                });
+
+            open &= !dialog_keys(ctx).1;
+            self.changes_dialog.open = open;
+            if refresh {
+                self.open_changes_dialog(is_system);
+            }
+        }
+
+        fn save_one(&mut self, is_system: bool) {
+            if self.dry_run {
+                let line = self.describe_store_write(is_system);
+                self.report_dry_run(&[line]);
+                return;
+            }
+
+            if is_system && !self.is_admin {
+                if let Err(err) = self.write_system_elevated(false) {
+                    self.report_save_error(err);
+                }
+                return;
+            }
+            match self.write_path(is_system) {
+                Ok(()) => {
+                    let target = scope_label(is_system);
+                    self.status = trf(Text::SavedScope, &[&target]);
+                    self.notify_saved(trf(Text::SavedScopeToast, &[&target]));
+                }
+                Err(err) => self.report_save_error(err),
+            }
+        }
+
+        /// A save the user backed out of only updates the status; anything else is an error.
+        fn report_save_error(&mut self, err: Box<dyn Error>) {
+            if err.is::<SaveCancelled>() {
+                self.status = err.to_string();
+                return;
+            }
+            // An access-denied write is often the first sign that the startup check was wrong.
+            self.recheck_admin();
+            MessageDialog::new()
+                .set_level(MessageLevel::Error)
+                .set_title(tr(Text::SaveFailed))
+                .set_description(err.to_string())
+                .set_buttons(MessageButtons::Ok)
+                .show();
+        }
+
+        fn save_all(&mut self) {
+            if self.dry_run {
+                let mut lines = vec![self.describe_store_write(false)];
+                if self.is_admin {
+                    lines.push(self.describe_store_write(true));
+                }
+                self.report_dry_run(&lines);
+                return;
+            }
+
+            if let Err(err) = self.write_path(false) {
+                self.report_save_error(err);
+                return;
+            }
+
+            if self.is_admin {
+                if let Err(err) = self.write_path(true) {
+                    self.report_save_error(err);
+                    return;
+                }
+                self.status = tr(Text::SavedBoth).to_string();
+            } else {
+                self.status = tr(Text::SavedUserOnly).to_string();
+            }
+            self.notify_saved(trf(
+                Text::SavedAllToast,
+                &[&self.save_summary(false), &self.save_summary(true)],
+            ));
+        }
+
+        /// Success is a toast unless the user asked for a message box; failures never come
+        /// through here.
+        fn notify_saved(&mut self, text: String) {
+            if !self.settings.save_toasts {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title(tr(Text::SavedTitle))
+                    .set_description(text)
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+                return;
+            }
+            if self.toasts.len() == 3 {
+                self.toasts.pop_front();
+            }
+            self.toasts.push_back((text, Instant::now()));
+        }
+
+        fn draw_toasts(&mut self, ctx: &egui::Context) {
+            self.toasts.retain(|(_, shown)| shown.elapsed() < TOAST_DURATION);
+            let Some(oldest) = self.toasts.front().map(|(_, shown)| *shown) else {
+                return;
+            };
+            let mut dismissed = None;
+            egui::Area::new(egui::Id::new("toasts"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -40.0])
+                .order(egui::Order::Foreground)
+                .show(ctx, |ui| {
+                    for (idx, (text, _)) in self.toasts.iter().enumerate() {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.set_max_width(360.0);
+                            let label = ui.add(
+                                egui::Label::new(RichText::new(text).color(ADDED_COLOR))
+                                    .sense(egui::Sense::click()),
+                            );
+                            if label.on_hover_text(tr(Text::ClickToDismiss)).clicked() {
+                                dismissed = Some(idx);
+                            }
+                        });
+                    }
+                });
+            if let Some(idx) = dismissed {
+                self.toasts.remove(idx);
+            }
+            ctx.request_repaint_after(TOAST_DURATION.saturating_sub(oldest.elapsed()));
+        }
+
+        /// One line describing what Save ALL writes (or skips) for a scope.
+        fn save_summary(&self, is_system: bool) -> String {
+            let title = self.panel_title(is_system);
+            if is_system && !self.is_admin {
+                return trf(Text::SummarySkipped, &[&title]);
+            }
+            let store = self.store(is_system);
+            trf(
+                Text::SummaryEntries,
+                &[&title, &store.enabled_parts().len(), &reg_type_name(&store.write_type())],
+            )
+        }
+
+        /// Logs each edit made this frame with the status text describing it and the entry
+        /// counts before and after.
+        fn flush_action_log(&mut self) {
+            let mut edited = false;
+            for is_system in [false, true] {
+                let Some(before) = self.store_mut(is_system).pending_log.take() else {
+                    continue;
+                };
+                edited = true;
+                log_event(&format!(
+                    "{}: {} ({before} -> {} entries)",
+                    self.panel_title(is_system),
+                    self.status,
+                    self.store(is_system).parts.len()
+                ));
+            }
+
+            if edited || self.status != self.last_status {
+                if self.history_log.len() == HISTORY_LIMIT {
+                    self.history_log.pop_front();
+                }
+                self.history_log.push_back(format!("{}  {}", clock_time(), self.status));
+                self.last_status = self.status.clone();
+            }
+        }
+
+        fn draw_history_panel(&mut self, ctx: &egui::Context) {
+            if !self.history_open {
+                return;
+            }
+
+            let mut clear = false;
+            egui::TopBottomPanel::bottom("history")
+                .resizable(true)
+                .default_height(160.0)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(tr(Text::SessionHistory)).strong());
+                        ui.label(
+                            RichText::new(trf(Text::MessageCount, &[&self.history_log.len()]))
+                                .small()
+                                .color(Color32::from_gray(170)),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button(tr(Text::Clear)).clicked() {
+                                clear = true;
+                            }
+                        });
+                    });
+                    ScrollArea::vertical()
+                        .id_source("history_log")
+                        .auto_shrink([false, false])
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for line in &self.history_log {
+                                ui.label(RichText::new(line).small().monospace());
+                            }
+                        });
+                });
+
+            if clear {
+                self.history_log.clear();
+            }
+        }
+
+        fn describe_store_write(&self, is_system: bool) -> String {
+            let store = self.store(is_system);
+            describe_write(
+                is_system,
+                &store.var_name,
+                Some(&store.raw_preview()),
+                &store.write_type(),
+            )
+        }
+
+        /// The GUI has no console, so the preview goes to the log, the status bar and a dialog;
+        /// only the CLI prints to stdout.
+        fn report_dry_run(&mut self, lines: &[String]) {
+            for line in lines {
+                log_event(&format!("Dry run: {line}"));
+            }
+            self.status = trf(Text::DryRunStatus, &[&lines.len()]);
+            MessageDialog::new()
+                .set_level(MessageLevel::Info)
+                .set_title(tr(Text::DryRunTitle))
+                .set_description(lines.join("\n\n"))
+                .set_buttons(MessageButtons::Ok)
+                .show();
+        }
+
+        fn write_path(&mut self, is_system: bool) -> Result<(), Box<dyn Error>> {
+            let store = self.store(is_system);
+            let name = store.var_name.clone();
+            let is_path = store.is_path();
+            let value = store.raw_preview();
+
+            let enabled = store.enabled_parts();
+            let critical = &self.settings.critical_paths;
+            if is_path && !confirm_critical_removal(&store.saved_parts, &enabled, critical) {
+                return Err(Box::new(SaveCancelled));
+            }
+            if is_system && is_path && !confirm_missing_system32(&enabled) {
+                return Err(Box::new(SaveCancelled));
+            }
+
+            let backend = path_backend(is_system, &name);
+            if !backend.is_registry() {
+                save_store(backend.as_ref(), is_system, self.store_mut(is_system))?;
+                log_event(&format!(
+                    "Saved {} ({} entries)",
+                    self.panel_title(is_system),
+                    self.store(is_system).parts.len()
+                ));
+                return Ok(());
+            }
+
+            // Like the elevated helper, refuse to write a value that could not be backed up.
+            if self.settings.backup_on_save {
+                backup_reg_value(is_system, &name, self.settings.backup_keep).map_err(|err| {
+                    log_event(&format!("Backup before saving {name} failed: {err}"));
+                    trf(Text::BackupFailed, &[&err])
+                })?;
+            }
+
+            let store = self.store_mut(is_system);
+            save_store(backend.as_ref(), is_system, store)?;
+            store.last_write = key_last_write(is_system);
+            log_event(&format!(
+                "Saved {} ({} entries)",
+                self.panel_title(is_system),
+                self.store(is_system).parts.len()
+            ));
+
+            self.broadcast();
+
+            // This process's environment is ours, not that of the profile being edited.
+            if self.hive.is_some() {
+                return Ok(());
+            }
+            if !is_path {
+                env::set_var(&name, value);
+            } else if is_system {
+                env::set_var("PATH", self.merged_path());
+            } else {
+                env::set_var("PATH", value);
+            }
+
+            Ok(())
+        }
+
+        /// Saves System PATH through an elevated copy of this exe instead of restarting the
+        /// whole editor as Administrator, so unsaved User edits survive. The helper runs on a
+        /// worker thread so the window keeps repainting; `poll_elevated_save` finishes the save.
+        fn write_system_elevated(&mut self, then_exit: bool) -> Result<(), Box<dyn Error>> {
+            if self.elevated_save.is_some() {
+                return Err(tr(Text::HelperBusy).into());
+            }
+            let store = self.store(true);
+            let name = store.var_name.clone();
+            let is_path = store.is_path();
+            let enabled = store.enabled_parts();
+            let critical = &self.settings.critical_paths;
+            if is_path && !confirm_critical_removal(&store.saved_parts, &enabled, critical) {
+                return Err(Box::new(SaveCancelled));
+            }
+            if is_path && !confirm_missing_system32(&enabled) {
+                return Err(Box::new(SaveCancelled));
+            }
+
+            let request = SystemWriteRequest {
+                name: name.clone(),
+                value: store.raw_preview(),
+                expand: store.write_type() == REG_EXPAND_SZ,
+                backup_keep: self.settings.backup_on_save.then_some(self.settings.backup_keep),
+            };
+            let file = env::temp_dir().join(format!("path_editor_apply_{}.json", process::id()));
+            let payload = serde_json::to_vec(&request)?;
+            fs::write(&file, &payload)?;
+            let digest = sha256_hex(&payload);
+            let request_file = file.clone();
+            let spawned = thread::Builder::new()
+                .name("elevated-save".to_string())
+                .spawn(move || {
+                    // ShellExecuteEx may need COM for the runas verb.
+                    let com = unsafe {
+                        CoInitializeEx(None, COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE)
+                    }
+                    .is_ok();
+                    let result = run_elevated_helper(&file, &digest).map_err(|err| err.to_string());
+                    let _ = fs::remove_file(&file);
+                    if com {
+                        unsafe { CoUninitialize() };
+                    }
+                    result
+                });
+            if spawned.is_err() {
+                let _ = fs::remove_file(&request_file);
+            }
+
+            self.elevated_save = Some(ElevatedSave {
+                worker: spawned?,
+                name,
+                value: request.value,
+                enabled,
+                is_path,
+                then_exit,
+            });
+            self.status = tr(Text::HelperWaiting).to_string();
+            Ok(())
+        }
+
+        /// Finishes a System save once the elevated helper has exited: reads the value back,
+        /// reports the outcome and closes the window when the save was part of exiting.
+        fn poll_elevated_save(&mut self, ctx: &egui::Context) {
+            let Some(save) = &self.elevated_save else {
+                return;
+            };
+            if !save.worker.is_finished() {
+                ctx.request_repaint_after(Duration::from_millis(250));
+                return;
+            }
+            let Some(save) = self.elevated_save.take() else {
+                return;
+            };
+            let result = save
+                .worker
+                .join()
+                .unwrap_or_else(|_| Err(tr(Text::HelperPanicked).to_string()));
+            if let Err(err) = result {
+                self.report_save_error(err.into());
+                return;
+            }
+
+            // Read back what the helper wrote rather than trusting the request.
+            let fresh = load_store(true, &save.name);
+            let store = self.store_mut(true);
+            // Left alone if the panel moved on to another variable while the helper ran.
+            if store.var_name.eq_ignore_ascii_case(&save.name) {
+                if fresh.parts == save.enabled {
+                    // Edits made while the helper ran stay unsaved.
+                    store.saved_parts = fresh.parts;
+                    store.reg_type = fresh.reg_type.clone();
+                    store.saved_type = fresh.reg_type;
+                    store.last_write = key_last_write(true);
+                } else {
+                    let filter = std::mem::take(&mut store.filter);
+                    *store = fresh;
+                    store.filter = filter;
+                }
+            }
+            log_event(&format!(
+                "Saved {} through the elevated helper ({} entries)",
+                self.panel_title(true),
+                save.enabled.len()
+            ));
+
+            if self.hive.is_none() {
+                if save.is_path {
+                    env::set_var("PATH", self.merged_path());
+                } else {
+                    env::set_var(&save.name, &save.value);
+                }
+            }
+            self.status = trf(Text::SavedScope, &[&scope_label(true)]);
+            self.notify_saved(trf(Text::SavedScopeToast, &[&scope_label(true)]));
+            if save.then_exit {
+                self.allow_close = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+
+        /// The registry write has already succeeded by the time this runs, so a failed
+        /// broadcast only raises a warning in the status bar with a retry.
+        fn broadcast(&mut self) {
+            self.broadcast_failed = !broadcast_env_change();
+        }
+
+        fn retry_broadcast(&mut self) {
+            self.broadcast();
+            self.status = if self.broadcast_failed {
+                tr(Text::BroadcastFailedAgain).to_string()
+            } else {
+                tr(Text::BroadcastSent).to_string()
+            };
+        }
+
+        fn reload(&mut self) {
+            if self.has_unsaved_changes() {
+                let confirmed = matches!(
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Warning)
+                        .set_title(tr(Text::DiscardTitle))
+                        .set_description(tr(Text::ReloadDiscardBody))
+                        .set_buttons(MessageButtons::OkCancel)
+                        .show(),
+                    MessageDialogResult::Ok
+                );
+                if !confirmed {
+                    return;
+                }
+            }
+
+            for is_system in [false, true] {
+                let store = self.store_mut(is_system);
+                let filter = std::mem::take(&mut store.filter);
+                *store = load_store(is_system, &store.var_name);
+                store.filter = filter;
+            }
+            self.external_change = false;
+            self.status = tr(Text::Reloaded).to_string();
+            self.recheck_admin();
+        }
+
+        /// Re-reads `IsUserAnAdmin` so the System save buttons and the Restart as Admin button
+        /// follow the real token instead of the check made at startup. Returns whether it
+        /// changed.
+        fn recheck_admin(&mut self) -> bool {
+            let now = is_admin();
+            if now == self.is_admin {
+                return false;
+            }
+            self.is_admin = now;
+            let state = if now { "now running as Administrator" } else { "no longer elevated" };
+            log_event(&format!("Privileges changed: {state}"));
+            let text = if now { Text::NowAdmin } else { Text::NoLongerAdmin };
+            self.status = tr(text).to_string();
+            true
+        }
+
+        /// True when the registry no longer matches what this editor last read or wrote,
+        /// which filters out notifications caused by our own saves.
+        fn registry_differs(&self) -> bool {
+            [false, true].into_iter().any(|is_system| {
+                let store = self.store(is_system);
+                let fresh = load_store(is_system, &store.var_name);
+                fresh.parts != store.saved_parts || fresh.reg_type != store.saved_type
+            })
+        }
+
+        fn poll_external_changes(&mut self) {
+            let changed = self.watcher.as_ref().is_some_and(RegistryWatcher::take_change);
+            if changed && self.registry_differs() {
+                self.external_change = true;
+            }
+        }
+
+        fn draw_external_change_banner(&mut self, ctx: &egui::Context) {
+            if !self.external_change {
+                return;
+            }
+
+            egui::TopBottomPanel::top("external_change").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(tr(Text::ExternalChange))
+                            .color(WARNING_COLOR)
+                            .strong(),
+                    );
+                    if self.has_unsaved_changes() {
+                        ui.label(
+                            RichText::new(tr(Text::ReloadDiscards))
+                                .small()
+                                .color(Color32::from_gray(170)),
+                        );
+                    }
+                    if ui.button(tr(Text::Reload)).clicked() {
+                        self.reload();
+                    }
+                    if ui.button(tr(Text::Dismiss)).clicked() {
+                        self.external_change = false;
+                    }
+                });
+            });
+        }
+
+        fn draw_clipboard_banner(&mut self, ctx: &egui::Context) {
+            let Some(folder) = self.clipboard_offer.clone() else {
+                return;
+            };
+
+            egui::TopBottomPanel::top("clipboard_offer").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(trf(Text::ClipboardOffer, &[&folder])).strong());
+                    ui.label(
+                        RichText::new(tr(Text::FoundOnClipboard))
+                            .small()
+                            .color(Color32::from_gray(170)),
+                    );
+                    if ui.button(tr(Text::Add)).clicked() {
+                        self.add_folders(false, &[PathBuf::from(&folder)]);
+                        self.clipboard_offer = None;
+                    }
+                    if ui.button(tr(Text::Dismiss)).clicked() {
+                        self.clipboard_offer = None;
+                    }
+                });
+            });
+        }
+
+        fn draw_session_changes_banner(&mut self, ctx: &egui::Context) {
+            if !self.session_changes.banner {
+                return;
+            }
+
+            let text = match self.session_changes.scopes.as_slice() {
+                [(is_system, ..)] => trf(Text::SessionChangedOne, &[&scope_label(*is_system)]),
+                _ => tr(Text::SessionChangedBoth).to_string(),
+            };
+            egui::TopBottomPanel::top("session_changes").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(text).color(WARNING_COLOR).strong());
+                    if ui.button(tr(Text::ViewChanges)).clicked() {
+                        self.session_changes.open = true;
+                    }
+                    if ui.button(tr(Text::Dismiss)).clicked() {
+                        self.session_changes.banner = false;
+                    }
+                });
+            });
+        }
+
+        fn draw_session_changes_dialog(&mut self, ctx: &egui::Context) {
+            if !self.session_changes.open {
+                return;
+            }
+
+            let mut open = self.session_changes.open;
+            egui::Window::new(tr(Text::SessionChangesTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([820.0, 480.0])
+                .show(ctx, |ui| {
+                    ScrollArea::vertical()
+                        .id_source("session_diff")
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            for (is_system, file, backup, current) in &self.session_changes.scopes {
+                                ui.heading(trf(Text::ScopePath, &[&scope_label(*is_system)]));
+                                ui.label(
+                                    RichText::new(trf(Text::SessionChangesSource, &[file]))
+                                        .small()
+                                        .color(Color32::from_gray(170)),
+                                );
+                                draw_diff(ui, &diff_entries(backup, current));
+                                ui.add_space(8.0);
+                            }
+                        });
+                });
+            open &= !dialog_keys(ctx).1;
+            self.session_changes.open = open;
+        }
+
+        fn set_theme(&mut self, ctx: &egui::Context, theme: Theme) {
+            self.settings.theme = theme;
+            apply_style(ctx, theme.is_light(self.system_light));
+        }
+
+        fn open_settings_dialog(&mut self) {
+            self.settings_dialog = SettingsDialogState {
+                open: true,
+                draft: self.settings.clone(),
+                backup_keep_text: self.settings.backup_keep.to_string(),
+                backup_hours_text: self.settings.startup_backup_hours.to_string(),
+                critical_text: self.settings.critical_paths.join("\n"),
+            };
+        }
+
+        fn apply_settings(&mut self, ctx: &egui::Context, settings: Settings) {
+            if settings.theme != self.settings.theme {
+                self.set_theme(ctx, settings.theme);
+            }
+            if settings.dry_run_default != self.settings.dry_run_default {
+                self.dry_run = settings.dry_run_default;
+            }
+            LOGGING_ENABLED.store(settings.logging, Ordering::Relaxed);
+            GERMAN_UI.store(settings.language.resolve() == Language::German, Ordering::Relaxed);
+            if settings.language != self.settings.language {
+                // Cached entry problems are worded in the old language.
+                self.user.info_cache.clear();
+                self.system.info_cache.clear();
+            }
+            self.settings = settings;
+            self.status = tr(Text::SettingsSaved).to_string();
+        }
+
+        fn draw_settings_dialog(&mut self, ctx: &egui::Context) {
+            if !self.settings_dialog.open {
+                return;
+            }
+
+            let mut open = self.settings_dialog.open;
+            let mut apply = None;
+            let mut cancelled = false;
+
+            egui::Window::new(tr(Text::SettingsTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let SettingsDialogState {
+                        draft,
+                        backup_keep_text,
+                        backup_hours_text,
+                        critical_text,
+                        ..
+                    } = &mut self.settings_dialog;
+
+                    egui::Grid::new("settings_grid")
+                        .num_columns(2)
+                        .spacing([16.0, 8.0])
+                        .show(ui, |ui| {
+                            ui.label(tr(Text::ThemeLabel));
+                            egui::ComboBox::from_id_source("settings_theme")
+                                .selected_text(draft.theme.label())
+                                .show_ui(ui, |ui| {
+                                    for option in Theme::ALL {
+                                        ui.selectable_value(
+                                            &mut draft.theme,
+                                            option,
+                                            option.label(),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+
+                            ui.label(tr(Text::LanguageLabel));
+                            egui::ComboBox::from_id_source("settings_language")
+                                .selected_text(draft.language.label())
+                                .show_ui(ui, |ui| {
+                                    for option in Language::ALL {
+                                        ui.selectable_value(
+                                            &mut draft.language,
+                                            option,
+                                            option.label(),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+
+                            ui.label(tr(Text::AddEntriesTo));
+                            ui.vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    for is_system in [false, true] {
+                                        ui.radio_value(
+                                            &mut draft.add_to_system,
+                                            is_system,
+                                            trf(Text::ScopePath, &[&scope_label(is_system)]),
+                                        );
+                                    }
+                                });
+                                ui.checkbox(&mut draft.add_to_front, tr(Text::AddToFront));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr(Text::Browse));
+                            ui.checkbox(&mut draft.browse_tokenize, tr(Text::BrowseTokenize));
+                            ui.end_row();
+
+                            ui.label(tr(Text::Dedupe));
+                            ui.checkbox(&mut draft.dedupe_prefer_tokens, tr(Text::DedupeTokens));
+                            ui.end_row();
+
+                            ui.label(tr(Text::EntriesLabel));
+                            ui.checkbox(&mut draft.category_colors, tr(Text::CategoryColors));
+                            ui.end_row();
+
+                            ui.label(tr(Text::StartupLabel));
+                            ui.checkbox(&mut draft.clipboard_check, tr(Text::ClipboardCheck))
+                                .on_hover_text(tr(Text::ClipboardCheckHint));
+                            ui.end_row();
+
+                            ui.label(tr(Text::CriticalLabel));
+                            ui.vertical(|ui| {
+                                ui.add(
+                                    TextEdit::multiline(critical_text)
+                                        .hint_text(tr(Text::CriticalHint))
+                                        .desired_rows(4)
+                                        .desired_width(320.0),
+                                )
+                                .on_hover_text(tr(Text::CriticalHover));
+                                if ui.small_button(tr(Text::ResetDefaults)).clicked() {
+                                    *critical_text = CRITICAL_PATHS.join("\n");
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label(tr(Text::SavingLabel));
+                            ui.vertical(|ui| {
+                                ui.checkbox(&mut draft.confirm_diff, tr(Text::ConfirmDiff));
+                                ui.checkbox(&mut draft.dry_run_default, tr(Text::DryRunDefault));
+                                ui.checkbox(&mut draft.backup_on_save, tr(Text::BackupOnSave));
+                                ui.checkbox(&mut draft.save_toasts, tr(Text::SaveToasts))
+                                    .on_hover_text(tr(Text::SaveToastsHint));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr(Text::StartupBackupLabel));
+                            ui.vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(
+                                        &mut draft.startup_backup,
+                                        tr(Text::StartupBackupEvery),
+                                    );
+                                    ui.add_enabled(
+                                        draft.startup_backup,
+                                        TextEdit::singleline(backup_hours_text)
+                                            .desired_width(40.0),
+                                    );
+                                    ui.label(tr(Text::Hours));
+                                })
+                                .response
+                                .on_hover_text(tr(Text::StartupBackupHint));
+                                ui.checkbox(
+                                    &mut draft.session_changes,
+                                    tr(Text::SessionChangesOption),
+                                );
+                            });
+                            ui.end_row();
+
+                            ui.label(tr(Text::BackupKeepLabel));
+                            ui.add_enabled(
+                                draft.backup_on_save || draft.startup_backup,
+                                TextEdit::singleline(backup_keep_text).desired_width(60.0),
+                            )
+                            .on_disabled_hover_text(tr(Text::BackupKeepDisabled));
+                            ui.end_row();
+
+                            ui.label(tr(Text::LoggingLabel));
+                            let hint = log_dir().map_or_else(
+                                || tr(Text::LoggingHint).to_string(),
+                                |dir| trf(Text::LoggingDir, &[&dir.display()]),
+                            );
+                            ui.checkbox(&mut draft.logging, tr(Text::LoggingOption))
+                                .on_hover_text(hint);
+                            ui.end_row();
+                        });
+
+                    let keep = parse_backup_keep(backup_keep_text);
+                    let hours = parse_backup_hours(backup_hours_text);
+                    let mut problems = Vec::new();
+                    if keep.is_none() {
+                        problems.push(trf(Text::BackupKeepInvalid, &[&MAX_BACKUP_KEEP]));
+                    }
+                    if hours.is_none() {
+                        problems.push(trf(Text::BackupHoursInvalid, &[&MAX_BACKUP_HOURS]));
+                    }
+                    for problem in &problems {
+                        ui.label(RichText::new(problem).color(ERROR_COLOR));
+                    }
+
+                    ui.add_space(8.0);
+                    let (submit, dismiss) = dialog_keys(ui.ctx());
+                    cancelled |= dismiss;
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let save = ui
+                            .add_enabled(problems.is_empty(), egui::Button::new(tr(Text::Save)))
+                            .on_disabled_hover_text(problems.join("\n"))
+                            .clicked();
+                        if save || (submit && problems.is_empty()) {
+                            let mut settings = draft.clone();
+                            settings.backup_keep = keep.unwrap_or(DEFAULT_BACKUP_KEEP);
+                            settings.startup_backup_hours = hours.unwrap_or(DEFAULT_BACKUP_HOURS);
+                            settings.critical_paths = parse_critical_paths(critical_text);
+                            apply = Some(settings);
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            self.settings_dialog.open = open && apply.is_none() && !cancelled;
+            if let Some(settings) = apply {
+                self.apply_settings(ctx, settings);
+            }
+        }
+
+        fn switch_variable(&mut self, is_system: bool, name: String) {
+            if self.store(is_system).is_dirty() {
+                let confirmed = matches!(
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Warning)
+                        .set_title(tr(Text::DiscardTitle))
+                        .set_description(trf(
+                            Text::SwitchDiscardBody,
+                            &[&self.panel_title(is_system)],
+                        ))
+                        .set_buttons(MessageButtons::OkCancel)
+                        .show(),
+                    MessageDialogResult::Ok
+                );
+                if !confirmed {
+                    return;
+                }
+            }
+
+            *self.store_mut(is_system) = load_store(is_system, &name);
+            self.status = trf(Text::Editing, &[&self.panel_title(is_system)]);
+        }
+
+        /// PATH entries for a scope, read from the registry when the panel is showing
+        /// another variable.
+        fn path_entries(&self, is_system: bool) -> Vec<String> {
+            let store = self.store(is_system);
+            if store.is_path() {
+                store.enabled_parts()
+            } else {
+                load_store(is_system, "Path").parts
+            }
+        }
+
+        /// User entries followed by System entries, as this process's PATH is rebuilt after a
+        /// save.
+        fn merged_path(&self) -> String {
+            let mut merged = self.path_entries(false);
+            merged.extend(self.path_entries(true));
+            join_path(&merged)
+        }
+
+        /// Puts the edited values into this process's environment only, so programs started
+        /// from the editor see them. Nothing is written to the registry or broadcast.
+        fn apply_to_session(&mut self) {
+            for is_system in [false, true] {
+                let store = self.store(is_system);
+                if !store.is_path() {
+                    env::set_var(&store.var_name, store.raw_preview());
+                }
+            }
+            env::set_var("PATH", self.merged_path());
+            self.status = tr(Text::AppliedToSession).to_string();
+        }
+
+        fn has_unsaved_changes(&self) -> bool {
+            self.user.is_dirty() || self.system.is_dirty()
+        }
+
+        fn sync_window_title(&mut self, ctx: &egui::Context) {
+            let title = if self.has_unsaved_changes() {
+                format!("{APP_TITLE} *")
+            } else {
+                APP_TITLE.to_string()
+            };
+            if title != self.window_title {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+                self.window_title = title;
+            }
+        }
+
+        fn confirm_exit(&mut self, ctx: &egui::Context) {
+            let choice = MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title(tr(Text::ExitTitle))
+                .set_description(tr(Text::ExitBody))
+                .set_buttons(MessageButtons::YesNoCancel)
+                .show();
+
+            match choice {
+                MessageDialogResult::Yes => {
+                    for is_system in [false, true] {
+                        if !self.store(is_system).is_dirty() {
+                            continue;
+                        }
+                        if is_system && !self.is_admin {
+                            // The window closes once the helper reports success.
+                            if let Err(err) = self.write_system_elevated(true) {
+                                self.report_save_error(err);
+                            }
+                            return;
+                        }
+                        if let Err(err) = self.write_path(is_system) {
+                            self.report_save_error(err);
+                            return;
+                        }
+                    }
+                }
+                MessageDialogResult::No => {}
+                _ => return,
+            }
+
+            self.allow_close = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+
+        fn export_reg(&mut self, include_user: bool, include_system: bool) {
+            let mut sections = Vec::new();
+            if include_user {
+                sections.push((
+                    env_key_path(false),
+                    self.user.var_name.clone(),
+                    self.user.raw_preview(),
+                    self.user.write_type(),
+                ));
+            }
+            if include_system {
+                sections.push((
+                    format!(r"HKEY_LOCAL_MACHINE\{SYSTEM_ENV_KEY}"),
+                    self.system.var_name.clone(),
+                    self.system.raw_preview(),
+                    self.system.write_type(),
+                ));
+            }
+
+            let file_name = match (include_user, include_system) {
+                (true, true) => "path_all.reg",
+                (false, true) => "path_system.reg",
+                _ => "path_user.reg",
+            };
+            let Some(target) = FileDialog::new()
+                .add_filter(tr(Text::RegistryFile), &["reg"])
+                .set_file_name(file_name)
+                .save_file()
+            else {
+                return;
+            };
+
+            let content = build_reg_file(&sections);
+            match fs::write(&target, encode_reg_file(&content)) {
+                Ok(()) => {
+                    self.status = trf(Text::ExportedPath, &[&target.display()]);
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::ExportFailed))
+                        .set_description(err.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        /// Saves the scope's enabled entries as a .bat or .ps1 script that applies them elsewhere.
+        fn export_script(&mut self, is_system: bool, powershell: bool) {
+            let store = self.store(is_system);
+            let parts = store.enabled_parts();
+            let (content, filter, ext) = if powershell {
+                let script = to_powershell(&parts, &store.var_name, is_system, &store.write_type());
+                // Windows PowerShell reads BOM-less scripts in the ANSI code page.
+                (
+                    "\u{feff}".to_string() + &script,
+                    tr(Text::PowerShellScript),
+                    "ps1",
+                )
+            } else {
+                match to_batch(&parts, &store.var_name, is_system, &store.write_type()) {
+                    Ok(script) => (script, tr(Text::BatchFile), "bat"),
+                    Err(err) => {
+                        MessageDialog::new()
+                            .set_level(MessageLevel::Warning)
+                            .set_title(tr(Text::ExportFailed))
+                            .set_description(err)
+                            .set_buttons(MessageButtons::Ok)
+                            .show();
+                        return;
+                    }
+                }
+            };
+            let scope = if is_system { "system" } else { "user" };
+            let Some(target) = FileDialog::new()
+                .add_filter(filter, &[ext])
+                .set_file_name(format!("path_{scope}.{ext}"))
+                .save_file()
+            else {
+                return;
+            };
+
+            match fs::write(&target, content) {
+                Ok(()) => {
+                    let spaced = parts.iter().filter(|part| part.contains(' ')).count();
+                    self.status = trf(
+                        Text::ExportedScope,
+                        &[&self.panel_title(is_system), &target.display()],
+                    );
+                    if spaced > 0 {
+                        self.status += &trf(Text::QuotedSpaces, &[&spaced]);
+                    }
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::ExportFailed))
+                        .set_description(err.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn export_json(&mut self) {
+            let Some(target) = FileDialog::new()
+                .add_filter("JSON", &["json"])
+                .set_file_name("path_editor.json")
+                .save_file()
+            else {
+                return;
+            };
+
+            let snapshot = EditorSnapshot {
+                user: ScopeSnapshot::capture(&self.user),
+                system: ScopeSnapshot::capture(&self.system),
+            };
+            let result = serde_json::to_string_pretty(&snapshot)
+                .map_err(|err| err.to_string())
+                .and_then(|json| fs::write(&target, json).map_err(|err| err.to_string()));
+            match result {
+                Ok(()) => self.status = trf(Text::ExportedState, &[&target.display()]),
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::ExportFailed))
+                        .set_description(err)
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        /// Loads both scopes from a JSON export as unsaved, undoable edits.
+        fn import_json(&mut self) {
+            let Some(source) = FileDialog::new().add_filter("JSON", &["json"]).pick_file() else {
+                return;
+            };
+
+            let snapshot = fs::read_to_string(&source)
+                .map_err(|err| err.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<EditorSnapshot>(&json).map_err(|err| err.to_string())
+                });
+            let snapshot = match snapshot {
+                Ok(snapshot) => snapshot,
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::ImportFailed))
+                        .set_description(format!("{}\n\n{err}", source.display()))
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                    return;
+                }
+            };
+
+            for (is_system, scope) in [(false, snapshot.user), (true, snapshot.system)] {
+                let Some(reg_type) = reg_type_from_name(&scope.reg_type) else {
+                    self.status = trf(Text::UnknownType, &[&scope.reg_type]);
+                    continue;
+                };
+                let store = self.store_mut(is_system);
+                if !store.var_name.eq_ignore_ascii_case(&scope.variable) {
+                    *store = load_store(is_system, &scope.variable);
+                }
+                store.checkpoint();
+                store.parts = scope.parts;
+                store.reg_type = reg_type;
+                let mut seen: HashMap<String, usize> = HashMap::new();
+                store.disabled = scope
+                    .disabled
+                    .into_iter()
+                    .map(|part| {
+                        let nth = seen.entry(part.clone()).or_insert(0);
+                        *nth += 1;
+                        (part, *nth - 1)
+                    })
+                    .collect();
+                store.disabled_synced = true;
+                store.selected.clear();
+            }
+            self.status = trf(Text::ImportedState, &[&source.display()]);
+        }
+
+        fn start_import(&mut self) {
+            let Some(source) = FileDialog::new()
+                .add_filter(tr(Text::RegistryOrText), &["reg", "txt"])
+                .add_filter(tr(Text::AllFiles), &["*"])
+                .pick_file()
+            else {
+                return;
+            };
+
+            match read_import_file(&source, &self.import_names()) {
+                Ok(sections) => {
+                    let is_system = sections
+                        .first()
+                        .is_some_and(|section| section.key.starts_with("HKEY_LOCAL_MACHINE"));
+                    self.import_dialog = ImportDialogState {
+                        open: true,
+                        source: source.display().to_string(),
+                        sections,
+                        section: 0,
+                        is_system,
+                        mode: ImportMode::Replace,
+                    };
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::ImportFailed))
+                        .set_description(format!("{}\n\n{err}", source.display()))
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn import_names(&self) -> Vec<String> {
+            let mut names = vec![self.user.var_name.clone()];
+            if !self.system.var_name.eq_ignore_ascii_case(&self.user.var_name) {
+                names.push(self.system.var_name.clone());
+            }
+            names
+        }
+
+        fn open_compare_dialog(&mut self) {
+            let registry = |is_system: bool| {
+                let store = load_store(is_system, &self.store(is_system).var_name);
+                ImportedSection {
+                    key: tr(Text::RegistrySource).to_string(),
+                    name: self.panel_title(is_system),
+                    parts: store.parts,
+                }
+            };
+            self.compare_dialog = CompareDialogState {
+                open: true,
+                sources: vec![registry(false), registry(true)],
+                left: 0,
+                right: 1,
+                target_system: false,
+            };
+        }
+
+        fn load_compare_file(&mut self) {
+            let Some(source) = FileDialog::new()
+                .add_filter(tr(Text::RegistryOrText), &["reg", "txt"])
+                .add_filter(tr(Text::AllFiles), &["*"])
+                .pick_file()
+            else {
+                return;
+            };
+
+            match read_import_file(&source, &self.import_names()) {
+                Ok(sections) => {
+                    let file_name = source
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let dialog = &mut self.compare_dialog;
+                    let first = dialog.sources.len();
+                    dialog.sources.extend(sections.into_iter().map(|section| ImportedSection {
+                        key: file_name.clone(),
+                        ..section
+                    }));
+                    dialog.right = first;
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::LoadFailed))
+                        .set_description(format!("{}\n\n{err}", source.display()))
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn draw_compare_dialog(&mut self, ctx: &egui::Context) {
+            if !self.compare_dialog.open {
+                return;
+            }
+
+            let mut open = self.compare_dialog.open;
+            let mut load_file = false;
+            let mut pull: Option<String> = None;
+
+            egui::Window::new(tr(Text::CompareTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([960.0, 520.0])
+                .show(ctx, |ui| {
+                    let dialog = &mut self.compare_dialog;
+                    ui.horizontal(|ui| {
+                        for (id, label, choice) in [
+                            ("compare_a", "A", &mut dialog.left),
+                            ("compare_b", "B", &mut dialog.right),
+                        ] {
+                            ui.label(label);
+                            egui::ComboBox::from_id_source(id)
+                                .width(280.0)
+                                .selected_text(dialog.sources[*choice].label())
+                                .show_ui(ui, |ui| {
+                                    for (idx, source) in dialog.sources.iter().enumerate() {
+                                        ui.selectable_value(choice, idx, source.label());
+                                    }
+                                });
+                        }
+                        if ui.button(tr(Text::LoadFile)).clicked() {
+                            load_file = true;
+                        }
+                    });
+
+                    let comparison = compare_entries(
+                        &dialog.sources[dialog.left].parts,
+                        &dialog.sources[dialog.right].parts,
+                    );
+                    ui.add_space(8.0);
+
+                    ui.columns(3, |cols| {
+                        cols[0].heading(trf(Text::OnlyInA, &[&comparison.only_left.len()]));
+                        ScrollArea::vertical()
+                            .id_source("compare_only_a")
+                            .max_height(360.0)
+                            .show(&mut cols[0], |ui| {
+                                for entry in &comparison.only_left {
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .small_button(tr(Text::Add))
+                                            .on_hover_text(tr(Text::AddToLive))
+                                            .clicked()
+                                        {
+                                            pull = Some(entry.clone());
+                                        }
+                                        ui.label(RichText::new(entry).monospace());
+                                    });
+                                }
+                            });
+
+                        cols[1].heading(trf(Text::OnlyInB, &[&comparison.only_right.len()]));
+                        ScrollArea::vertical()
+                            .id_source("compare_only_b")
+                            .max_height(360.0)
+                            .show(&mut cols[1], |ui| {
+                                for entry in &comparison.only_right {
+                                    ui.label(RichText::new(entry).monospace());
+                                }
+                            });
+
+                        cols[2].heading(trf(Text::Common, &[&comparison.common.len()]));
+                        ScrollArea::vertical()
+                            .id_source("compare_common")
+                            .max_height(360.0)
+                            .show(&mut cols[2], |ui| {
+                                for (entry, left, right) in &comparison.common {
+                                    if left == right {
+                                        ui.label(RichText::new(entry).monospace());
+                                    } else {
+                                        ui.label(
+                                            RichText::new(trf(
+                                                Text::MovedPosition,
+                                                &[entry, &(left + 1), &(right + 1)],
+                                            ))
+                                            .monospace()
+                                            .color(WARNING_COLOR),
+                                        );
+                                    }
+                                }
+                            });
+                    });
+
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        ui.label(tr(Text::PullTarget));
+                        for is_system in [false, true] {
+                            ui.radio_value(
+                                &mut dialog.target_system,
+                                is_system,
+                                scope_label(is_system),
+                            );
+                        }
+                    });
+                });
+
+            open &= !dialog_keys(ctx).1;
+            self.compare_dialog.open = open && self.compare_dialog.open;
+            if load_file {
+                self.load_compare_file();
+            }
+            if let Some(entry) = pull {
+                let is_system = self.compare_dialog.target_system;
+                let store = self.store_mut(is_system);
+                let key = normalize_for_compare(&entry);
+                if store.parts.iter().any(|part| normalize_for_compare(part) == key) {
+                    self.status = trf(Text::AlreadyIn, &[&entry, &self.panel_title(is_system)]);
+                } else {
+                    store.checkpoint();
+                    store.edit_entries(|entries| entries.push((entry.clone(), false)));
+                    self.status = trf(Text::AddedTo, &[&entry, &self.panel_title(is_system)]);
+                }
+            }
+        }
+
+        fn apply_import(&mut self) {
+            let dialog = &self.import_dialog;
+            let Some(section) = dialog.sections.get(dialog.section) else {
+                return;
+            };
+            let incoming = section.parts.clone();
+            let mode = dialog.mode;
+            let is_system = dialog.is_system;
+            let prefer_tokens = self.settings.dedupe_prefer_tokens;
+
+            let store = self.store_mut(is_system);
+            let before = store.parts.len();
+            store.checkpoint();
+            let incoming = incoming.into_iter().map(|entry| (entry, false));
+            match mode {
+                ImportMode::Replace => store.set_entries(incoming.collect()),
+                ImportMode::Append => store.edit_entries(|entries| entries.extend(incoming)),
+                ImportMode::AppendDedupe => {
+                    store.edit_entries(|entries| entries.extend(incoming));
+                    store.replace_parts(dedupe_with(&store.parts, prefer_tokens));
+                }
+            }
+            store.selected.clear();
+            let after = store.parts.len();
+            self.status = trf(
+                Text::ImportedInto,
+                &[&self.panel_title(is_system), &before, &after],
+            );
+            self.import_dialog.open = false;
+        }
+
+        fn draw_panel(&mut self, ui: &mut egui::Ui, is_system: bool) {
+            let mut do_add = false;
+            let mut do_browse = false;
+            let mut do_remove = false;
+            let mut do_up = false;
+            let mut do_down = false;
+            let mut do_dedupe = false;
+            let mut do_review_duplicates = false;
+            let mut do_sort: Option<SortOrder> = None;
+            let mut do_normalize = false;
+            let mut do_casing = false;
+            let mut do_trim = false;
+            let mut do_trim_whitespace = false;
+            let mut do_tokenize = false;
+            let mut do_expand_all = false;
+            let mut do_open = false;
+            let mut do_replace = false;
+            let mut do_cleanup = false;
+            let mut do_rescan = false;
+            let mut do_move_scope = false;
+            let mut do_undo = false;
+            let mut do_expand = false;
+            let mut do_save = false;
+            let mut remove_cross: Option<usize> = None;
+            let mut fix_index: Option<usize> = None;
+            let mut create_dir: Option<usize> = None;
+            let scanner = self.scanner.clone();
+            let mut make_absolute: Option<usize> = None;
+            let mut entry_action: Option<(usize, EntryAction)> = None;
+            let mut select_mode: Option<SelectMode> = None;
+            let move_text = if is_system {
+                Text::MoveToUser
+            } else {
+                Text::MoveToSystem
+            };
+            let mut do_remove_relative = false;
+            let mut do_remove_invalid = false;
+            let mut do_split = false;
+            let mut do_show_changes = false;
+            let mut focused = false;
+            let restore_focus = self.restore_focus == Some(is_system);
+
+            let mut switch_to: Option<String> = None;
+            let title = self.panel_title(is_system);
+            let needs_admin = is_system && !self.is_admin;
+            let search_focused = ui.ctx().memory(|m| m.has_focus(egui::Id::new(GLOBAL_SEARCH_ID)));
+            let keyboard =
+                self.focused_system == is_system && !self.any_dialog_open() && !search_focused;
+            let mut filter_focused = false;
+            let mut scroll_to_cursor = false;
+            let dry_run = self.dry_run;
+            let long_paths = self.long_paths;
+            let category_colors = self.settings.category_colors;
+            let search = self.global_search.trim().to_lowercase();
+            let notes = self.notes.clone();
+            let shield = self.shield.clone();
+
+            let same_var = self
+                .store(!is_system)
+                .var_name
+                .eq_ignore_ascii_case(&self.store(is_system).var_name);
+            let other_keys = if same_var {
+                Arc::clone(&self.store_mut(!is_system).derived().keys)
+            } else {
+                Arc::default()
+            };
+            let other_scope = scope_label(!is_system);
+
+            let panel_rect = {
+                let store = self.store_mut(is_system);
+                let search_hits = store.search_matches(&search);
+
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            if store.is_dirty() {
+                                ui.heading(format!("{title} *"));
+                            } else {
+                                ui.heading(&title);
+                            }
+                            let bytes = store.derived().bytes;
+                            ui.label(
+                                RichText::new(trf(
+                                    Text::EntriesBytes,
+                                    &[&store.parts.len(), &group_thousands(bytes)],
+                                ))
+                                .color(Color32::from_gray(170)),
+                            )
+                            .on_hover_text(tr(Text::SizeHint));
+                            if let Some(changed) = &store.last_write {
+                                ui.label(
+                                    RichText::new(trf(Text::LastChanged, &[changed]))
+                                        .color(Color32::from_gray(170)),
+                                )
+                                .on_hover_text(tr(Text::LastChangedHint));
+                            }
+                        });
+                        let chars = (bytes / 2).saturating_sub(1);
+                        if is_system && store.is_path() && chars > LONG_PATH_CHARS {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(trf(
+                                        Text::LongSystemPath,
+                                        &[
+                                            &group_thousands(chars),
+                                            &group_thousands(LONG_PATH_CHARS),
+                                        ],
+                                    ))
+                                    .color(WARNING_COLOR),
+                                )
+                                .on_hover_text(tr(Text::LongSystemPathHint));
+                                if ui
+                                    .small_button(tr(Text::MoveOverflow))
+                                    .on_hover_text(tr(Text::MoveOverflowHint))
+                                    .clicked()
+                                {
+                                    do_split = true;
+                                }
+                            });
+                        }
+                        ui.label(
+                            RichText::new(tr(Text::PanelHint))
+                                .small()
+                                .color(Color32::from_gray(170)),
+                        );
+                        ui.add_space(8.0);
+
+                        ui.horizontal(|ui| {
+                            ui.label(tr(Text::Variable));
+                            egui::ComboBox::from_id_source(format!("var_{is_system}"))
+                                .selected_text(&store.var_name)
+                                .show_ui(ui, |ui| {
+                                    for name in &store.var_names {
+                                        let current = name.eq_ignore_ascii_case(&store.var_name);
+                                        if ui.selectable_label(current, name).clicked() && !current {
+                                            switch_to = Some(name.clone());
+                                        }
+                                    }
+                                });
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label(tr(Text::Filter));
+                            egui::ComboBox::from_id_source(format!("filter_mode_{is_system}"))
+                                .selected_text(store.filter_mode.label())
+                                .width(90.0)
+                                .show_ui(ui, |ui| {
+                                    for mode in FilterMode::ALL {
+                                        ui.selectable_value(&mut store.filter_mode, mode, mode.label());
+                                    }
+                                });
+                            let clear_width = if store.filter.is_empty() { 0.0 } else { 24.0 };
+                            let response = ui.add(
+                                TextEdit::singleline(&mut store.filter)
+                                    .hint_text(tr(Text::FilterHint))
+                                    .desired_width(ui.available_width() - clear_width),
+                            );
+                            if !store.filter.is_empty()
+                                && ui
+                                    .small_button("x")
+                                    .on_hover_text(tr(Text::ClearFilter))
+                                    .clicked()
+                            {
+                                store.filter.clear();
+                            }
+                            if restore_focus {
+                                response.request_focus();
+                            }
+                            filter_focused = response.has_focus();
+                            focused |= filter_focused;
+                        });
+                        if let Some(err) = store.filter_error() {
+                            ui.label(
+                                RichText::new(trf(Text::InvalidRegex, &[&err]))
+                                    .small()
+                                    .color(ERROR_COLOR),
+                            );
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new(trf(Text::SelectedCount, &[&store.selected.len()]))
+                                    .small()
+                                    .color(Color32::from_gray(170)),
+                            );
+                            for (mode, label) in [
+                                (SelectMode::All, tr(Text::SelectAll)),
+                                (SelectMode::Invert, tr(Text::Invert)),
+                                (SelectMode::Clear, tr(Text::Clear)),
+                            ] {
+                                if ui.small_button(label).clicked() {
+                                    select_mode = Some(mode);
+                                }
+                            }
+                        });
+
+                        ui.add_space(8.0);
+
+                        if keyboard && !filter_focused {
+                            use egui::{Key, Modifiers};
+                            let (shift_down, shift_up, down, up, space, enter) =
+                                ui.input_mut(|i| {
+                                    (
+                                        i.consume_key(Modifiers::SHIFT, Key::ArrowDown),
+                                        i.consume_key(Modifiers::SHIFT, Key::ArrowUp),
+                                        i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                                        i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                                        i.consume_key(Modifiers::NONE, Key::Space),
+                                        i.consume_key(Modifiers::NONE, Key::Enter),
+                                    )
+                                });
+                            store.cursor = store.cursor.filter(|&c| c < store.parts.len());
+                            let step = if shift_down || down {
+                                1
+                            } else if shift_up || up {
+                                -1
+                            } else {
+                                0
+                            };
+                            if step != 0 {
+                                let previous = store.cursor;
+                                store.move_cursor(step);
+                                if shift_down || shift_up {
+                                    store.selected.extend(previous);
+                                    store.selected.extend(store.cursor);
+                                }
+                                scroll_to_cursor = true;
+                            }
+                            if let Some(cursor) = store.cursor {
+                                if space && !store.selected.remove(&cursor) {
+                                    store.selected.insert(cursor);
+                                }
+                                if enter {
+                                    entry_action = Some((cursor, EntryAction::Edit));
+                                }
+                            }
+                        }
+
+                        let visible = store.visible_indices();
+                        let linked = linked_entries(store, &scanner, ui.ctx());
+                        if category_colors {
+                            ui.horizontal_wrapped(|ui| {
+                                for category in EntryCategory::ALL {
+                                    let text = RichText::new(category.label()).small();
+                                    ui.label(match category.color() {
+                                        Some(color) => text.color(color),
+                                        None => text,
+                                    });
+                                }
+                            });
+                        }
+                        let unfiltered = store.filter.trim().is_empty();
+                        let row_height = ui.text_style_height(&egui::TextStyle::Body)
+                            + 2.0 * ui.spacing().button_padding.y;
+                        let cursor_row =
+                            store.cursor.and_then(|c| visible.iter().position(|&idx| idx == c));
+                        let mut scroll_area = ScrollArea::vertical()
+                            .id_source(format!("list_{is_system}"))
+                            .auto_shrink([false, false]);
+                        if let Some(offset) = store.scroll_offset.take() {
+                            scroll_area = scroll_area.vertical_scroll_offset(offset);
+                        }
+
+                        egui::Frame::canvas(ui.style()).show(ui, |ui| {
+                            ui.set_height(300.0);
+                            // Only rows inside the viewport are laid out, so a frame costs the
+                            // same with 20 entries or 2000.
+                            let output = scroll_area.show_rows(
+                                ui,
+                                row_height,
+                                visible.len(),
+                                |ui, rows| {
+                                    for &idx in &visible[rows] {
+                                        let selected = store.selected.contains(&idx);
+                                        let enabled = store.is_enabled(idx);
+                                        let info = store.entry_info(idx);
+                                        let in_other = other_keys.contains(&info.key);
+                                        let note = notes.get(&info.key).cloned();
+                                        let relative = info.relative;
+                                        let problem = info.problem;
+                                        let too_long = info.too_long;
+                                        let tint =
+                                            info.category.color().filter(|_| category_colors);
+                                        let undefined = info.unresolved.clone();
+                                        let missing = !relative
+                                            && undefined.is_empty()
+                                            && matches!(
+                                                scanner.get(&info.expanded, ui.ctx()),
+                                                ScanState::Missing
+                                            );
+                                        let flagged = relative || missing || !undefined.is_empty();
+                                        let hit = search_hits.contains(&idx);
+                                        let response = ui
+                                            .horizontal(|ui| {
+                                                ui.set_min_height(row_height);
+                                                let text = if !enabled {
+                                                    RichText::new(&store.parts[idx])
+                                                        .strikethrough()
+                                                        .color(Color32::from_gray(130))
+                                                } else if flagged {
+                                                    RichText::new(&store.parts[idx]).color(WARNING_COLOR)
+                                                } else if let Some(tint) = tint {
+                                                    RichText::new(&store.parts[idx]).color(tint)
+                                                } else {
+                                                    RichText::new(&store.parts[idx])
+                                                };
+                                                let text = if hit {
+                                                    text.background_color(SEARCH_HIGHLIGHT)
+                                                } else {
+                                                    text
+                                                };
+                                                let response = ui
+                                                    .selectable_label(selected, text)
+                                                    .on_hover_ui(|ui| {
+                                                        let info = store.entry_info(idx);
+                                                        let scan = scanner.get(&info.expanded, ui.ctx());
+                                                        draw_entry_tooltip(ui, info, &scan, relative);
+                                                    });
+                                                if let Some(note) = &note {
+                                                    ui.label(RichText::new("📝").small())
+                                                        .on_hover_text(note.as_str());
+                                                }
+                                                if !undefined.is_empty() {
+                                                    let names = undefined
+                                                        .iter()
+                                                        .map(|name| format!("%{name}%"))
+                                                        .collect::<Vec<_>>()
+                                                        .join(", ");
+                                                    ui.label(
+                                                        RichText::new(trf(
+                                                            Text::Undefined,
+                                                            &[&names],
+                                                        ))
+                                                        .small()
+                                                        .color(WARNING_COLOR),
+                                                    )
+                                                    .on_hover_text(trf(
+                                                        Text::UndefinedHint,
+                                                        &[&names],
+                                                    ));
+                                                }
+                                                if relative {
+                                                    if ui
+                                                        .small_button(tr(Text::MakeAbsolute))
+                                                        .on_hover_text(tr(Text::MakeAbsoluteHint))
+                                                        .clicked()
+                                                    {
+                                                        make_absolute = Some(idx);
+                                                    }
+                                                }
+                                                if in_other {
+                                                    ui.label(
+                                                        RichText::new(trf(
+                                                            Text::AlsoIn,
+                                                            &[&other_scope],
+                                                        ))
+                                                        .small()
+                                                        .color(WARNING_COLOR),
+                                                    );
+                                                    if !is_system
+                                                        && ui
+                                                            .small_button(tr(Text::RemoveFromUser))
+                                                            .on_hover_text(tr(
+                                                                Text::RemoveFromUserHint,
+                                                            ))
+                                                            .clicked()
+                                                    {
+                                                        remove_cross = Some(idx);
+                                                    }
+                                                }
+                                                if let Some(len) = too_long {
+                                                    let support = tr(if long_paths {
+                                                        Text::LongPathsOn
+                                                    } else {
+                                                        Text::LongPathsOff
+                                                    });
+                                                    ui.label(
+                                                        RichText::new(trf(
+                                                            Text::CharCount,
+                                                            &[&len],
+                                                        ))
+                                                        .small()
+                                                        .color(Color32::from_gray(170)),
+                                                    )
+                                                    .on_hover_text(trf(
+                                                        Text::TooLongHint,
+                                                        &[&LEGACY_MAX_PATH, &support],
+                                                    ));
+                                                }
+                                                if let Some(other) = linked.get(&idx) {
+                                                    ui.label(
+                                                        RichText::new(trf(
+                                                            Text::SameFolder,
+                                                            &[&(other + 1)],
+                                                        ))
+                                                        .small()
+                                                        .color(WARNING_COLOR),
+                                                    )
+                                                    .on_hover_text(tr(Text::SameFolderHint));
+                                                }
+                                                if missing
+                                                    && ui
+                                                        .small_button(tr(Text::Create))
+                                                        .on_hover_text(tr(Text::CreateHint))
+                                                        .clicked()
+                                                {
+                                                    create_dir = Some(idx);
+                                                }
+                                                if let Some(problem) = problem {
+                                                    ui.label(
+                                                        RichText::new(format!("({problem})"))
+                                                            .small()
+                                                            .color(ERROR_COLOR),
+                                                    );
+                                                    // Malformed UNC paths have nothing to fix.
+                                                    let entry = &store.parts[idx];
+                                                    let fixable =
+                                                        fix_entry(entry) != [entry.clone()];
+                                                    if fixable
+                                                        && ui
+                                                            .small_button(tr(Text::Fix))
+                                                            .on_hover_text(tr(Text::FixHint))
+                                                            .clicked()
+                                                    {
+                                                        fix_index = Some(idx);
+                                                    }
+                                                }
+                                                response
+                                            })
+                                            .inner;
+                                        if store.cursor == Some(idx) {
+                                            ui.painter().rect_stroke(
+                                                response.rect.expand(1.0),
+                                                2.0,
+                                                egui::Stroke::new(1.0, ACCENT),
+                                            );
+                                        }
+                                        if response.clicked() {
+                                            focused = true;
+                                            store.cursor = Some(idx);
+                                            let ctrl = ui.input(|i| i.modifiers.ctrl || i.modifiers.command);
+                                            if ctrl {
+                                                if selected {
+                                                    store.selected.remove(&idx);
+                                                } else {
+                                                    store.selected.insert(idx);
+                                                }
+                                            } else {
+                                                store.selected.clear();
+                                                store.selected.insert(idx);
+                                            }
+                                        }
+                                        if response.secondary_clicked() {
+                                            focused = true;
+                                            store.selected.insert(idx);
+                                        }
+                                        response.context_menu(|ui| {
+                                            let items = [
+                                                (EntryAction::OpenInExplorer, Text::OpenInExplorer),
+                                                (EntryAction::Copy, Text::Copy),
+                                                (EntryAction::CopyExpanded, Text::CopyExpanded),
+                                                (
+                                                    EntryAction::CopyPowerShellSession,
+                                                    Text::CopyPsSession,
+                                                ),
+                                                (
+                                                    EntryAction::CopyPowerShellPersistent,
+                                                    Text::CopyPsPersistent,
+                                                ),
+                                                (EntryAction::Edit, Text::EditEntry),
+                                                (EntryAction::Duplicate, Text::Duplicate),
+                                                (EntryAction::EditNote, Text::Note),
+                                                (
+                                                    EntryAction::ListExecutables,
+                                                    Text::ListExecutables,
+                                                ),
+                                                (EntryAction::Remove, Text::Remove),
+                                                (EntryAction::MoveToTop, Text::MoveToTop),
+                                                (EntryAction::MoveToBottom, Text::MoveToBottom),
+                                                (EntryAction::MoveToOtherScope, move_text),
+                                            ];
+                                            for (action, label) in items {
+                                                if ui.button(tr(label)).clicked() {
+                                                    entry_action = Some((idx, action));
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                            let toggle_label =
+                                                if enabled { Text::Disable } else { Text::Enable };
+                                            if ui
+                                                .button(tr(toggle_label))
+                                                .on_hover_text(tr(Text::DisableHint))
+                                                .clicked()
+                                            {
+                                                entry_action =
+                                                    Some((idx, EntryAction::ToggleEnabled));
+                                                ui.close_menu();
+                                            }
+                                            if ui
+                                                .add_enabled(
+                                                    unfiltered,
+                                                    egui::Button::new(tr(Text::MoveToPosition)),
+                                                )
+                                                .on_disabled_hover_text(tr(
+                                                    Text::MoveToPositionHint,
+                                                ))
+                                                .clicked()
+                                            {
+                                                entry_action =
+                                                    Some((idx, EntryAction::MoveToPosition));
+                                                ui.close_menu();
+                                            }
+                                        });
+                                    }
+                                },
+                            );
+
+                            // The cursor row may not be laid out this frame, so scroll by
+                            // arithmetic on the fixed row height instead of `scroll_to_me`.
+                            if let Some(row) = cursor_row.filter(|_| scroll_to_cursor) {
+                                let top = row as f32 * (row_height + ui.spacing().item_spacing.y);
+                                let view = output.inner_rect.height();
+                                let offset = output.state.offset.y;
+                                if top < offset {
+                                    store.scroll_offset = Some(top);
+                                } else if top + row_height > offset + view {
+                                    store.scroll_offset = Some(top + row_height - view);
+                                }
+                                if store.scroll_offset.is_some() {
+                                    ui.ctx().request_repaint();
+                                }
+                            }
+                        });
+
+                        ui.add_space(8.0);
+
+                        ui.horizontal_wrapped(|ui| {
+                            if ui.button(tr(Text::Add)).clicked() {
+                                do_add = true;
+                            }
+                            if ui.button(tr(Text::Browse)).clicked() {
+                                do_browse = true;
+                            }
+                            let has_selection = !store.selected.is_empty();
+                            let needs_selection = tr(Text::NeedsSelection);
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new(tr(Text::Remove)))
+                                .on_disabled_hover_text(needs_selection)
+                                .clicked()
+                            {
+                                do_remove = true;
+                            }
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new(tr(Text::Open)))
+                                .on_hover_text(tr(Text::OpenHint))
+                                .on_disabled_hover_text(needs_selection)
+                                .clicked()
+                            {
+                                do_open = true;
+                            }
+                            if ui.button(tr(Text::Replace)).clicked() {
+                                do_replace = true;
+                            }
+                            if ui.button(tr(Text::Cleanup)).clicked() {
+                                do_cleanup = true;
+                            }
+                            if ui
+                                .button(tr(Text::Rescan))
+                                .on_hover_text(tr(Text::RescanCacheHint))
+                                .clicked()
+                            {
+                                do_rescan = true;
+                            }
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new(tr(Text::MoveUp)))
+                                .on_disabled_hover_text(needs_selection)
+                                .clicked()
+                            {
+                                do_up = true;
+                            }
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new(tr(Text::MoveDown)))
+                                .on_disabled_hover_text(needs_selection)
+                                .clicked()
+                            {
+                                do_down = true;
+                            }
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new(tr(move_text)))
+                                .on_hover_text(tr(Text::MoveScopeHint))
+                                .on_disabled_hover_text(needs_selection)
+                                .clicked()
+                            {
+                                do_move_scope = true;
+                            }
+                            if ui.button(tr(Text::Dedupe)).clicked() {
+                                do_dedupe = true;
+                            }
+                            if ui
+                                .button(tr(Text::ReviewDuplicates))
+                                .on_hover_text(tr(Text::ReviewDuplicatesHint))
+                                .clicked()
+                            {
+                                do_review_duplicates = true;
+                            }
+                            if ui
+                                .button(tr(Text::RemoveRelative))
+                                .on_hover_text(tr(Text::RemoveRelativeHint))
+                                .clicked()
+                            {
+                                do_remove_relative = true;
+                            }
+                            if ui
+                                .button(tr(Text::RemoveInvalid))
+                                .on_hover_text(tr(Text::RemoveInvalidHint))
+                                .clicked()
+                            {
+                                do_remove_invalid = true;
+                            }
+                            ui.menu_button(tr(Text::Sort), |ui| {
+                                for order in SortOrder::ALL {
+                                    if ui.button(order.label()).clicked() {
+                                        do_sort = Some(order);
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                            if ui
+                                .button(tr(Text::NormalizeSlashes))
+                                .on_hover_text(tr(Text::NormalizeSlashesHint))
+                                .clicked()
+                            {
+                                do_normalize = true;
+                            }
+                            if ui
+                                .button(tr(Text::NormalizeCasing))
+                                .on_hover_text(tr(Text::NormalizeCasingHint))
+                                .clicked()
+                            {
+                                do_casing = true;
+                            }
+                            if ui
+                                .button(tr(Text::TrimSlashes))
+                                .on_hover_text(tr(Text::TrimSlashesHint))
+                                .clicked()
+                            {
+                                do_trim = true;
+                            }
+                            let padded =
+                                store.parts.iter().filter(|p| p.trim() != p.as_str()).count();
+                            let trim_button = egui::Button::new(tr(Text::TrimWhitespace));
+                            if ui
+                                .add_enabled(padded > 0, trim_button)
+                                .on_hover_text(trf(Text::TrimWhitespaceHint, &[&padded]))
+                                .on_disabled_hover_text(tr(Text::NoWhitespace))
+                                .clicked()
+                            {
+                                do_trim_whitespace = true;
+                            }
+                            if ui
+                                .button(tr(Text::Tokenize))
+                                .on_hover_text(tr(Text::TokenizeHint))
+                                .clicked()
+                            {
+                                do_tokenize = true;
+                            }
+                            if ui
+                                .button(tr(Text::ExpandAll))
+                                .on_hover_text(tr(Text::ExpandAllHint))
+                                .clicked()
+                            {
+                                do_expand_all = true;
+                            }
+                            if ui
+                                .add_enabled(
+                                    !store.history.is_empty(),
+                                    egui::Button::new(tr(Text::Undo)),
+                                )
+                                .on_disabled_hover_text(tr(Text::NothingToUndo))
+                                .clicked()
+                            {
+                                do_undo = true;
+                            }
+                            if ui.button(tr(Text::Expanded)).clicked() {
+                                do_expand = true;
+                            }
+                            if ui
+                                .button(tr(Text::ShowChanges))
+                                .on_hover_text(tr(Text::ShowChangesHint))
+                                .clicked()
+                            {
+                                do_show_changes = true;
+                            }
+                        });
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(tr(Text::RawPreview)).small());
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    let selected = match store.type_override {
+                                        TypeOverride::Auto => {
+                                            let effective = reg_type_name(&store.write_type());
+                                            trf(Text::AutoType, &[&effective])
+                                        }
+                                        other => other.label().to_string(),
+                                    };
+                                    egui::ComboBox::from_id_source(format!("reg_type_{is_system}"))
+                                        .selected_text(selected)
+                                        .show_ui(ui, |ui| {
+                                            for option in TypeOverride::ALL {
+                                                ui.selectable_value(
+                                                    &mut store.type_override,
+                                                    option,
+                                                    option.label(),
+                                                );
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text(tr(Text::AutoTypeHint));
+                                    ui.label(RichText::new(tr(Text::Type)).small());
+                                    if store.type_override == TypeOverride::Sz
+                                        && has_env_token(&store.raw_preview())
+                                    {
+                                        ui.label(
+                                            RichText::new(tr(Text::TokensWontExpand))
+                                                .small()
+                                                .color(WARNING_COLOR),
+                                        );
+                                    }
+                                },
+                            );
+                        });
+                        let mut raw_preview = store.raw_preview();
+                        ui.add(
+                            TextEdit::multiline(&mut raw_preview)
+                                .desired_rows(5)
+                                .interactive(false)
+                                .desired_width(f32::INFINITY),
+                        );
+
+                        ui.add_space(8.0);
+                        let save = if needs_admin {
+                            ui.add(elevated_button(&shield, tr(Text::SavePath)))
+                                .on_hover_text(tr(Text::SaveElevatedHint))
+                        } else {
+                            ui.button(tr(Text::SavePath))
+                        };
+                        if save.clicked() {
+                            do_save = true;
+                        }
+                        if needs_admin {
+                            ui.label(
+                                RichText::new(tr(Text::AsksForAdmin))
+                                    .small()
+                                    .color(WARNING_COLOR),
+                            );
+                        }
+                    });
+                })
+                .response
+                .rect
+            };
+            self.panel_rects[usize::from(is_system)] = Some(panel_rect);
+
+            if restore_focus {
+                self.restore_focus = None;
+            }
+            if let Some(name) = switch_to {
+                self.switch_variable(is_system, name);
+                return;
+            }
+            if focused {
+                self.focused_system = is_system;
+            }
+            if do_add {
+                self.open_add_dialog(is_system);
+            }
+            if do_browse {
+                if let Some(folders) = FileDialog::new().pick_folders() {
+                    self.add_folders(is_system, &folders);
+                }
+            }
+            if do_remove {
+                self.remove_selected(is_system);
+            }
+            if do_open {
+                self.open_selected(is_system);
+            }
+            if do_replace {
+                self.open_replace_dialog(is_system);
+            }
+            if do_cleanup {
+                self.open_cleanup_dialog(is_system);
+            }
+            if do_rescan {
+                self.scanner.clear();
+                self.status = tr(Text::ScanCacheCleared).to_string();
+            }
+            if do_move_scope {
+                self.move_selected_to_other_scope(is_system);
+            }
+            if let Some(idx) = remove_cross {
+                self.remove_entry(is_system, idx);
+            }
+            if let Some(idx) = create_dir {
+                let expanded = self.store_mut(is_system).entry_info(idx).expanded.clone();
+                self.status = match fs::create_dir_all(&expanded) {
+                    Ok(()) => {
+                        self.scanner.forget(&expanded);
+                        trf(Text::CreatedFolder, &[&expanded])
+                    }
+                    Err(err) => trf(Text::CreateFailed, &[&expanded, &err]),
+                };
+            }
+            if let Some(idx) = fix_index {
+                let store = self.store_mut(is_system);
+                let fixed = fix_entry(&store.parts[idx]);
+                let count = fixed.len();
+                store.checkpoint();
+                store.edit_entries(|entries| {
+                    // Every piece split out of a disabled entry stays disabled with it.
+                    let disabled = entries[idx].1;
+                    entries.splice(idx..=idx, fixed.into_iter().map(|part| (part, disabled)));
+                });
+                store.selected.clear();
+                self.status = trf(Text::FixedEntry, &[&self.panel_title(is_system), &count]);
+            }
+            if let Some(mode) = select_mode {
+                let store = self.store_mut(is_system);
+                store.select_visible(mode);
+                let count = store.selected.len();
+                self.status = trf(
+                    Text::SelectedEntries,
+                    &[&count, &self.panel_title(is_system)],
+                );
+            }
+            if let Some((idx, action)) = entry_action {
+                self.run_entry_action(ui.ctx(), is_system, idx, action);
+            }
+            if let Some(idx) = make_absolute {
+                self.make_entry_absolute(is_system, idx);
+            }
+            if do_remove_relative {
+                self.remove_relative(is_system);
+            }
+            if do_remove_invalid {
+                self.open_remove_invalid_dialog(is_system);
+            }
+            if do_split {
+                self.open_split_dialog();
+            }
+            if do_up {
+                self.move_selected(is_system, -1);
+            }
+            if do_down {
+                self.move_selected(is_system, 1);
+            }
+            if do_dedupe {
+                self.apply_dedupe(is_system);
+            }
+            if do_review_duplicates {
+                self.open_duplicates_dialog(is_system);
+            }
+            if let Some(order) = do_sort {
+                self.apply_sort(is_system, order);
+            }
+            if do_normalize {
+                self.apply_normalize_slashes(is_system);
+            }
+            if do_casing {
+                self.apply_normalize_casing(is_system);
+            }
+            if do_trim {
+                self.apply_trim_slashes(is_system);
+            }
+            if do_trim_whitespace {
+                self.apply_trim_whitespace(is_system);
+            }
+            if do_tokenize {
+                self.apply_tokenize(is_system);
+            }
+            if do_expand_all {
+                self.apply_expand_all(is_system);
+            }
+            if do_undo {
+                self.apply_undo(is_system);
+            }
+            if do_expand {
+                self.open_expanded_dialog(is_system);
+            }
+            if do_show_changes {
+                self.open_changes_dialog(is_system);
+            }
+            if do_save {
+                self.request_save_one(is_system);
+            }
+        }
+
+        fn draw_add_dialog(&mut self, ctx: &egui::Context) {
+            if !self.add_dialog.open {
+                return;
+            }
+
+            let mut open = self.add_dialog.open;
+            let mut picked: Option<Vec<PathBuf>> = None;
+            let editing = self.add_dialog.edit_index;
+            let title = tr(match (editing.is_some(), self.add_dialog.is_system) {
+                (false, true) => Text::AddSystemTitle,
+                (false, false) => Text::AddUserTitle,
+                (true, true) => Text::EditSystemTitle,
+                (true, false) => Text::EditUserTitle,
+            });
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .default_width(640.0)
+                .show(ctx, |ui| {
+                    ui.label(tr(Text::EntryLabel));
+                    ui.add_space(6.0);
+                    ui.add(
+                        TextEdit::singleline(&mut self.add_dialog.input)
+                            .desired_width(f32::INFINITY)
+                            .hint_text(r"C:\Tools\bin"),
+                    );
+                    let (submit, dismiss) = dialog_keys(ctx);
+
+                    let pieces = split_input(&self.add_dialog.input);
+                    if pieces.len() > 1 {
+                        ui.label(
+                            RichText::new(trf(Text::EntriesDetected, &[&pieces.len()]))
+                                .small()
+                                .color(Color32::from_gray(170)),
+                        );
+                    }
+                    let existing = self
+                        .store(self.add_dialog.is_system)
+                        .parts
+                        .iter()
+                        .enumerate()
+                        .filter(|&(idx, _)| Some(idx) != editing)
+                        .map(|(_, part)| normalize_for_compare(part))
+                        .collect::<HashSet<_>>();
+                    let mut duplicates = 0;
+                    let mut missing = 0;
+                    let mut unreachable = 0;
+                    for piece in &pieces {
+                        if existing.contains(&normalize_for_compare(piece)) {
+                            duplicates += 1;
+                        }
+                        match self.scanner.get(&expand_env_vars(piece), ctx) {
+                            ScanState::Missing => missing += 1,
+                            ScanState::Pending(started) if started.elapsed() >= SCAN_TIMEOUT => {
+                                unreachable += 1;
+                            }
+                            ScanState::Pending(_) => ctx.request_repaint_after(SCAN_TIMEOUT),
+                            ScanState::Done(_) => {}
+                        }
+                    }
+                    let single = pieces.len() == 1;
+                    for (count, one, many) in [
+                        (duplicates, Text::OneDuplicate, Text::ManyDuplicates),
+                        (missing, Text::OneMissing, Text::ManyMissing),
+                        (unreachable, Text::OneUnreachable, Text::ManyUnreachable),
+                    ] {
+                        if count > 0 {
+                            let text = if single {
+                                tr(one).to_string()
+                            } else {
+                                trf(many, &[&count, &pieces.len()])
+                            };
+                            ui.label(RichText::new(text).color(WARNING_COLOR));
+                        }
+                    }
+
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button(tr(Text::BrowseDots)).clicked() {
+                            picked = if editing.is_some() {
+                                FileDialog::new().pick_folder().map(|folder| vec![folder])
+                            } else {
+                                FileDialog::new().pick_folders()
+                            };
+                        }
+                        if editing.is_none() {
+                            ui.checkbox(&mut self.settings.add_to_front, tr(Text::AddToFrontCheck))
+                                .on_hover_text(tr(Text::AddToFrontHint));
+                        }
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let label = if editing.is_some() {
+                                Text::Save
+                            } else {
+                                Text::Add
+                            };
+                            if ui.button(tr(label)).clicked() || submit {
+                                let count = pieces.len();
+                                if count > 0 {
+                                    let to_front = self.settings.add_to_front;
+                                    let store = self.store_mut(self.add_dialog.is_system);
+                                    match editing {
+                                        Some(idx) if idx < store.parts.len() => {
+                                            if store.parts[idx..=idx] != pieces[..] {
+                                                store.checkpoint();
+                                                store.edit_entries(|entries| {
+                                                    let disabled = entries[idx].1;
+                                                    let pieces = pieces
+                                                        .into_iter()
+                                                        .map(|part| (part, disabled));
+                                                    entries.splice(idx..=idx, pieces);
+                                                });
+                                            }
+                                        }
+                                        _ if to_front => {
+                                            store.checkpoint();
+                                            store.edit_entries(|entries| {
+                                                let pieces =
+                                                    pieces.into_iter().map(|part| (part, false));
+                                                entries.splice(0..0, pieces);
+                                            });
+                                        }
+                                        _ => {
+                                            store.checkpoint();
+                                            store.edit_entries(|entries| {
+                                                let pieces =
+                                                    pieces.into_iter().map(|part| (part, false));
+                                                entries.extend(pieces);
+                                            });
+                                        }
+                                    }
+                                    let text = if editing.is_some() {
+                                        Text::UpdatedEntries
+                                    } else {
+                                        Text::AddedEntries
+                                    };
+                                    self.status = trf(
+                                        text,
+                                        &[&count, &self.panel_title(self.add_dialog.is_system)],
+                                    );
+                                }
+                                self.add_dialog.input.clear();
+                                self.add_dialog.open = false;
+                            }
+                            if ui.button(tr(Text::Cancel)).clicked() || dismiss {
+                                self.add_dialog.open = false;
+                                self.add_dialog.input.clear();
+                            }
+                        });
+                    });
+                });
+
+            self.add_dialog.open = open && self.add_dialog.open;
+
+            match picked.as_deref() {
+                Some([folder]) => {
+                    let is_system = self.add_dialog.is_system;
+                    let entry = self.browse_entry(is_system, folder);
+                    let key = normalize_for_compare(&entry);
+                    let editing = self.add_dialog.edit_index;
+                    let present = self
+                        .store(is_system)
+                        .parts
+                        .iter()
+                        .enumerate()
+                        .any(|(idx, part)| {
+                            Some(idx) != editing && normalize_for_compare(part) == key
+                        });
+                    let title = self.panel_title(is_system);
+                    if present
+                        && !matches!(
+                            MessageDialog::new()
+                                .set_level(MessageLevel::Warning)
+                                .set_title(tr(Text::AlreadyPresentTitle))
+                                .set_description(trf(Text::AlreadyPresentBody, &[&entry, &title]))
+                                .set_buttons(MessageButtons::YesNo)
+                                .show(),
+                            MessageDialogResult::Yes
+                        )
+                    {
+                        self.status = trf(Text::AlreadyPresent, &[&title, &entry]);
+                    } else {
+                        self.add_dialog.input = entry;
+                    }
+                }
+                Some(folders) if !folders.is_empty() => {
+                    self.add_folders(self.add_dialog.is_system, folders);
+                    self.add_dialog.open = false;
+                    self.add_dialog.input.clear();
+                }
+                _ => {}
+            }
+        }
+
+        fn draw_move_dialog(&mut self, ctx: &egui::Context) {
+            if !self.move_dialog.open {
+                return;
+            }
+
+            let mut open = self.move_dialog.open;
+            let mut target: Option<usize> = None;
+            let mut cancelled = false;
+            let dialog = &mut self.move_dialog;
+            let store = if dialog.is_system { &self.system } else { &self.user };
+            let count = store.parts.len();
+            let entry = store.parts.get(dialog.index).cloned().unwrap_or_default();
+
+            egui::Window::new(tr(Text::MoveToPositionTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(RichText::new(&entry).monospace());
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        ui.label(trf(Text::PositionRange, &[&count]));
+                        ui.add(TextEdit::singleline(&mut dialog.input).desired_width(80.0));
+                    });
+                    // Out-of-range numbers clamp to the nearest end instead of being rejected.
+                    let position = dialog
+                        .input
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .map(|n| n.clamp(1, count.max(1)));
+                    match position {
+                        None => {
+                            ui.label(RichText::new(tr(Text::EnterNumber)).color(WARNING_COLOR));
+                        }
+                        Some(n) if dialog.input.trim() != n.to_string() => {
+                            ui.label(
+                                RichText::new(trf(Text::WillMoveTo, &[&n])).color(WARNING_COLOR),
+                            );
+                        }
+                        Some(_) => {}
+                    }
+
+                    ui.add_space(8.0);
+                    let (submit, dismiss) = dialog_keys(ui.ctx());
+                    cancelled |= dismiss;
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let clicked = ui
+                            .add_enabled(position.is_some(), egui::Button::new(tr(Text::Move)))
+                            .on_disabled_hover_text(tr(Text::EnterPosition))
+                            .clicked();
+                        if clicked || submit {
+                            target = position.map(|n| n - 1);
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            self.move_dialog.open = open && target.is_none() && !cancelled;
+            if let Some(target) = target {
+                let is_system = self.move_dialog.is_system;
+                let idx = self.move_dialog.index;
+                let store = self.store_mut(is_system);
+                if idx >= store.parts.len() || idx == target {
+                    return;
+                }
+                store.checkpoint();
+                store.edit_entries(|entries| {
+                    let entry = entries.remove(idx);
+                    entries.insert(target, entry);
+                });
+                store.selected.clear();
+                store.selected.insert(target);
+                store.cursor = Some(target);
+                self.status = trf(
+                    Text::MovedToPosition,
+                    &[&(target + 1), &self.panel_title(is_system)],
+                );
+            }
+        }
+
+        fn draw_expanded_dialog(&mut self, ctx: &egui::Context) {
+            if !self.expanded_dialog.open {
+                return;
+            }
+
+            let mut open = self.expanded_dialog.open;
+            let is_system = self.expanded_dialog.is_system;
+            let title = tr(if is_system {
+                Text::ExpandedSystemTitle
+            } else {
+                Text::ExpandedUserTitle
+            });
+            let scanner = self.scanner.clone();
+            let store = self.store_mut(is_system);
+            let rows = (0..store.parts.len())
+                .map(|idx| {
+                    let info = store.entry_info(idx);
+                    let scan = scanner.get(&info.expanded, ctx);
+                    (store.parts[idx].clone(), info.expanded.clone(), scan)
+                })
+                .collect::<Vec<_>>();
+            let filter = self.expanded_dialog.filter.trim().to_lowercase();
+            let visible = rows
+                .iter()
+                .filter(|(original, expanded, _)| {
+                    filter.is_empty()
+                        || original.to_lowercase().contains(&filter)
+                        || expanded.to_lowercase().contains(&filter)
+                })
+                .collect::<Vec<_>>();
+
+            let mut copied: Option<(String, &str)> = None;
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([980.0, 420.0])
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            TextEdit::singleline(&mut self.expanded_dialog.filter)
+                                .hint_text(tr(Text::ExpandedFilterHint))
+                                .desired_width(280.0),
+                        );
+                        if ui
+                            .button(tr(Text::Copy))
+                            .on_hover_text(tr(Text::CopyListingHint))
+                            .clicked()
+                        {
+                            let listing = visible
+                                .iter()
+                                .map(|(original, expanded, _)| {
+                                    format!("{original}\n    -> {expanded}")
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n\n");
+                            copied = Some((listing, tr(Text::ExpandedListing)));
+                        }
+                        if ui
+                            .button(tr(Text::CopyResolved))
+                            .on_hover_text(tr(Text::CopyResolvedHint))
+                            .clicked()
+                        {
+                            let resolved = visible
+                                .iter()
+                                .map(|(_, expanded, _)| expanded.as_str())
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            copied = Some((resolved, tr(Text::ResolvedPaths)));
+                        }
+                        ui.label(trf(Text::ShownOf, &[&visible.len(), &rows.len()]));
+                    });
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            for (original, expanded, scan) in &visible {
+                                ui.horizontal(|ui| {
+                                    match scan {
+                                        ScanState::Done(_) => {
+                                            ui.label(RichText::new("✔").color(ADDED_COLOR))
+                                                .on_hover_text(tr(Text::FolderExists));
+                                        }
+                                        ScanState::Missing => {
+                                            ui.label(RichText::new("✖").color(ERROR_COLOR))
+                                                .on_hover_text(tr(Text::FolderMissing));
+                                        }
+                                        ScanState::Pending(started)
+                                            if started.elapsed() >= SCAN_TIMEOUT =>
+                                        {
+                                            ui.label(RichText::new("?").color(WARNING_COLOR))
+                                                .on_hover_text(tr(Text::FolderUnavailable));
+                                        }
+                                        ScanState::Pending(_) => {
+                                            ui.spinner();
+                                            ctx.request_repaint_after(SCAN_TIMEOUT);
+                                        }
+                                    }
+                                    if ui
+                                        .small_button(tr(Text::Copy))
+                                        .on_hover_text(tr(Text::CopyExpandedHint))
+                                        .clicked()
+                                    {
+                                        copied = Some((expanded.clone(), tr(Text::ExpandedPath)));
+                                    }
+                                    ui.vertical(|ui| {
+                                        ui.label(RichText::new(original).monospace());
+                                        if expanded != original {
+                                            ui.label(
+                                                RichText::new(format!("-> {expanded}"))
+                                                    .monospace()
+                                                    .weak(),
+                                            );
+                                        }
+                                    });
+                                });
+                            }
+                        });
+                });
+
+            if let Some((text, what)) = copied {
+                ctx.output_mut(|o| o.copied_text = text);
+                self.status = trf(Text::CopiedToClipboard, &[&what]);
+            }
+            open &= !dialog_keys(ctx).1;
+            self.expanded_dialog.open = open;
+        }
+
+        fn open_replace_dialog(&mut self, is_system: bool) {
+            self.replace_dialog.open = true;
+            self.replace_dialog.is_system = is_system;
+            self.replace_dialog.selected_only = !self.store(is_system).selected.is_empty();
+        }
+
+        fn draw_replace_dialog(&mut self, ctx: &egui::Context) {
+            if !self.replace_dialog.open {
+                return;
+            }
+
+            let mut open = self.replace_dialog.open;
+            let mut apply: Option<(Vec<String>, usize)> = None;
+            let is_system = self.replace_dialog.is_system;
+            let title = tr(if is_system {
+                Text::ReplaceSystemTitle
+            } else {
+                Text::ReplaceUserTitle
+            });
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_width(760.0)
+                .show(ctx, |ui| {
+                    let store = if is_system { &self.system } else { &self.user };
+                    let dialog = &mut self.replace_dialog;
+                    egui::Grid::new("replace_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(tr(Text::Find));
+                        ui.add(TextEdit::singleline(&mut dialog.find).desired_width(520.0));
+                        ui.end_row();
+                        ui.label(tr(Text::ReplaceWith));
+                        ui.add(TextEdit::singleline(&mut dialog.replace).desired_width(520.0));
+                        ui.end_row();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut dialog.ignore_case, tr(Text::IgnoreCase));
+                        ui.checkbox(&mut dialog.use_regex, tr(Text::Regex));
+                        ui.add_enabled(
+                            !store.selected.is_empty(),
+                            egui::Checkbox::new(&mut dialog.selected_only, tr(Text::SelectedOnly)),
+                        )
+                        .on_disabled_hover_text(tr(Text::NoneSelected));
+                    });
+
+                    ui.add_space(8.0);
+                    let (submit, dismiss) = dialog_keys(ui.ctx());
+                    if dismiss {
+                        dialog.open = false;
+                    }
+                    let selection = (dialog.selected_only && !store.selected.is_empty())
+                        .then_some(&store.selected);
+                    let result = replace_entries(
+                        &store.parts,
+                        selection,
+                        &dialog.find,
+                        &dialog.replace,
+                        dialog.ignore_case,
+                        dialog.use_regex,
+                    );
+                    match result {
+                        Err(err) => {
+                            ui.label(RichText::new(err).color(ERROR_COLOR));
+                        }
+                        Ok((updated, changed)) => {
+                            ui.label(trf(Text::WillChange, &[&changed]));
+                            ScrollArea::vertical()
+                                .id_source("replace_preview")
+                                .max_height(280.0)
                                 .show(ui, |ui| {
-                                    for idx in visible {
-                                        let selected = store.selected.contains(&idx);
-                                        let response = ui.selectable_label(selected, &store.parts[idx]);
-                                        if response.clicked() {
-                                            let ctrl = ui.input(|i| i.modifiers.ctrl || i.modifiers.command);
-                                            if ctrl {
-                                                if selected {
-                                                    store.selected.remove(&idx);
-                                                } else {
-                                                    store.selected.insert(idx);
-                                                }
-                                            } else {
-                                                store.selected.clear();
-                                                store.selected.insert(idx);
+                                    for (before, after) in store.parts.iter().zip(&updated) {
+                                        if before != after {
+                                            ui.label(
+                                                RichText::new(format!("- {before}"))
+                                                    .monospace()
+                                                    .color(ERROR_COLOR),
+                                            );
+                                            ui.label(
+                                                RichText::new(format!("+ {after}"))
+                                                    .monospace()
+                                                    .color(ADDED_COLOR),
+                                            );
+                                        }
+                                    }
+                                });
+                            ui.add_space(8.0);
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                let replace = egui::Button::new(tr(Text::ReplaceButton));
+                                let clicked = ui
+                                    .add_enabled(changed > 0, replace)
+                                    .on_disabled_hover_text(tr(Text::NoMatches))
+                                    .clicked();
+                                if clicked || (submit && changed > 0) {
+                                    apply = Some((updated, changed));
+                                }
+                                if ui.button(tr(Text::Cancel)).clicked() {
+                                    dialog.open = false;
+                                }
+                            });
+                        }
+                    }
+                });
+
+            self.replace_dialog.open = open && self.replace_dialog.open;
+            if let Some((updated, changed)) = apply {
+                let store = self.store_mut(is_system);
+                store.checkpoint();
+                store.edit_entries(|entries| {
+                    for ((part, _), updated) in entries.iter_mut().zip(updated) {
+                        *part = updated;
+                    }
+                });
+                self.replace_dialog.open = false;
+                self.status = trf(
+                    Text::ReplacedText,
+                    &[&changed, &self.panel_title(is_system)],
+                );
+            }
+        }
+
+        fn open_duplicates_dialog(&mut self, is_system: bool) {
+            let parts = self.store(is_system).parts.clone();
+            let groups = duplicate_groups(&parts);
+            if groups.is_empty() {
+                self.status = trf(Text::NoDuplicates, &[&self.panel_title(is_system)]);
+                return;
+            }
+            self.duplicates_dialog = DuplicatesDialogState {
+                open: true,
+                is_system,
+                snapshot: parts,
+                groups,
+            };
+        }
+
+        fn draw_duplicates_dialog(&mut self, ctx: &egui::Context) {
+            if !self.duplicates_dialog.open {
+                return;
+            }
+
+            let mut open = self.duplicates_dialog.open;
+            let mut do_apply = false;
+            let mut cancelled = false;
+            let title = trf(
+                Text::DuplicatesTitle,
+                &[&self.panel_title(self.duplicates_dialog.is_system)],
+            );
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([720.0, 420.0])
+                .show(ctx, |ui| {
+                    let dialog = &mut self.duplicates_dialog;
+                    ui.label(
+                        RichText::new(tr(Text::DuplicatesHint))
+                            .small()
+                            .color(Color32::from_gray(170)),
+                    );
+                    ui.add_space(6.0);
+                    ScrollArea::vertical()
+                        .id_source("duplicate_groups")
+                        .max_height(320.0)
+                        .show(ui, |ui| {
+                            for (group_idx, group) in dialog.groups.iter_mut().enumerate() {
+                                ui.push_id(group_idx, |ui| {
+                                    ui.group(|ui| {
+                                        for (member, &idx) in group.indices.iter().enumerate() {
+                                            ui.radio_value(
+                                                &mut group.keep,
+                                                member,
+                                                RichText::new(format!(
+                                                    "#{}  {}",
+                                                    idx + 1,
+                                                    dialog.snapshot[idx]
+                                                ))
+                                                .monospace(),
+                                            );
+                                        }
+                                    });
+                                });
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Apply)).clicked() {
+                            do_apply = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_apply |= submit;
+            cancelled |= dismiss;
+            self.duplicates_dialog.open = open && !do_apply && !cancelled;
+            if do_apply {
+                self.apply_duplicates();
+            }
+        }
+
+        fn apply_duplicates(&mut self) {
+            let dialog = &self.duplicates_dialog;
+            let is_system = dialog.is_system;
+            if self.store(is_system).parts != dialog.snapshot {
+                self.status = tr(Text::DuplicatesChanged).to_string();
+                return;
+            }
+
+            let mut replacement = HashMap::new();
+            let mut dropped = HashSet::new();
+            for group in &dialog.groups {
+                let first = group.indices[0];
+                replacement.insert(first, dialog.snapshot[group.indices[group.keep]].clone());
+                dropped.extend(group.indices[1..].iter().copied());
+            }
+            let merged = dialog
+                .snapshot
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| !dropped.contains(idx))
+                .map(|(idx, part)| replacement.remove(&idx).unwrap_or_else(|| part.clone()))
+                .collect::<Vec<_>>();
+            let removed = dropped.len();
+
+            let store = self.store_mut(is_system);
+            store.checkpoint();
+            store.replace_parts(merged);
+            store.selected.clear();
+            self.status = trf(
+                Text::MergedDuplicates,
+                &[&self.panel_title(is_system), &removed],
+            );
+        }
+
+        fn open_cross_duplicates_dialog(&mut self) {
+            if !self.user.var_name.eq_ignore_ascii_case(&self.system.var_name) {
+                self.status = tr(Text::NoSharedVariable).to_string();
+                return;
+            }
+            let duplicates = cross_scope_duplicates(&self.user.parts, &self.system.parts);
+            if duplicates.is_empty() {
+                self.status = tr(Text::NoCrossDuplicates).to_string();
+                return;
+            }
+            self.cross_duplicates_dialog = CrossDuplicatesDialogState {
+                open: true,
+                user_snapshot: self.user.parts.clone(),
+                system_snapshot: self.system.parts.clone(),
+                duplicates,
+            };
+        }
+
+        fn draw_cross_duplicates_dialog(&mut self, ctx: &egui::Context) {
+            if !self.cross_duplicates_dialog.open {
+                return;
+            }
+
+            let mut open = self.cross_duplicates_dialog.open;
+            let mut do_apply = false;
+            let mut cancelled = false;
+
+            egui::Window::new(tr(Text::MergeTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([720.0, 420.0])
+                .show(ctx, |ui| {
+                    let dialog = &mut self.cross_duplicates_dialog;
+                    ui.label(
+                        RichText::new(tr(Text::MergeHint))
+                            .small()
+                            .color(Color32::from_gray(170)),
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.small_button(tr(Text::AllInSystem)).clicked() {
+                            dialog.duplicates.iter_mut().for_each(|dup| dup.keep_system = true);
+                        }
+                        if ui.small_button(tr(Text::AllInUser)).clicked() {
+                            dialog.duplicates.iter_mut().for_each(|dup| dup.keep_system = false);
+                        }
+                    });
+                    ui.add_space(6.0);
+                    ScrollArea::vertical()
+                        .id_source("cross_duplicates")
+                        .max_height(320.0)
+                        .show(ui, |ui| {
+                            for (dup_idx, dup) in dialog.duplicates.iter_mut().enumerate() {
+                                ui.push_id(dup_idx, |ui| {
+                                    let describe = |indices: &[usize], snapshot: &[String]| {
+                                        indices
+                                            .iter()
+                                            .map(|&idx| format!("#{}  {}", idx + 1, snapshot[idx]))
+                                            .collect::<Vec<_>>()
+                                            .join("\n")
+                                    };
+                                    let sides = [
+                                        (true, describe(&dup.system, &dialog.system_snapshot)),
+                                        (false, describe(&dup.user, &dialog.user_snapshot)),
+                                    ];
+                                    ui.group(|ui| {
+                                        for (is_system, label) in sides {
+                                            let scope = scope_label(is_system);
+                                            ui.radio_value(
+                                                &mut dup.keep_system,
+                                                is_system,
+                                                RichText::new(trf(Text::KeepIn, &[&scope, &label]))
+                                                    .monospace(),
+                                            );
+                                        }
+                                    });
+                                });
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Apply)).clicked() {
+                            do_apply = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_apply |= submit;
+            cancelled |= dismiss;
+            self.cross_duplicates_dialog.open = open && !do_apply && !cancelled;
+            if do_apply {
+                self.apply_cross_duplicates();
+            }
+        }
+
+        /// Applies every choice at once, with a single undo step in each scope it touches.
+        fn apply_cross_duplicates(&mut self) {
+            let dialog = &self.cross_duplicates_dialog;
+            if self.user.parts != dialog.user_snapshot
+                || self.system.parts != dialog.system_snapshot
+            {
+                self.status = tr(Text::MergeChanged).to_string();
+                return;
+            }
+
+            let mut from_user = HashSet::new();
+            let mut from_system = HashSet::new();
+            for dup in &dialog.duplicates {
+                if dup.keep_system {
+                    from_user.extend(dup.user.iter().copied());
+                } else {
+                    from_system.extend(dup.system.iter().copied());
+                }
+            }
+
+            for (is_system, removing) in [(false, &from_user), (true, &from_system)] {
+                if removing.is_empty() {
+                    continue;
+                }
+                let store = self.store_mut(is_system);
+                store.checkpoint();
+                store.edit_entries(|entries| {
+                    let mut idx = 0;
+                    entries.retain(|_| {
+                        let keep = !removing.contains(&idx);
+                        idx += 1;
+                        keep
+                    });
+                });
+                store.selected.clear();
+                store.cursor = None;
+            }
+            self.status = trf(
+                Text::RemovedFromBoth,
+                &[
+                    &from_user.len(),
+                    &self.panel_title(false),
+                    &from_system.len(),
+                    &self.panel_title(true),
+                ],
+            );
+        }
+
+        fn open_remove_invalid_dialog(&mut self, is_system: bool) {
+            let parts = self.store(is_system).parts.clone();
+            if invalid_entries(&parts, false).is_empty() {
+                self.status = trf(Text::NoInvalid, &[&self.panel_title(is_system)]);
+                return;
+            }
+            self.remove_invalid_dialog = RemoveInvalidDialogState {
+                open: true,
+                is_system,
+                snapshot: parts,
+                skip_unc: true,
+            };
+        }
+
+        fn draw_remove_invalid_dialog(&mut self, ctx: &egui::Context) {
+            if !self.remove_invalid_dialog.open {
+                return;
+            }
+
+            let mut open = self.remove_invalid_dialog.open;
+            let mut do_apply = false;
+            let mut cancelled = false;
+            let title = trf(
+                Text::RemoveInvalidTitle,
+                &[&self.panel_title(self.remove_invalid_dialog.is_system)],
+            );
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([720.0, 360.0])
+                .show(ctx, |ui| {
+                    let dialog = &mut self.remove_invalid_dialog;
+                    ui.checkbox(&mut dialog.skip_unc, tr(Text::KeepUnc))
+                        .on_hover_text(tr(Text::KeepUncHint));
+                    let doomed = invalid_entries(&dialog.snapshot, dialog.skip_unc);
+                    ui.label(
+                        RichText::new(tr(Text::UndefinedKept))
+                            .small()
+                            .color(Color32::from_gray(170)),
+                    );
+                    ui.add_space(6.0);
+                    ui.label(trf(Text::WillBeRemoved, &[&doomed.len()]));
+                    ScrollArea::vertical()
+                        .id_source("remove_invalid")
+                        .max_height(260.0)
+                        .show(ui, |ui| {
+                            for &idx in &doomed {
+                                ui.label(
+                                    RichText::new(format!(
+                                        "- {}",
+                                        expand_env_vars(&dialog.snapshot[idx])
+                                    ))
+                                    .monospace()
+                                    .color(ERROR_COLOR),
+                                );
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add_enabled(!doomed.is_empty(), egui::Button::new(tr(Text::Remove)))
+                            .clicked()
+                        {
+                            do_apply = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_apply |= submit;
+            cancelled |= dismiss;
+            self.remove_invalid_dialog.open = open && !do_apply && !cancelled;
+            if do_apply {
+                self.apply_remove_invalid();
+            }
+        }
+
+        fn apply_remove_invalid(&mut self) {
+            let dialog = &self.remove_invalid_dialog;
+            let is_system = dialog.is_system;
+            if self.store(is_system).parts != dialog.snapshot {
+                self.status = tr(Text::RemoveInvalidChanged).to_string();
+                return;
+            }
+
+            let doomed = invalid_entries(&dialog.snapshot, dialog.skip_unc)
+                .into_iter()
+                .collect::<HashSet<_>>();
+            let store = self.store_mut(is_system);
+            let keep = store.selection_keys();
+            store.checkpoint();
+            store.edit_entries(|entries| {
+                let mut idx = 0;
+                entries.retain(|_| {
+                    let kept = !doomed.contains(&idx);
+                    idx += 1;
+                    kept
+                });
+            });
+            store.reselect(&keep);
+            self.status = trf(
+                Text::RemovedInvalid,
+                &[&doomed.len(), &self.panel_title(is_system)],
+            );
+        }
+
+        fn open_split_dialog(&mut self) {
+            let store = self.store(true);
+            let indices = overflow_entries(
+                &store.parts,
+                &store.disabled_flags(),
+                &self.settings.critical_paths,
+                LONG_PATH_CHARS,
+            );
+            if indices.is_empty() {
+                self.status = tr(Text::OverflowCritical).to_string();
+                return;
+            }
+            self.split_dialog = SplitDialogState {
+                open: true,
+                snapshot: store.parts.clone(),
+                indices,
+            };
+        }
+
+        fn draw_split_dialog(&mut self, ctx: &egui::Context) {
+            if !self.split_dialog.open {
+                return;
+            }
+
+            let mut open = self.split_dialog.open;
+            let mut do_apply = false;
+            let mut cancelled = false;
+            let in_user = Arc::clone(&self.store_mut(false).derived().keys);
+            let store = self.store(true);
+            let disabled = store.disabled_flags();
+            let remaining = store
+                .parts
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| !self.split_dialog.indices.contains(idx) && !disabled[*idx])
+                .map(|(_, part)| part.clone())
+                .collect::<Vec<_>>();
+            let after = join_path(&remaining).encode_utf16().count();
+
+            egui::Window::new(tr(Text::OverflowTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_size([720.0, 360.0])
+                .show(ctx, |ui| {
+                    let dialog = &self.split_dialog;
+                    ui.label(trf(
+                        Text::OverflowBody,
+                        &[&dialog.indices.len(), &group_thousands(after)],
+                    ));
+                    ui.add_space(6.0);
+                    ScrollArea::vertical()
+                        .id_source("split_overflow")
+                        .max_height(260.0)
+                        .show(ui, |ui| {
+                            for &idx in &dialog.indices {
+                                let part = &dialog.snapshot[idx];
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        RichText::new(format!("#{}  {part}", idx + 1)).monospace(),
+                                    );
+                                    if in_user.contains(&normalize_for_compare(part)) {
+                                        ui.label(
+                                            RichText::new(tr(Text::AlreadyInUser))
+                                                .small()
+                                                .color(Color32::from_gray(170)),
+                                        );
+                                    }
+                                });
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Move)).clicked() {
+                            do_apply = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_apply |= submit;
+            cancelled |= dismiss;
+            self.split_dialog.open = open && !do_apply && !cancelled;
+            if do_apply {
+                if self.store(true).parts != self.split_dialog.snapshot {
+                    self.status = tr(Text::OverflowChanged).to_string();
+                    return;
+                }
+                let indices = std::mem::take(&mut self.split_dialog.indices);
+                self.move_to_other_scope(true, &indices);
+            }
+        }
+
+        fn open_cleanup_dialog(&mut self, is_system: bool) {
+            self.cleanup_dialog.open = true;
+            self.cleanup_dialog.is_system = is_system;
+            self.cleanup_dialog.preview = None;
+        }
+
+        fn draw_cleanup_dialog(&mut self, ctx: &egui::Context) {
+            if !self.cleanup_dialog.open {
+                return;
+            }
+
+            let is_system = self.cleanup_dialog.is_system;
+            let options = self.cleanup_dialog.options;
+            if self
+                .cleanup_dialog
+                .preview
+                .as_ref()
+                .is_none_or(|preview| preview.options != options)
+            {
+                let preview = cleanup_entries(&self.store(is_system).parts, options);
+                self.cleanup_dialog.preview = Some(preview);
+            }
+
+            let mut open = self.cleanup_dialog.open;
+            let mut do_apply = false;
+            let before = self.store(is_system).parts.len();
+            let title = tr(if is_system {
+                Text::CleanupSystemTitle
+            } else {
+                Text::CleanupUserTitle
+            });
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_width(760.0)
+                .show(ctx, |ui| {
+                    let dialog = &mut self.cleanup_dialog;
+                    ui.checkbox(&mut dialog.options.trim, tr(Text::CleanupTrimOption));
+                    ui.checkbox(
+                        &mut dialog.options.normalize,
+                        tr(Text::CleanupNormalizeOption),
+                    );
+                    ui.checkbox(&mut dialog.options.dedupe, tr(Text::CleanupDedupeOption));
+                    ui.checkbox(
+                        &mut dialog.options.remove_missing,
+                        tr(Text::CleanupMissingOption),
+                    );
+
+                    ui.add_space(8.0);
+                    let Some(preview) = dialog.preview.as_ref() else {
+                        return;
+                    };
+                    ui.label(trf(
+                        Text::EntriesBeforeAfter,
+                        &[&before, &preview.result.len()],
+                    ));
+                    ScrollArea::vertical()
+                        .id_source("cleanup_preview")
+                        .max_height(320.0)
+                        .show(ui, |ui| {
+                            for step in &preview.steps {
+                                ui.strong(format!("{} ({})", tr(step.name), step.changes.len()));
+                                for change in &step.changes {
+                                    ui.label(RichText::new(change).monospace().small());
+                                }
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Apply)).clicked() {
+                            do_apply = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            dialog.open = false;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_apply |= submit;
+            open &= !dismiss;
+            self.cleanup_dialog.open = open && self.cleanup_dialog.open;
+            if do_apply {
+                self.apply_cleanup();
+            }
+        }
+
+        fn apply_cleanup(&mut self) {
+            let is_system = self.cleanup_dialog.is_system;
+            let Some(preview) = self.cleanup_dialog.preview.take() else {
+                return;
+            };
+            let current = &self.store(is_system).parts;
+            if !confirm_critical_removal(current, &preview.result, &self.settings.critical_paths) {
+                return;
+            }
+            let store = self.store_mut(is_system);
+            let before = store.parts.len();
+            if store.parts != preview.result {
+                store.checkpoint();
+                store.replace_parts(preview.result);
+                store.selected.clear();
+            }
+            let after = store.parts.len();
+            self.cleanup_dialog.open = false;
+            self.status = trf(
+                Text::CleanedUp,
+                &[&self.panel_title(is_system), &before, &after],
+            );
+        }
+
+        fn draw_resolve_dialog(&mut self, ctx: &egui::Context) {
+            if !self.resolve_dialog.open {
+                return;
+            }
+
+            let mut open = self.resolve_dialog.open;
+            let mut do_resolve = false;
+
+            egui::Window::new(tr(Text::ResolveTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_width(760.0)
+                .show(ctx, |ui| {
+                    ui.label(tr(Text::ExecutableName));
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            TextEdit::singleline(&mut self.resolve_dialog.input)
+                                .desired_width(520.0)
+                                .hint_text("python"),
+                        );
+                        if ui.button(tr(Text::ResolveButton)).clicked() {
+                            do_resolve = true;
+                        }
+                    });
+
+                    ui.add_space(8.0);
+                    if self.resolve_dialog.searched && self.resolve_dialog.results.is_empty() {
+                        ui.label(RichText::new(tr(Text::NoMatch)).color(ERROR_COLOR));
+                    }
+
+                    ScrollArea::vertical()
+                        .id_source("resolve_results")
+                        .max_height(320.0)
+                        .show(ui, |ui| {
+                            for (n, found) in self.resolve_dialog.results.iter().enumerate() {
+                                let scope = scope_label(found.is_system);
+                                let text = found.file.display().to_string();
+                                if n == 0 {
+                                    ui.label(RichText::new(text).strong());
+                                } else {
+                                    ui.label(
+                                        RichText::new(trf(Text::Shadowed, &[&text]))
+                                            .color(Color32::from_gray(150)),
+                                    );
+                                }
+                                ui.label(
+                                    RichText::new(trf(Text::FromEntry, &[&scope, &found.entry]))
+                                    .small()
+                                    .color(Color32::from_gray(170)),
+                                );
+                            }
+                        });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            if do_resolve || submit {
+                self.run_resolve();
+            }
+            self.resolve_dialog.open = open && !dismiss;
+        }
+
+        fn draw_import_dialog(&mut self, ctx: &egui::Context) {
+            if !self.import_dialog.open {
+                return;
+            }
+
+            let mut open = self.import_dialog.open;
+            let mut do_apply = false;
+
+            egui::Window::new(tr(Text::ImportTitle))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(true)
+                .default_width(760.0)
+                .show(ctx, |ui| {
+                    let dialog = &mut self.import_dialog;
+                    ui.label(RichText::new(&dialog.source).small());
+                    ui.add_space(6.0);
+
+                    if dialog.sections.len() > 1 {
+                        let current = dialog.sections[dialog.section].label();
+                        egui::ComboBox::from_label(tr(Text::SourceKey))
+                            .selected_text(current)
+                            .show_ui(ui, |ui| {
+                                for (idx, section) in dialog.sections.iter().enumerate() {
+                                    ui.selectable_value(&mut dialog.section, idx, section.label());
+                                }
+                            });
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr(Text::Target));
+                        for is_system in [false, true] {
+                            let label = trf(Text::ScopePath, &[&scope_label(is_system)]);
+                            ui.radio_value(&mut dialog.is_system, is_system, label);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr(Text::Mode));
+                        ui.radio_value(
+                            &mut dialog.mode,
+                            ImportMode::Replace,
+                            tr(Text::ReplaceButton),
+                        );
+                        ui.radio_value(&mut dialog.mode, ImportMode::Append, tr(Text::Append));
+                        ui.radio_value(
+                            &mut dialog.mode,
+                            ImportMode::AppendDedupe,
+                            tr(Text::AppendDedupe),
+                        );
+                    });
+
+                    ui.add_space(8.0);
+                    let parts = &dialog.sections[dialog.section].parts;
+                    ui.label(trf(Text::EntryCount, &[&parts.len()]));
+                    let mut preview = parts.join("\n");
+                    ui.add(
+                        TextEdit::multiline(&mut preview)
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(12)
+                            .interactive(false),
+                    );
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::ImportButton)).clicked() {
+                            do_apply = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            dialog.open = false;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_apply |= submit;
+            open &= !dismiss;
+            if do_apply {
+                self.apply_import();
+            }
+            self.import_dialog.open = open && self.import_dialog.open;
+        }
+
+        fn refresh_env_browser(&mut self) {
+            self.env_browser.rows = [false, true]
+                .into_iter()
+                .flat_map(read_env_values)
+                .collect();
+        }
+
+        fn open_env_edit(&mut self, row: Option<usize>) {
+            let edit = match row.and_then(|idx| self.env_browser.rows.get(idx)) {
+                Some(row) => EnvEditState {
+                    open: true,
+                    is_system: row.is_system,
+                    original_name: Some(row.name.clone()),
+                    name: row.name.clone(),
+                    value: row.value.clone(),
+                    expand: row.vtype == REG_EXPAND_SZ,
+                },
+                None => EnvEditState {
+                    open: true,
+                    ..EnvEditState::default()
+                },
+            };
+            self.env_browser.edit = edit;
+        }
+
+        fn require_admin_for(&self, is_system: bool) -> bool {
+            if is_system && !self.is_admin {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title(tr(Text::AdminRequired))
+                    .set_description(tr(Text::SystemEnvAdmin))
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+                return false;
+            }
+            true
+        }
+
+        fn commit_env_edit(&mut self) {
+            let edit = &self.env_browser.edit;
+            let is_system = edit.is_system;
+            let name = edit.name.trim().to_string();
+            let value = edit.value.clone();
+            let vtype = if edit.expand { REG_EXPAND_SZ } else { REG_SZ };
+            let original = edit.original_name.clone();
+
+            if name.is_empty() {
+                return;
+            }
+
+            if self.dry_run {
+                let mut lines = vec![describe_write(is_system, &name, Some(&value), &vtype)];
+                if let Some(old) = original.filter(|old| !old.eq_ignore_ascii_case(&name)) {
+                    lines.push(describe_write(is_system, &old, None, &vtype));
+                }
+                self.env_browser.edit.open = false;
+                self.report_dry_run(&lines);
+                return;
+            }
+
+            if !self.require_admin_for(is_system) {
+                return;
+            }
+
+            let (root, subkey) = env_key(is_system);
+            let result = write_reg_value(root, subkey, &name, &value, vtype).and_then(|()| {
+                match original.as_deref() {
+                    Some(old) if !old.eq_ignore_ascii_case(&name) => {
+                        delete_reg_value(root, subkey, old)
+                    }
+                    _ => Ok(()),
+                }
+            });
+
+            match result {
+                Ok(()) => {
+                    self.broadcast();
+                    self.env_browser.edit.open = false;
+                    self.status = match original {
+                        Some(old) if old != name => trf(Text::RenamedVariable, &[&old, &name]),
+                        Some(_) => trf(Text::SavedVariable, &[&name]),
+                        None => trf(Text::AddedVariable, &[&name]),
+                    };
+                    self.refresh_env_browser();
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::SaveFailed))
+                        .set_description(err.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn delete_env_var(&mut self, idx: usize) {
+            let Some(row) = self.env_browser.rows.get(idx) else {
+                return;
+            };
+            let is_system = row.is_system;
+            let name = row.name.clone();
+            if self.dry_run {
+                let line = describe_write(is_system, &name, None, &row.vtype);
+                self.report_dry_run(&[line]);
+                return;
+            }
+            if !self.require_admin_for(is_system) {
+                return;
+            }
+
+            let scope = scope_label(is_system);
+            let confirmed = matches!(
+                MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title(tr(Text::DeleteVariableTitle))
+                    .set_description(trf(Text::DeleteVariableBody, &[&scope, &name]))
+                    .set_buttons(MessageButtons::OkCancel)
+                    .show(),
+                MessageDialogResult::Ok
+            );
+            if !confirmed {
+                return;
+            }
+
+            let (root, subkey) = env_key(is_system);
+            match delete_reg_value(root, subkey, &name) {
+                Ok(()) => {
+                    self.broadcast();
+                    self.status = trf(Text::DeletedVariable, &[&scope, &name]);
+                    self.refresh_env_browser();
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::DeleteFailed))
+                        .set_description(err.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn draw_env_browser(&mut self, ui: &mut egui::Ui) {
+            let mut do_add = false;
+            let mut do_refresh = false;
+            let mut edit_row: Option<usize> = None;
+            let mut delete_row: Option<usize> = None;
+
+            ui.horizontal(|ui| {
+                ui.label(tr(Text::Filter));
+                ui.add(
+                    TextEdit::singleline(&mut self.env_browser.filter)
+                        .hint_text(tr(Text::EnvFilterHint))
+                        .desired_width(360.0),
+                );
+                if ui.button(tr(Text::Add)).clicked() {
+                    do_add = true;
+                }
+                if ui.button(tr(Text::Refresh)).clicked() {
+                    do_refresh = true;
+                }
+            });
+            ui.add_space(8.0);
+
+            let filter = self.env_browser.filter.trim().to_lowercase();
+            egui::Frame::canvas(ui.style()).show(ui, |ui| {
+                ScrollArea::both()
+                    .id_source("env_browser")
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        egui::Grid::new("env_grid")
+                            .striped(true)
+                            .num_columns(5)
+                            .show(ui, |ui| {
+                                ui.strong(tr(Text::Scope));
+                                ui.strong(tr(Text::Name));
+                                ui.strong(tr(Text::Type));
+                                ui.strong(tr(Text::Value));
+                                ui.label("");
+                                ui.end_row();
+
+                                for (idx, row) in self.env_browser.rows.iter().enumerate() {
+                                    if !filter.is_empty()
+                                        && !row.name.to_lowercase().contains(&filter)
+                                        && !row.value.to_lowercase().contains(&filter)
+                                    {
+                                        continue;
+                                    }
+                                    ui.label(scope_label(row.is_system));
+                                    ui.label(&row.name);
+                                    ui.label(reg_type_name(&row.vtype));
+                                    ui.label(&row.value);
+                                    ui.horizontal(|ui| {
+                                        if row.is_editable() {
+                                            if ui.small_button(tr(Text::EditButton)).clicked() {
+                                                edit_row = Some(idx);
                                             }
+                                        } else {
+                                            ui.label(
+                                                RichText::new(tr(Text::ReadOnly))
+                                                    .small()
+                                                    .color(Color32::from_gray(150)),
+                                            );
                                         }
-                                    }
-                                });
+                                        if ui.small_button(tr(Text::Delete)).clicked() {
+                                            delete_row = Some(idx);
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+            if do_refresh {
+                self.refresh_env_browser();
+                self.status = tr(Text::ReloadedVariables).to_string();
+            }
+            if do_add {
+                self.open_env_edit(None);
+            }
+            if let Some(idx) = edit_row {
+                self.open_env_edit(Some(idx));
+            }
+            if let Some(idx) = delete_row {
+                self.delete_env_var(idx);
+            }
+        }
+
+        fn draw_env_edit_dialog(&mut self, ctx: &egui::Context) {
+            if !self.env_browser.edit.open {
+                return;
+            }
+
+            let mut open = self.env_browser.edit.open;
+            let mut do_save = false;
+            let title = tr(if self.env_browser.edit.original_name.is_some() {
+                Text::EditVariableTitle
+            } else {
+                Text::AddVariableTitle
+            });
+
+            egui::Window::new(title)
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .default_width(640.0)
+                .show(ctx, |ui| {
+                    let edit = &mut self.env_browser.edit;
+                    ui.horizontal(|ui| {
+                        ui.label(tr(Text::Scope));
+                        ui.add_enabled_ui(edit.original_name.is_none(), |ui| {
+                            for is_system in [false, true] {
+                                let label = scope_label(is_system);
+                                ui.radio_value(&mut edit.is_system, is_system, label)
+                                    .on_disabled_hover_text(tr(Text::FixedScope));
+                            }
+                        });
+                    });
+                    ui.label(tr(Text::Name));
+                    ui.add(TextEdit::singleline(&mut edit.name).desired_width(f32::INFINITY));
+                    ui.label(tr(Text::Value));
+                    ui.add(
+                        TextEdit::multiline(&mut edit.value)
+                            .desired_rows(3)
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.checkbox(&mut edit.expand, tr(Text::Expandable));
+
+                    ui.add_space(8.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(Text::Save)).clicked() {
+                            do_save = true;
+                        }
+                        if ui.button(tr(Text::Cancel)).clicked() {
+                            edit.open = false;
+                        }
+                    });
+                });
+
+            let (submit, dismiss) = dialog_keys(ctx);
+            do_save |= submit;
+            open &= !dismiss;
+            self.env_browser.edit.open = open && self.env_browser.edit.open;
+            if do_save {
+                self.commit_env_edit();
+            }
+        }
+
+        fn disabled_key(&self, is_system: bool) -> String {
+            let scope = match &self.hive {
+                _ if is_system => "system".to_string(),
+                Some(hive) => format!("hive:{}", hive.user),
+                None => "user".to_string(),
+            };
+            format!("{scope}:{}", self.store(is_system).var_name).to_lowercase()
+        }
+
+        /// The registry only holds enabled entries, so a freshly loaded store gets its disabled
+        /// entries put back at their remembered positions; after that the store is the source
+        /// of truth and its disabled entries are copied out for persistence.
+        fn sync_disabled(&mut self) {
+            for is_system in [false, true] {
+                let key = self.disabled_key(is_system);
+                if !self.store(is_system).disabled_synced {
+                    let remembered = self.disabled_entries.get(&key).cloned().unwrap_or_default();
+                    let store = self.store_mut(is_system);
+                    store.disabled_synced = true;
+                    store.restore_disabled(remembered);
+                    store.generation += 1;
+                    continue;
+                }
+
+                let current = self.store(is_system).remembered_disabled();
+                if current.is_empty() {
+                    self.disabled_entries.remove(&key);
+                } else {
+                    self.disabled_entries.insert(key, current);
+                }
+            }
+        }
+
+        fn open_other_user(&mut self) {
+            if !self.is_admin {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title(tr(Text::AdminRequired))
+                    .set_description(tr(Text::HiveAdmin))
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+                return;
+            }
+            if !self.confirm_discard_user() {
+                return;
+            }
+            let Some(profile) = FileDialog::new()
+                .set_title(tr(Text::ChooseProfile))
+                .set_directory(r"C:\Users")
+                .pick_folder()
+            else {
+                return;
+            };
+
+            match LoadedHive::load(&profile) {
+                Ok(hive) => {
+                    self.hive = Some(hive);
+                    self.user = load_store(false, "Path");
+                    self.status = trf(Text::Editing, &[&self.panel_title(false)]);
+                }
+                Err(err) => {
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::ProfileFailed))
+                        .set_description(err)
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+
+        fn close_other_user(&mut self) {
+            if !self.confirm_discard_user() {
+                return;
+            }
+            let user = self.hive.take().map(|hive| hive.user.clone());
+            self.user = load_store(false, "Path");
+            if let Some(user) = user {
+                self.status = trf(Text::ClosedHive, &[&user]);
+            }
+        }
+
+        fn confirm_discard_user(&self) -> bool {
+            !self.user.is_dirty()
+                || matches!(
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Warning)
+                        .set_title(tr(Text::DiscardTitle))
+                        .set_description(trf(Text::DiscardUserBody, &[&self.panel_title(false)]))
+                        .set_buttons(MessageButtons::OkCancel)
+                        .show(),
+                    MessageDialogResult::Ok
+                )
+        }
+
+        fn restart_elevated(&mut self) {
+            match restart_as_admin() {
+                Ok(()) => {
+                    process::exit(0);
+                }
+                Err(err) => {
+                    self.recheck_admin();
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title(tr(Text::RestartFailed))
+                        .set_description(err.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                }
+            }
+        }
+    }
+
+    impl eframe::App for PathEditorApp {
+        fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+            if ctx.input(|i| i.viewport().close_requested())
+                && !self.allow_close
+                && self.has_unsaved_changes()
+            {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.confirm_exit(ctx);
+            }
+
+            self.poll_external_changes();
+            self.poll_elevated_save(ctx);
+            self.sync_disabled();
+
+            if self.view == View::Path
+                && ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::N))
+            {
+                self.open_add_dialog(self.settings.add_to_system);
+            }
+
+            if self.view == View::Path && !self.any_dialog_open() {
+                use egui::{Key, Modifiers};
+                // Ctrl+S would also match Ctrl+Shift+S, so the Shift variant is consumed first.
+                let (save_all, save_one) = ctx.input_mut(|i| {
+                    (
+                        i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::S),
+                        i.consume_key(Modifiers::COMMAND, Key::S),
+                    )
+                });
+                if save_all {
+                    self.request_save_all();
+                } else if save_one {
+                    self.request_save_one(self.focused_system);
+                }
+            }
+
+            egui::TopBottomPanel::top("header").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(APP_TITLE);
+                    ui.separator();
+                    let mut view = self.view;
+                    ui.selectable_value(&mut view, View::Path, tr(Text::Lists));
+                    ui.selectable_value(&mut view, View::Variables, tr(Text::AllVariables));
+                    ui.selectable_value(&mut view, View::Effective, tr(Text::EffectivePath));
+                    if view != self.view {
+                        self.view = view;
+                        match view {
+                            View::Variables => self.refresh_env_browser(),
+                            View::Effective => self.start_shadow_scan(ui.ctx(), false),
+                            View::Path => {}
+                        }
+                    }
+                    ui.separator();
+                    ui.label(tr(Text::Tagline));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if !self.is_admin
+                            && ui
+                                .add(elevated_button(
+                                    &self.shield,
+                                    RichText::new(tr(Text::RestartAdmin))
+                                        .color(ui.visuals().strong_text_color())
+                                        .strong(),
+                                ))
+                                .clicked()
+                        {
+                            self.restart_elevated();
+                        }
+                        if ui
+                            .small_button(tr(Text::Recheck))
+                            .on_hover_text(tr(Text::RecheckHint))
+                            .clicked()
+                            && !self.recheck_admin()
+                        {
+                            let text = if self.is_admin {
+                                Text::PrivilegesAdmin
+                            } else {
+                                Text::PrivilegesStandard
+                            };
+                            self.status = tr(text).to_string();
+                        }
+                        if ui.button(tr(Text::Reload)).clicked() {
+                            self.reload();
+                        }
+                        if ui.button(tr(Text::Resolve)).clicked() {
+                            self.open_resolve_dialog();
+                        }
+                        if ui
+                            .button(tr(Text::Shadowing))
+                            .on_hover_text(tr(Text::ShadowingHint))
+                            .clicked()
+                        {
+                            self.open_shadow_dialog(ctx, false);
+                        }
+                        if ui.button(tr(Text::Compare)).clicked() {
+                            self.open_compare_dialog();
+                        }
+                        if ui
+                            .button(tr(Text::MergeButton))
+                            .on_hover_text(tr(Text::MergeButtonHint))
+                            .clicked()
+                        {
+                            self.open_cross_duplicates_dialog();
+                        }
+                        if self.hive.is_some() {
+                            if ui.button(tr(Text::CloseOtherUser)).clicked() {
+                                self.close_other_user();
+                            }
+                        } else if ui
+                            .add(elevated_button(&self.shield, tr(Text::OpenOtherUser)))
+                            .on_hover_text(tr(Text::OpenOtherUserHint))
+                            .clicked()
+                        {
+                            self.open_other_user();
+                        }
+                        if ui.button(tr(Text::Import)).clicked() {
+                            self.start_import();
+                        }
+                        if ui.button(tr(Text::Settings)).clicked() {
+                            self.open_settings_dialog();
+                        }
+                        ui.menu_button("JSON", |ui| {
+                            if ui.button(tr(Text::ExportJson)).clicked() {
+                                ui.close_menu();
+                                self.export_json();
+                            }
+                            if ui.button(tr(Text::ImportJson)).clicked() {
+                                ui.close_menu();
+                                self.import_json();
+                            }
+                        });
+                        ui.menu_button(tr(Text::ExportReg), |ui| {
+                            let user = trf(Text::ScopePath, &[&scope_label(false)]);
+                            let system = trf(Text::ScopePath, &[&scope_label(true)]);
+                            if ui.button(user).clicked() {
+                                ui.close_menu();
+                                self.export_reg(true, false);
+                            }
+                            if ui.button(system).clicked() {
+                                ui.close_menu();
+                                self.export_reg(false, true);
+                            }
+                            if ui.button(tr(Text::Both)).clicked() {
+                                ui.close_menu();
+                                self.export_reg(true, true);
+                            }
+                        });
+                        ui.menu_button(tr(Text::ExportScript), |ui| {
+                            for (is_system, powershell) in
+                                [(false, false), (false, true), (true, false), (true, true)]
+                            {
+                                let extension = if powershell { "ps1" } else { "bat" };
+                                let label =
+                                    trf(Text::ScriptTarget, &[&scope_label(is_system), &extension]);
+                                if ui.button(label).clicked() {
+                                    ui.close_menu();
+                                    self.export_script(is_system, powershell);
+                                }
+                            }
                         });
+                    });
+                });
+                if self.view == View::Path {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(Text::SearchBoth));
+                        ui.add(
+                            TextEdit::singleline(&mut self.global_search)
+                                .id(egui::Id::new(GLOBAL_SEARCH_ID))
+                                .hint_text(tr(Text::SearchBothHint))
+                                .desired_width(320.0),
+                        );
+                        let query = self.global_search.trim().to_lowercase();
+                        if !query.is_empty() {
+                            let user = self.user.search_matches(&query).len();
+                            let system = self.system.search_matches(&query).len();
+                            ui.label(
+                                RichText::new(trf(Text::SearchCounts, &[&user, &system]))
+                                    .color(Color32::from_gray(170)),
+                            );
+                            if ui
+                                .small_button("x")
+                                .on_hover_text(tr(Text::ClearSearch))
+                                .clicked()
+                            {
+                                self.global_search.clear();
+                            }
+                        }
+                    });
+                }
+            });
+
+            self.draw_external_change_banner(ctx);
+            self.draw_clipboard_banner(ctx);
+            self.draw_session_changes_banner(ctx);
+
+            egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(&self.status).small());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .button(
+                                RichText::new(tr(Text::SaveAll))
+                                    .strong()
+                                    .color(ui.visuals().strong_text_color()),
+                            )
+                            .clicked()
+                        {
+                            self.request_save_all();
+                        }
+                        if ui
+                            .button(tr(Text::ApplyToSession))
+                            .on_hover_text(tr(Text::ApplyToSessionHint))
+                            .clicked()
+                        {
+                            self.apply_to_session();
+                        }
+                        ui.checkbox(&mut self.dry_run, tr(Text::DryRun))
+                            .on_hover_text(tr(Text::DryRunHint));
+                        ui.toggle_value(&mut self.history_open, tr(Text::History))
+                            .on_hover_text(tr(Text::HistoryHint));
+                        ui.separator();
+                        if self.broadcast_failed {
+                            if ui.button(tr(Text::RetryBroadcast)).clicked() {
+                                self.retry_broadcast();
+                            }
+                            ui.label(
+                                RichText::new(tr(Text::BroadcastFailed))
+                                    .small()
+                                    .color(WARNING_COLOR),
+                            )
+                            .on_hover_text(tr(Text::BroadcastFailedHint));
+                            ui.separator();
+                        }
+                        let undefined = self.user.undefined_count() + self.system.undefined_count();
+                        if undefined > 0 {
+                            ui.label(
+                                RichText::new(trf(Text::UndefinedCount, &[&undefined]))
+                                .small()
+                                .color(WARNING_COLOR),
+                            );
+                            ui.separator();
+                        }
+                        if self.wow64 {
+                            ui.label(
+                                RichText::new(tr(Text::Wow64View))
+                                    .small()
+                                    .color(WARNING_COLOR),
+                            )
+                            .on_hover_text(tr(Text::Wow64Hint));
+                        } else {
+                            ui.label(RichText::new(tr(Text::NativeView)).small());
+                        }
+                    });
+                });
+            });
+            self.draw_history_panel(ctx);
+
+            egui::CentralPanel::default().show(ctx, |ui| match self.view {
+                View::Path => {
+                    ui.columns(2, |cols| {
+                        self.draw_panel(&mut cols[0], false);
+                        self.draw_panel(&mut cols[1], true);
+                    });
+                }
+                View::Variables => self.draw_env_browser(ui),
+                View::Effective => self.draw_effective_view(ui),
+            });
+            self.handle_dropped_files(ctx);
+
+            self.draw_add_dialog(ctx);
+            self.draw_expanded_dialog(ctx);
+            self.draw_move_dialog(ctx);
+            self.draw_resolve_dialog(ctx);
+            self.draw_shadow_dialog(ctx);
+            self.draw_import_dialog(ctx);
+            self.draw_env_edit_dialog(ctx);
+            self.draw_save_confirm_dialog(ctx);
+            self.draw_replace_dialog(ctx);
+            self.draw_cleanup_dialog(ctx);
+            self.draw_settings_dialog(ctx);
+            self.draw_compare_dialog(ctx);
+            self.draw_duplicates_dialog(ctx);
+            self.draw_cross_duplicates_dialog(ctx);
+            self.draw_remove_invalid_dialog(ctx);
+            self.draw_split_dialog(ctx);
+            self.draw_changes_dialog(ctx);
+            self.draw_session_changes_dialog(ctx);
+            self.draw_note_dialog(ctx);
+            self.draw_executables_dialog(ctx);
+            self.draw_toasts(ctx);
+            self.flush_action_log();
+            self.sync_window_title(ctx);
+        }
+
+        fn save(&mut self, storage: &mut dyn eframe::Storage) {
+            let panel = if self.focused_system { "system" } else { "user" };
+            storage.set_string(FOCUSED_PANEL_KEY, panel.to_string());
+            storage.set_string(DISABLED_KEY, format_disabled(&self.disabled_entries));
+            storage.set_string(NOTES_KEY, format_notes(&self.notes));
+            storage.set_string(USER_FILTER_KEY, self.user.filter.clone());
+            if self.hive.is_none() && self.user.is_path() && PATH_FILE.get().is_none() {
+                storage.set_string(LAST_USER_PATH_KEY, join_path(&self.user.saved_parts));
+            }
+            if self.system.is_path() {
+                storage.set_string(LAST_SYSTEM_PATH_KEY, join_path(&self.system.saved_parts));
+            }
+            storage.set_string(SYSTEM_FILTER_KEY, self.system.filter.clone());
+            self.settings.save(storage);
+        }
+    }
+
+    /// One "key\tposition\tenabled copies\tentry" line per disabled entry.
+    fn format_disabled(entries: &HashMap<String, Vec<DisabledEntry>>) -> String {
+        entries
+            .iter()
+            .flat_map(|(key, list)| {
+                list.iter().map(move |entry| {
+                    format!(
+                        "{key}\t{}\t{}\t{}",
+                        entry.position, entry.enabled_copies, entry.value
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// One "key\tnote" line per note.
+    fn format_notes(notes: &BTreeMap<String, String>) -> String {
+        notes
+            .iter()
+            .map(|(key, note)| format!("{key}\t{note}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn load_notes(storage: &dyn eframe::Storage) -> BTreeMap<String, String> {
+        storage
+            .get_string(NOTES_KEY)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(key, note)| (key.to_string(), note.to_string()))
+            .collect()
+    }
+
+    fn load_disabled(storage: &dyn eframe::Storage) -> HashMap<String, Vec<DisabledEntry>> {
+        let mut entries: HashMap<String, Vec<DisabledEntry>> = HashMap::new();
+        for line in storage.get_string(DISABLED_KEY).unwrap_or_default().lines() {
+            let mut fields = line.splitn(4, '\t');
+            if let (Some(key), Some(Ok(position)), Some(Ok(enabled_copies)), Some(value)) = (
+                fields.next(),
+                fields.next().map(str::parse::<usize>),
+                fields.next().map(str::parse::<usize>),
+                fields.next(),
+            ) {
+                entries.entry(key.to_string()).or_default().push(DisabledEntry {
+                    position,
+                    enabled_copies,
+                    value: value.to_string(),
+                });
+            }
+        }
+        entries
+    }
+
+    fn apply_style(ctx: &egui::Context, light: bool) {
+        let mut style = (*ctx.style()).clone();
+        style.spacing.item_spacing = egui::vec2(8.0, 8.0);
+        style.spacing.button_padding = egui::vec2(12.0, 8.0);
+        if light {
+            style.visuals = egui::Visuals::light();
+            style.visuals.window_fill = Color32::from_rgb(250, 251, 253);
+            style.visuals.panel_fill = Color32::from_rgb(243, 245, 248);
+            style.visuals.widgets.hovered.bg_fill = Color32::from_rgb(204, 228, 247);
+            style.visuals.widgets.inactive.bg_fill = Color32::from_rgb(225, 229, 235);
+            style.visuals.selection.bg_fill = Color32::from_rgb(153, 201, 239);
+            style.visuals.hyperlink_color = Color32::from_rgb(0, 102, 180);
+        } else {
+            style.visuals = egui::Visuals::dark();
+            style.visuals.window_fill = Color32::from_rgb(20, 24, 30);
+            style.visuals.panel_fill = Color32::from_rgb(17, 20, 26);
+            style.visuals.widgets.hovered.bg_fill = Color32::from_rgb(0, 96, 172);
+            style.visuals.widgets.inactive.bg_fill = Color32::from_rgb(37, 44, 54);
+            style.visuals.hyperlink_color = Color32::from_rgb(0, 153, 255);
+        }
+        style.visuals.widgets.active.bg_fill = ACCENT;
+        ctx.set_style(style);
+    }
+
+    fn system_uses_light_theme() -> bool {
+        RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey_with_flags(PERSONALIZE_KEY, KEY_READ)
+            .and_then(|key| key.get_value::<u32, _>("AppsUseLightTheme"))
+            .is_ok_and(|value| value != 0)
+    }
+
+    /// Splits Add dialog input on `;` and line breaks so a pasted PATH becomes separate entries.
+    fn split_input(input: &str) -> Vec<String> {
+        split_path(&input.replace(['\r', '\n'], ";"))
+    }
+
+    fn entry_problem(entry: &str) -> Option<&'static str> {
+        let expanded = expand_env_vars(entry);
+        if entry.contains(';') {
+            Some(tr(Text::ProblemSemicolon))
+        } else if entry.contains('"') {
+            Some(tr(Text::ProblemQuotes))
+        } else if entry.trim() != entry {
+            // Windows keeps the spaces, so the folder is not found.
+            Some(tr(Text::ProblemWhitespace))
+        } else if is_malformed_unc(&expanded) {
+            Some(tr(Text::ProblemUnc))
+        } else if !defined_tokens(&expanded).is_empty() {
+            Some(tr(Text::ProblemExpansion))
+        } else {
+            None
+        }
+    }
+
+    /// Strips quotes and splits on stray `;` separators.
+    fn fix_entry(entry: &str) -> Vec<String> {
+        entry
+            .replace('"', "")
+            .split(';')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    /// `\\server\share\...` (either slash), excluding the `\\?\` and `\\.\` device prefixes.
+    fn is_unc(path: &str) -> bool {
+        let path = normalize_slashes(path);
+        path.starts_with(r"\\") && !path.starts_with(r"\\?\") && !path.starts_with(r"\\.\")
+    }
+
+    /// A UNC path needs both a server and a share; `\\server` alone is not a folder.
+    fn is_malformed_unc(path: &str) -> bool {
+        if !is_unc(path) {
+            return false;
+        }
+        let path = normalize_slashes(path);
+        let mut parts = path[2..].split('\\');
+        let server = parts.next().unwrap_or_default();
+        let share = parts.next().unwrap_or_default();
+        server.trim().is_empty() || share.trim().is_empty()
+    }
+
+    /// Uppercases a leading drive letter (`c:\` -> `C:\`) and leaves the rest untouched.
+    fn normalize_drive_case(path: &str) -> String {
+        let mut chars = path.chars();
+        match (chars.next(), chars.next()) {
+            (Some(drive), Some(':')) if drive.is_ascii_lowercase() => {
+                format!("{}{}", drive.to_ascii_uppercase(), &path[1..])
+            }
+            _ => path.to_string(),
+        }
+    }
+
+    /// Strips trailing backslashes but keeps a bare drive root like `C:\`, since `C:` alone
+    /// means "current directory on drive C", and never eats the leading `\\` of a UNC path.
+    fn trim_trailing_slashes(path: &str) -> String {
+        let trimmed = path.trim_end_matches(['\\', '/']);
+        if trimmed.is_empty() {
+            return path.to_string();
+        }
+        let is_drive = trimmed.len() == 2 && trimmed.ends_with(':');
+        if is_drive && trimmed.len() < path.len() {
+            format!("{trimmed}\\")
+        } else {
+            trimmed.to_string()
+        }
+    }
+
+    /// `(name, expanded value)` pairs for tokenizing, longest value first. Per-user tokens are
+    /// left out of System PATH because they don't expand there.
+    fn token_vars(is_system: bool) -> Vec<(String, String)> {
+        let mut vars = TOKEN_VARS
+            .iter()
+            .filter(|name| !is_system || !USER_ONLY_TOKEN_VARS.contains(*name))
+            .filter_map(|name| {
+                let value = env::var(name).ok()?;
+                let value = trim_trailing_slashes(&value);
+                (value.len() > 3).then(|| (name.to_string(), value))
+            })
+            .collect::<Vec<_>>();
+        vars.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+        vars
+    }
+
+    fn tokenize_path(path: &str, vars: &[(String, String)]) -> String {
+        if path.contains('%') {
+            return path.to_string();
+        }
+
+        for (name, value) in vars {
+            let Some(head) = path.get(..value.len()) else {
+                continue;
+            };
+            let rest = &path[value.len()..];
+            if head.eq_ignore_ascii_case(value) && (rest.is_empty() || rest.starts_with('\\')) {
+                return format!("%{name}%{rest}");
+            }
+        }
+        path.to_string()
+    }
+
+    /// Absolute means a drive root (`C:\`) or UNC share after `%VAR%` expansion. Drive-relative
+    /// (`C:bin`) and root-relative (`\bin`) forms still depend on the process state.
+    fn is_absolute_entry(entry: &str) -> bool {
+        let expanded = normalize_slashes(&expand_env_vars(entry));
+        let bytes = expanded.as_bytes();
+        let is_drive = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'\\';
+        is_drive || expanded.starts_with(r"\\")
+    }
+
+    fn lexically_normalize(path: &Path) -> PathBuf {
+        let mut out = PathBuf::new();
+        for component in path.components() {
+            match component {
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir => {
+                    out.pop();
+                }
+                other => out.push(other.as_os_str()),
+            }
+        }
+        out
+    }
+
+    /// Trailing enabled entries to drop until the joined value fits in `limit` UTF-16 units,
+    /// skipping critical ones. Empty when the value already fits or nothing can be moved.
+    fn overflow_entries(
+        parts: &[String],
+        disabled: &[bool],
+        critical: &[String],
+        limit: usize,
+    ) -> Vec<usize> {
+        let critical = critical
+            .iter()
+            .map(|p| normalize_for_compare(p))
+            .collect::<HashSet<_>>();
+        let mut enabled = parts
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !disabled[*idx])
+            .map(|(idx, part)| (idx, part.clone()))
+            .collect::<Vec<_>>();
+        let length = |entries: &[(usize, String)]| {
+            let parts = entries.iter().map(|(_, part)| part.clone()).collect::<Vec<_>>();
+            join_path(&parts).encode_utf16().count()
+        };
+        let mut moving = Vec::new();
+        let mut pos = enabled.len();
+        while length(&enabled) > limit {
+            let Some(next) = enabled[..pos]
+                .iter()
+                .rposition(|(_, part)| !critical.contains(&normalize_for_compare(part)))
+            else {
+                return Vec::new();
+            };
+            moving.push(enabled.remove(next).0);
+            pos = next;
+        }
+        moving.reverse();
+        moving
+    }
+
+    /// A single absolute path to an existing folder that is not on either PATH yet.
+    fn clipboard_folder(text: &str, known: &HashSet<String>) -> Option<String> {
+        let text = text.trim().trim_matches('"').trim();
+        if text.is_empty() || text.contains(['\n', '\r', ';']) || !is_absolute_entry(text) {
+            return None;
+        }
+        let exists = Path::new(&expand_env_vars(text)).is_dir();
+        (exists && !known.contains(&normalize_for_compare(text))).then(|| text.to_string())
+    }
+
+    /// Indices of entries whose expanded folder does not exist. Entries with undefined
+    /// variables are left alone, as are well-formed UNC paths when `skip_unc` is set.
+    fn invalid_entries(parts: &[String], skip_unc: bool) -> Vec<usize> {
+        parts
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| unresolved_tokens(&expand_env_vars(part)).is_empty())
+            .filter_map(|(idx, part)| {
+                let expanded = normalize_slashes(&expand_env_vars(part));
+                if skip_unc && is_unc(&expanded) && !is_malformed_unc(&expanded) {
+                    return None;
+                }
+                (!Path::new(&expanded).is_dir()).then_some(idx)
+            })
+            .collect()
+    }
+
+    /// Groups of two or more entries that `normalize_for_compare` treats as equal, in order of
+    /// first appearance.
+    fn duplicate_groups(parts: &[String]) -> Vec<DuplicateGroup> {
+        let mut by_key: HashMap<String, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (idx, part) in parts.iter().enumerate() {
+            match by_key.entry(normalize_for_compare(part)) {
+                std::collections::hash_map::Entry::Occupied(slot) => groups[*slot.get()].push(idx),
+                std::collections::hash_map::Entry::Vacant(slot) => {
+                    slot.insert(groups.len());
+                    groups.push(vec![idx]);
+                }
+            }
+        }
+        groups
+            .into_iter()
+            .filter(|indices| indices.len() > 1)
+            .map(|indices| DuplicateGroup { indices, keep: 0 })
+            .collect()
+    }
+
+    /// Folders listed in both scopes once expanded, in User order.
+    fn cross_scope_duplicates(user: &[String], system: &[String]) -> Vec<CrossDuplicate> {
+        let key_of = |part: &String| normalize_for_compare(&expand_env_vars(part));
+        let mut system_by_key: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, part) in system.iter().enumerate() {
+            system_by_key.entry(key_of(part)).or_default().push(idx);
+        }
+        let mut order = Vec::new();
+        let mut user_by_key: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, part) in user.iter().enumerate() {
+            let key = key_of(part);
+            if !system_by_key.contains_key(&key) {
+                continue;
+            }
+            let indices = user_by_key.entry(key.clone()).or_default();
+            if indices.is_empty() {
+                order.push(key);
+            }
+            indices.push(idx);
+        }
+        order
+            .into_iter()
+            .map(|key| CrossDuplicate {
+                user: user_by_key.remove(&key).unwrap_or_default(),
+                system: system_by_key.remove(&key).unwrap_or_default(),
+                keep_system: true,
+            })
+            .collect()
+    }
+
+    /// Folder prefixes for each category, from the current environment.
+    fn category_roots() -> Vec<(EntryCategory, String)> {
+        let mut roots = Vec::new();
+        for (category, vars) in [
+            (EntryCategory::System, &["SystemRoot"][..]),
+            (
+                EntryCategory::ProgramFiles,
+                &["ProgramFiles", "ProgramFiles(x86)", "ProgramW6432", "ProgramData"][..],
+            ),
+            (EntryCategory::UserProfile, &["USERPROFILE"][..]),
+        ] {
+            for var in vars {
+                if let Ok(root) = env::var(var) {
+                    roots.push((category, normalize_for_compare(&root)));
+                }
+            }
+        }
+        roots
+    }
+
+    /// Buckets an expanded path by the first root it lies under; UNC paths are Network.
+    fn classify_path(expanded: &str, roots: &[(EntryCategory, String)]) -> EntryCategory {
+        let path = normalize_for_compare(expanded);
+        if path.starts_with(r"\\") {
+            return EntryCategory::Network;
+        }
+        roots
+            .iter()
+            .find(|(_, root)| match path.strip_prefix(root.as_str()) {
+                Some(rest) => !root.is_empty() && (rest.is_empty() || rest.starts_with('\\')),
+                None => false,
+            })
+            .map_or(EntryCategory::Other, |&(category, _)| category)
+    }
+
+    /// Entries whose folder resolves, through a symlink or junction, to the same place as an
+    /// earlier entry spelled differently, mapped to that earlier entry. Plain duplicates are
+    /// left to `dedupe`, and folders that are missing or not yet scanned are skipped.
+    fn linked_entries(
+        store: &mut PathStore,
+        scanner: &DirScanner,
+        ctx: &egui::Context,
+    ) -> HashMap<usize, usize> {
+        let mut first: HashMap<String, (usize, String)> = HashMap::new();
+        let mut linked = HashMap::new();
+        for idx in 0..store.parts.len() {
+            let info = store.entry_info(idx);
+            if info.relative || !info.unresolved.is_empty() {
+                continue;
+            }
+            let Some(target) = scanner.canonical(&info.expanded, ctx) else {
+                continue;
+            };
+            match first.entry(target) {
+                std::collections::hash_map::Entry::Occupied(slot) => {
+                    let (other, key) = slot.get();
+                    if *key != info.key {
+                        linked.insert(idx, *other);
+                    }
+                }
+                std::collections::hash_map::Entry::Vacant(slot) => {
+                    slot.insert((idx, info.key.clone()));
+                }
+            }
+        }
+        linked
+    }
+
+    /// Enter and Escape pressed in a dialog, consumed so nothing else reacts to them. Call it
+    /// after the dialog's text fields: Enter only counts once no widget holds focus, so a
+    /// single-line field that just gave up focus submits and a multiline field keeps it.
+    fn dialog_keys(ctx: &egui::Context) -> (bool, bool) {
+        use egui::{Key, Modifiers};
+
+        let focused = ctx.memory(|m| m.focused().is_some());
+        ctx.input_mut(|i| {
+            let enter = !focused && i.consume_key(Modifiers::NONE, Key::Enter);
+            (enter, i.consume_key(Modifiers::NONE, Key::Escape))
+        })
+    }
+
+    /// Entry diff as shown by the save confirmation and the Show Changes dialog.
+    fn draw_diff(ui: &mut egui::Ui, diff: &[DiffLine]) {
+        if diff.iter().all(|line| matches!(line, DiffLine::Same(_))) {
+            ui.label(RichText::new(tr(Text::NoEntryChanges)).color(Color32::from_gray(170)));
+        }
+        for line in diff {
+            let text = match line {
+                DiffLine::Same(entry) => {
+                    RichText::new(format!("  {entry}")).color(Color32::from_gray(150))
+                }
+                DiffLine::Added(entry) => RichText::new(format!("+ {entry}")).color(ADDED_COLOR),
+                DiffLine::Removed(entry) => RichText::new(format!("- {entry}")).color(ERROR_COLOR),
+                DiffLine::Moved { entry, from, to } => {
+                    RichText::new(trf(Text::MovedLine, &[entry, &(from + 1), &(to + 1)]))
+                        .color(WARNING_COLOR)
+                }
+            };
+            ui.label(text.monospace());
+        }
+    }
+
+    /// Case-insensitive subsequence match. Higher scores mean the pattern's characters sit
+    /// closer together; `None` means not every character was found in order.
+    fn fuzzy_score(pattern: &str, text: &str) -> Option<i32> {
+        let text = text.to_lowercase();
+        let mut chars = text.chars().enumerate();
+        let mut score = 0;
+        let mut last: Option<usize> = None;
+        for wanted in pattern.to_lowercase().chars() {
+            let (pos, _) = chars.by_ref().find(|&(_, c)| c == wanted)?;
+            score += match last {
+                Some(prev) if pos == prev + 1 => 3,
+                _ => 1,
+            };
+            last = Some(pos);
+        }
+        Some(score)
+    }
+
+    fn sort_case_insensitive(parts: &mut [String]) {
+        parts.sort_by_cached_key(|p| p.to_lowercase());
+    }
+
+    fn unresolved_tokens(value: &str) -> Vec<String> {
+        env_tokens(value)
+            .into_iter()
+            .filter(|name| env::var(name).is_err())
+            .collect()
+    }
+
+    /// Applies a find/replace to every entry, or only to `selection` when given. Returns the
+    /// rewritten list and how many entries changed.
+    fn replace_entries(
+        parts: &[String],
+        selection: Option<&BTreeSet<usize>>,
+        find: &str,
+        replace: &str,
+        ignore_case: bool,
+        use_regex: bool,
+    ) -> Result<(Vec<String>, usize), String> {
+        if find.is_empty() {
+            return Ok((parts.to_vec(), 0));
+        }
+
+        let pattern = if use_regex {
+            find.to_string()
+        } else {
+            regex::escape(find)
+        };
+        let matcher = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|err| trf(Text::InvalidRegex, &[&err]))?;
+
+        let mut changed = 0;
+        let updated = parts
+            .iter()
+            .enumerate()
+            .map(|(idx, part)| {
+                if selection.is_some_and(|sel| !sel.contains(&idx)) {
+                    return part.clone();
+                }
+                let next = if use_regex {
+                    matcher.replace_all(part, replace).into_owned()
+                } else {
+                    matcher.replace_all(part, regex::NoExpand(replace)).into_owned()
+                };
+                if next != *part {
+                    changed += 1;
+                }
+                next
+            })
+            .collect();
+        Ok((updated, changed))
+    }
+
+    /// Moves selected entries one step among the `visible` positions, swapping with the
+    /// neighbouring visible entry so hidden entries keep their places. Contiguous selected
+    /// entries move as a block. Returns the new selection.
+    fn move_entries<T>(
+        parts: &mut [T],
+        selected: &BTreeSet<usize>,
+        visible: &[usize],
+        direction: i32,
+    ) -> BTreeSet<usize> {
+        let mut new_selected = selected.clone();
+        let mut step = |k: usize, neighbour: usize| {
+            let (idx, other) = (visible[k], visible[neighbour]);
+            if new_selected.contains(&idx) && !new_selected.contains(&other) {
+                parts.swap(idx, other);
+                new_selected.remove(&idx);
+                new_selected.insert(other);
+            }
+        };
+
+        if direction < 0 {
+            for k in 1..visible.len() {
+                step(k, k - 1);
+            }
+        } else {
+            for k in (0..visible.len().saturating_sub(1)).rev() {
+                step(k, k + 1);
+            }
+        }
+        new_selected
+    }
+
+    /// Critical entries present in `before` whose location no longer appears in `after`.
+    fn removed_critical_entries(
+        before: &[String],
+        after: &[String],
+        critical: &[String],
+    ) -> Vec<String> {
+        let critical = critical
+            .iter()
+            .map(|p| normalize_for_compare(p))
+            .collect::<HashSet<_>>();
+        let remaining = after
+            .iter()
+            .map(|p| normalize_for_compare(p))
+            .collect::<HashSet<_>>();
+        let mut reported = HashSet::new();
+        before
+            .iter()
+            .filter(|part| {
+                let key = normalize_for_compare(part);
+                critical.contains(&key) && !remaining.contains(&key) && reported.insert(key)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn confirm_critical_removal(before: &[String], after: &[String], critical: &[String]) -> bool {
+        let removed = removed_critical_entries(before, after, critical);
+        if removed.is_empty() {
+            return true;
+        }
+        matches!(
+            MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title(tr(Text::CriticalTitle))
+                .set_description(trf(Text::CriticalBody, &[&removed.join("\n")]))
+                .set_buttons(MessageButtons::OkCancel)
+                .show(),
+            MessageDialogResult::Ok
+        )
+    }
+
+    /// Last-chance check on the System PATH about to be written: without System32 most of
+    /// Windows stops working, however the entry came to be missing.
+    fn confirm_missing_system32(parts: &[String]) -> bool {
+        let system32 = normalize_for_compare(&expand_env_vars(CRITICAL_PATHS[0]));
+        if parts
+            .iter()
+            .any(|part| normalize_for_compare(&expand_env_vars(part)) == system32)
+        {
+            return true;
+        }
+        matches!(
+            MessageDialog::new()
+                .set_level(MessageLevel::Error)
+                .set_title(tr(Text::NoSystem32Title))
+                .set_description(trf(Text::NoSystem32Body, &[&CRITICAL_PATHS[0]]))
+                .set_buttons(MessageButtons::YesNo)
+                .show(),
+            MessageDialogResult::Yes
+        )
+    }
+
+    #[derive(Debug)]
+    struct SaveCancelled;
+
+    impl std::fmt::Display for SaveCancelled {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(tr(Text::SaveCancelled))
+        }
+    }
+
+    impl Error for SaveCancelled {}
+
+    fn cleanup_rewrite(
+        name: Text,
+        parts: &mut Vec<String>,
+        rewrite: impl Fn(&str) -> String,
+    ) -> CleanupStep {
+        let mut changes = Vec::new();
+        for part in parts.iter_mut() {
+            let next = rewrite(part);
+            if next != *part {
+                changes.push(format!("{part} -> {next}"));
+                *part = next;
+            }
+        }
+        parts.retain(|part| !part.is_empty());
+        CleanupStep { name, changes }
+    }
+
+    fn cleanup_entries(parts: &[String], options: CleanupOptions) -> CleanupPreview {
+        let mut result = parts.to_vec();
+        let mut steps = Vec::new();
+
+        if options.trim {
+            steps.push(cleanup_rewrite(Text::StepTrim, &mut result, |part| {
+                trim_trailing_slashes(part.trim())
+            }));
+        }
+        if options.normalize {
+            steps.push(cleanup_rewrite(
+                Text::StepNormalize,
+                &mut result,
+                normalize_slashes,
+            ));
+        }
+
+        if options.dedupe {
+            let mut seen = HashSet::new();
+            let mut changes = Vec::new();
+            result.retain(|part| {
+                let keep = seen.insert(normalize_for_compare(part));
+                if !keep {
+                    changes.push(format!("- {part}"));
+                }
+                keep
+            });
+            steps.push(CleanupStep {
+                name: Text::Dedupe,
+                changes,
+            });
+        }
+        if options.remove_missing {
+            let mut changes = Vec::new();
+            result.retain(|part| {
+                let keep = Path::new(&expand_env_vars(part)).is_dir();
+                if !keep {
+                    changes.push(format!("- {part}"));
+                }
+                keep
+            });
+            steps.push(CleanupStep {
+                name: Text::StepMissing,
+                changes,
+            });
+        }
+
+        CleanupPreview {
+            options,
+            result,
+            steps,
+        }
+    }
+
+    fn sort_descending(parts: &mut [String]) {
+        parts.sort_by_cached_key(|p| std::cmp::Reverse(p.to_lowercase()));
+    }
+
+    fn sort_by_length(parts: &mut [String]) {
+        parts.sort_by_cached_key(|p| (p.chars().count(), p.to_lowercase()));
+    }
+
+    fn sort_natural(parts: &mut [String]) {
+        parts.sort_by(|a, b| natural_cmp(a, b));
+    }
+
+    /// Stable sort by where entries point: Windows, then Program Files, then the user profile,
+    /// then everything else. Order within each group is kept.
+    fn sort_by_priority(parts: &mut [String], roots: &[(EntryCategory, String)]) {
+        parts.sort_by_cached_key(|p| match classify_path(&expand_env_vars(p), roots) {
+            EntryCategory::System => 0,
+            EntryCategory::ProgramFiles => 1,
+            EntryCategory::UserProfile => 2,
+            EntryCategory::Network | EntryCategory::Other => 3,
+        });
+    }
+
+    /// Case-insensitive comparison that orders digit runs by numeric value, so `v2` < `v10`.
+    fn natural_cmp(a: &str, b: &str) -> CmpOrdering {
+        let (a, b) = (a.to_lowercase(), b.to_lowercase());
+        let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+        loop {
+            match (a.peek().copied(), b.peek().copied()) {
+                (None, None) => return CmpOrdering::Equal,
+                (None, Some(_)) => return CmpOrdering::Less,
+                (Some(_), None) => return CmpOrdering::Greater,
+                (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                    let take_digits = |it: &mut std::iter::Peekable<std::str::Chars<'_>>| {
+                        let mut digits = String::new();
+                        while let Some(c) = it.next_if(char::is_ascii_digit) {
+                            digits.push(c);
+                        }
+                        digits
+                    };
+                    let (da, db) = (take_digits(&mut a), take_digits(&mut b));
+                    let (ta, tb) = (da.trim_start_matches('0'), db.trim_start_matches('0'));
+                    let ord = ta.len().cmp(&tb.len()).then_with(|| ta.cmp(tb));
+                    if ord != CmpOrdering::Equal {
+                        return ord;
+                    }
+                }
+                (Some(x), Some(y)) => {
+                    if x != y {
+                        return x.cmp(&y);
+                    }
+                    a.next();
+                    b.next();
+                }
+            }
+        }
+    }
+
+    fn has_env_token(value: &str) -> bool {
+        let chars: Vec<char> = value.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] == '%' {
+                let mut j = i + 1;
+                while j < chars.len() && chars[j] != '%' {
+                    j += 1;
+                }
+                if j < chars.len() && j > i + 1 {
+                    return true;
+                }
+            }
+            i += 1;
+        }
+        false
+    }
+
+    fn draw_entry_tooltip(ui: &mut egui::Ui, info: &EntryInfo, scan: &ScanState, relative: bool) {
+        ui.label(RichText::new(&info.expanded).monospace());
+        for name in &info.unresolved {
+            ui.label(RichText::new(trf(Text::NotExpanded, &[name])).color(ERROR_COLOR));
+        }
+        if relative {
+            ui.label(RichText::new(tr(Text::RelativeEntry)).color(WARNING_COLOR));
+        }
+        match scan {
+            ScanState::Pending(started) if started.elapsed() >= SCAN_TIMEOUT => {
+                ui.label(RichText::new(tr(Text::ScanTimedOut)).color(WARNING_COLOR));
+            }
+            ScanState::Pending(_) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr(Text::ScanningFolderDots));
+                });
+                ui.ctx().request_repaint_after(Duration::from_millis(250));
+            }
+            ScanState::Missing => {
+                ui.label(RichText::new(tr(Text::FolderMissing)).color(ERROR_COLOR));
+            }
+            ScanState::Done(found) => {
+                ui.label(trf(Text::FolderExecutables, &[&found.executables]));
+                for name in found.names.iter().take(SCAN_SAMPLE) {
+                    ui.label(RichText::new(format!("  {name}")).monospace().small());
+                }
+                if found.executables > SCAN_SAMPLE {
+                    ui.label(
+                        RichText::new(trf(Text::AndMore, &[&(found.executables - SCAN_SAMPLE)]))
+                            .small(),
+                    );
+                }
+            }
+        }
+    }
 
-                        ui.add_space(8.0);
+    fn pathext_list() -> Vec<String> {
+        let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+        split_path(&pathext)
+            .into_iter()
+            .map(|ext| ext.to_lowercase())
+            .collect()
+    }
 
-                        ui.horizontal_wrapped(|ui| {
-                            if ui.button("Add").clicked() {
-                                do_add = true;
-                            }
-                            if ui.button("Browse").clicked() {
-                                do_browse = true;
-                            }
-                            if ui.button("Remove").clicked() {
-                                do_remove = true;
-                            }
-                            if ui
-                                .add_enabled(
-                                    store.filter.trim().is_empty(),
-                                    egui::Button::new("Move Up"),
-                                )
-                                .clicked()
-                            {
-                                do_up = true;
-                            }
-                            if ui
-                                .add_enabled(
-                                    store.filter.trim().is_empty(),
-                                    egui::Button::new("Move Down"),
-                                )
-                                .clicked()
-                            {
-                                do_down = true;
-                            }
-                            if ui.button("Dedupe").clicked() {
-                                do_dedupe = true;
-                            }
-                            if ui.button("Sort").clicked() {
-                                do_sort = true;
-                            }
-                            if ui.button("Expanded").clicked() {
-                                do_expand = true;
-                            }
-                        });
+    fn is_executable_name(name: &str, pathext: &[String]) -> bool {
+        let name = name.to_lowercase();
+        pathext.iter().any(|ext| name.ends_with(ext.as_str()))
+    }
 
-                        ui.add_space(8.0);
-                        ui.label(RichText::new("Raw PATH preview").small());
-                        let mut raw_preview = store.raw_preview();
-                        ui.add(
-                            TextEdit::multiline(&mut raw_preview)
-                                .desired_rows(5)
-                                .interactive(false)
-                                .desired_width(f32::INFINITY),
-                        );
+    fn scan_directory(dir: &Path) -> ScanState {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return ScanState::Missing;
+        };
+        let pathext = pathext_list();
+        let mut names = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| is_executable_name(name, &pathext))
+            .collect::<Vec<_>>();
+        sort_case_insensitive(&mut names);
+        let executables = names.len();
+        // Failing to resolve (permissions, a share dropping mid-scan) just means no link check.
+        let canonical = fs::canonicalize(dir).ok().map(|target| {
+            let target = target.display().to_string();
+            let target = match target.strip_prefix(r"\\?\UNC\") {
+                Some(share) => format!(r"\\{share}"),
+                None => target.trim_start_matches(r"\\?\").to_string(),
+            };
+            normalize_for_compare(&target)
+        });
+        ScanState::Done(DirScan {
+            executables,
+            names,
+            canonical,
+        })
+    }
 
-                        ui.add_space(8.0);
-                        if ui.button("Save this PATH").clicked() {
-                            do_save = true;
-                        }
+    fn executable_candidates(name: &str) -> Vec<String> {
+        if Path::new(name).extension().is_some() {
+            return vec![name.to_string()];
+        }
+
+        pathext_list()
+            .into_iter()
+            .map(|ext| format!("{name}{ext}"))
+            .collect()
+    }
+
+    fn resolve_executable<'a>(
+        name: &str,
+        entries: impl Iterator<Item = (bool, &'a str)>,
+    ) -> Vec<ResolveMatch> {
+        let candidates = executable_candidates(name);
+        let mut matches = Vec::new();
+        for (is_system, entry) in entries {
+            let dir = PathBuf::from(expand_env_vars(entry));
+            for candidate in &candidates {
+                let file = dir.join(candidate);
+                if file.is_file() {
+                    matches.push(ResolveMatch {
+                        is_system,
+                        entry: entry.to_string(),
+                        file,
                     });
-                });
+                }
             }
+        }
+        matches
+    }
 
-            if do_add {
-                self.open_add_dialog(is_system);
+    /// Groups every PATHEXT file in the given folders by command name, in search order, and
+    /// keeps the names found in more than one folder. A folder listed twice counts once.
+    fn shadowed_executables(entries: &[(bool, String)], cancel: &AtomicBool) -> Vec<Shadowed> {
+        let pathext = pathext_list();
+        let mut seen_dirs = HashSet::new();
+        let mut by_name: HashMap<String, Shadowed> = HashMap::new();
+        for (is_system, entry) in entries {
+            if cancel.load(Ordering::Relaxed) {
+                break;
             }
-            if do_browse {
-                if let Some(folder) = FileDialog::new().pick_folder() {
-                    self.store_mut(is_system)
-                        .parts
-                        .push(folder.display().to_string());
-                    self.status = format!("Added folder to {}", Self::panel_title(is_system));
+            let dir = PathBuf::from(expand_env_vars(entry));
+            if !seen_dirs.insert(normalize_for_compare(&dir.display().to_string())) {
+                continue;
+            }
+            let Ok(files) = fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut names = files
+                .filter_map(Result::ok)
+                .filter(|file| file.file_type().is_ok_and(|t| t.is_file()))
+                .map(|file| file.file_name().to_string_lossy().into_owned())
+                .filter(|name| is_executable_name(name, &pathext))
+                .collect::<Vec<_>>();
+            // Within one folder the PATHEXT order decides, e.g. git.exe before git.cmd.
+            names.sort_by_key(|name| {
+                let lower = name.to_lowercase();
+                pathext.iter().position(|ext| lower.ends_with(ext.as_str()))
+            });
+            let mut claimed = HashSet::new();
+            for name in names {
+                let stem = Path::new(&name)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| name.clone());
+                let key = stem.to_lowercase();
+                if !claimed.insert(key.clone()) {
+                    continue;
                 }
+                by_name
+                    .entry(key)
+                    .or_insert_with(|| Shadowed {
+                        name: stem,
+                        providers: Vec::new(),
+                    })
+                    .providers
+                    .push(ResolveMatch {
+                        is_system: *is_system,
+                        entry: entry.clone(),
+                        file: dir.join(&name),
+                    });
             }
-            if do_remove {
-                self.remove_selected(is_system);
+        }
+        let mut shadowed = by_name
+            .into_values()
+            .filter(|shadowed| shadowed.providers.len() > 1)
+            .collect::<Vec<_>>();
+        shadowed.sort_by_cached_key(|shadowed| shadowed.name.to_lowercase());
+        shadowed
+    }
+
+    fn log_dir() -> Option<PathBuf> {
+        let base = env::var_os("LOCALAPPDATA")?;
+        Some(PathBuf::from(base).join("PathEditorNative").join("logs"))
+    }
+
+    fn backup_dir() -> Option<PathBuf> {
+        let base = env::var_os("LOCALAPPDATA")?;
+        Some(PathBuf::from(base).join("PathEditorNative").join("backups"))
+    }
+
+    fn parse_backup_keep(text: &str) -> Option<usize> {
+        text.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|keep| (1..=MAX_BACKUP_KEEP).contains(keep))
+    }
+
+    fn parse_backup_hours(text: &str) -> Option<u64> {
+        text.trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|hours| (1..=MAX_BACKUP_HOURS).contains(hours))
+    }
+
+    /// Age of the newest file in the backups folder; `None` when there is none yet.
+    fn newest_backup_age() -> Option<Duration> {
+        fs::read_dir(backup_dir()?)
+            .ok()?
+            .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+            .max()
+            .map(|newest| newest.elapsed().unwrap_or_default())
+    }
+
+    /// File name and entries of the newest `Path` backup for the scope.
+    fn latest_backup(is_system: bool) -> Option<(String, Vec<String>)> {
+        let prefix = if is_system { "system_" } else { "user_" };
+        let newest = fs::read_dir(backup_dir()?)
+            .ok()?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| {
+                name.starts_with(prefix) && name.to_lowercase().ends_with("_path.reg")
+            })
+            .max()?;
+        let path = backup_dir()?.join(&newest);
+        let section = read_import_file(&path, &["Path".to_string()]).ok()?.into_iter().next()?;
+        Some((newest, section.parts))
+    }
+
+    /// Backs up both PATH values when the newest backup is older than `hours`, so there is a
+    /// restore point even for changes made by other programs.
+    fn startup_backup(hours: u64, keep: usize) -> Result<(), String> {
+        if newest_backup_age().is_some_and(|age| age < Duration::from_secs(hours * 3600)) {
+            return Ok(());
+        }
+        for is_system in [false, true] {
+            backup_reg_value(is_system, "Path", keep)
+                .map_err(|err| trf(Text::StartupBackupFailed, &[&err]))?;
+        }
+        log_event("Startup backup of User and System PATH written");
+        Ok(())
+    }
+
+    /// Writes the value currently in the registry to a timestamped `.reg` file and prunes the
+    /// oldest backups for that scope beyond `keep`.
+    fn backup_reg_value(is_system: bool, name: &str, keep: usize) -> Result<(), Box<dyn Error>> {
+        let dir = backup_dir().ok_or("LOCALAPPDATA is not set")?;
+        fs::create_dir_all(&dir)?;
+
+        let (root, subkey) = env_key(is_system);
+        let (value, vtype) = read_reg_value(root, subkey, name)?;
+        let scope = if is_system { "system" } else { "user" };
+        let content = build_reg_file(&[(
+            env_key_path(is_system),
+            name.to_string(),
+            value,
+            vtype,
+        )]);
+        let now = unsafe { GetLocalTime() };
+        let file_name = format!(
+            "{scope}_{:04}{:02}{:02}-{:02}{:02}{:02}_{name}.reg",
+            now.wYear, now.wMonth, now.wDay, now.wHour, now.wMinute, now.wSecond
+        );
+        fs::write(dir.join(file_name), encode_reg_file(&content))?;
+
+        let prefix = format!("{scope}_");
+        let mut backups = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".reg"))
+            })
+            .collect::<Vec<_>>();
+        backups.sort();
+        let excess = backups.len().saturating_sub(keep);
+        for old in &backups[..excess] {
+            let _ = fs::remove_file(old);
+        }
+        Ok(())
+    }
+
+    fn clock_time() -> String {
+        let now = unsafe { GetLocalTime() };
+        format!("{:02}:{:02}:{:02}", now.wHour, now.wMinute, now.wSecond)
+    }
+
+    fn log_timestamp() -> String {
+        let now = unsafe { GetLocalTime() };
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            now.wYear, now.wMonth, now.wDay, now.wHour, now.wMinute, now.wSecond
+        )
+    }
+
+    /// Appends a line to the action log. Failures are ignored so logging can never get in
+    /// the way of an edit or a save.
+    fn log_event(message: &str) {
+        if !LOGGING_ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(dir) = log_dir() else {
+            return;
+        };
+        let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = write_log_line(&dir, &format!("{} {message}\n", log_timestamp()));
+    }
+
+    fn write_log_line(dir: &Path, line: &str) -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let current = dir.join(LOG_FILE);
+        if fs::metadata(&current).is_ok_and(|meta| meta.len() >= LOG_MAX_BYTES) {
+            for idx in (1..LOG_KEEP).rev() {
+                let from = if idx == 1 {
+                    current.clone()
+                } else {
+                    dir.join(format!("path_editor.{}.log", idx - 1))
+                };
+                let _ = fs::rename(from, dir.join(format!("path_editor.{idx}.log")));
             }
-            if do_up {
-                self.move_selected(is_system, -1);
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&current)?
+            .write_all(line.as_bytes())
+    }
+
+    fn elevated_button<'a>(
+        shield: &Option<egui::TextureHandle>,
+        text: impl Into<egui::WidgetText>,
+    ) -> egui::Button<'a> {
+        match shield {
+            Some(texture) => egui::Button::image_and_text(
+                egui::Image::new(egui::load::SizedTexture::from_handle(texture))
+                    .fit_to_exact_size(egui::vec2(16.0, 16.0)),
+                text,
+            ),
+            None => egui::Button::new(text),
+        }
+    }
+
+    /// Loads the small stock UAC shield icon as RGBA pixels.
+    fn load_shield_icon() -> Option<egui::ColorImage> {
+        unsafe {
+            let mut info = SHSTOCKICONINFO {
+                cbSize: std::mem::size_of::<SHSTOCKICONINFO>() as u32,
+                ..Default::default()
+            };
+            SHGetStockIconInfo(SIID_SHIELD, SHGSI_ICON | SHGSI_SMALLICON, &mut info).ok()?;
+
+            let mut icon = ICONINFO::default();
+            let got_info = GetIconInfo(info.hIcon, &mut icon);
+            let _ = DestroyIcon(info.hIcon);
+            got_info.ok()?;
+
+            let mut bitmap = BITMAP::default();
+            let size = std::mem::size_of::<BITMAP>() as i32;
+            let pixels = if GetObjectW(icon.hbmColor, size, Some(&mut bitmap as *mut _ as _)) == 0
+            {
+                None
+            } else {
+                let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+                let mut bmi = BITMAPINFO {
+                    bmiHeader: BITMAPINFOHEADER {
+                        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                        biWidth: width,
+                        biHeight: -height,
+                        biPlanes: 1,
+                        biBitCount: 32,
+                        biCompression: BI_RGB.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                let mut bgra = vec![0u8; (width * height * 4) as usize];
+                let dc = CreateCompatibleDC(None);
+                let rows = GetDIBits(
+                    dc,
+                    icon.hbmColor,
+                    0,
+                    height as u32,
+                    Some(bgra.as_mut_ptr() as _),
+                    &mut bmi,
+                    DIB_RGB_COLORS,
+                );
+                let _ = DeleteDC(dc);
+                (rows == height).then(|| {
+                    for px in bgra.chunks_exact_mut(4) {
+                        px.swap(0, 2);
+                    }
+                    egui::ColorImage::from_rgba_unmultiplied(
+                        [width as usize, height as usize],
+                        &bgra,
+                    )
+                })
+            };
+
+            let _ = DeleteObject(icon.hbmColor);
+            let _ = DeleteObject(icon.hbmMask);
+            pixels
+        }
+    }
+
+    fn is_wow64() -> bool {
+        let mut wow64 = FALSE;
+        unsafe { IsWow64Process(GetCurrentProcess(), &mut wow64).is_ok() && wow64.as_bool() }
+    }
+
+    /// Enables a privilege the token already holds but has disabled, as RegLoadKey requires.
+    fn enable_privilege(name: PCWSTR) -> windows::core::Result<()> {
+        unsafe {
+            let mut token = HANDLE::default();
+            OpenProcessToken(
+                GetCurrentProcess(),
+                TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
+                &mut token,
+            )?;
+            let mut luid = LUID::default();
+            let result = LookupPrivilegeValueW(PCWSTR::null(), name, &mut luid).and_then(|()| {
+                let privileges = TOKEN_PRIVILEGES {
+                    PrivilegeCount: 1,
+                    Privileges: [LUID_AND_ATTRIBUTES {
+                        Luid: luid,
+                        Attributes: SE_PRIVILEGE_ENABLED,
+                    }],
+                };
+                AdjustTokenPrivileges(token, FALSE, Some(&privileges), 0, None, None)
+            });
+            // AdjustTokenPrivileges reports success even when the privilege is not held.
+            let not_held = GetLastError() == ERROR_NOT_ALL_ASSIGNED;
+            let _ = CloseHandle(token);
+            result?;
+            if not_held {
+                return Err(ERROR_NOT_ALL_ASSIGNED.to_hresult().into());
             }
-            if do_down {
-                self.move_selected(is_system, 1);
+            Ok(())
+        }
+    }
+
+    /// Whether Win32 long path support is switched on for the machine.
+    fn long_paths_enabled() -> bool {
+        open_env_subkey(HKEY_LOCAL_MACHINE, FILESYSTEM_KEY, KEY_READ)
+            .and_then(|key| key.get_value::<u32, _>("LongPathsEnabled"))
+            .is_ok_and(|value| value != 0)
+    }
+
+    fn is_admin() -> bool {
+        unsafe { IsUserAnAdmin().as_bool() }
+    }
+
+    fn restart_as_admin() -> Result<(), Box<dyn Error>> {
+        let exe = env::current_exe()?;
+        let exe_str = exe.to_string_lossy().to_string();
+        let args = env::args()
+            .skip(1)
+            .map(|a| quote_arg(&a))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let op = to_wide("runas");
+        let exe_w = to_wide(&exe_str);
+        let args_w = to_wide(&args);
+
+        let result = unsafe {
+            ShellExecuteW(
+                None,
+                PCWSTR(op.as_ptr()),
+                PCWSTR(exe_w.as_ptr()),
+                if args.is_empty() {
+                    PCWSTR::null()
+                } else {
+                    PCWSTR(args_w.as_ptr())
+                },
+                PCWSTR::null(),
+                SW_SHOW,
+            )
+        };
+
+        if result.0 as isize <= 32 {
+            Err(format!("ShellExecuteW failed with code {}", result.0 as isize).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn open_in_explorer(path: &str) -> Result<(), Box<dyn Error>> {
+        let op = to_wide("open");
+        let target = to_wide(path);
+
+        let result = unsafe {
+            ShellExecuteW(
+                None,
+                PCWSTR(op.as_ptr()),
+                PCWSTR(target.as_ptr()),
+                PCWSTR::null(),
+                PCWSTR::null(),
+                SW_SHOW,
+            )
+        };
+
+        if result.0 as isize <= 32 {
+            Err(format!("ShellExecuteW failed with code {}", result.0 as isize).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn quote_arg(arg: &str) -> String {
+        if arg.contains(' ') || arg.contains('"') {
+            format!("\"{}\"", arg.replace('"', "\\\""))
+        } else {
+            arg.to_string()
+        }
+    }
+
+    /// Tells running applications the environment changed. Returns false when the broadcast
+    /// was aborted by a hung window or timed out.
+    fn broadcast_env_change() -> bool {
+        let env = to_wide("Environment");
+        let mut result = 0usize;
+        let sent = unsafe {
+            SendMessageTimeoutW(
+                HWND_BROADCAST,
+                WM_SETTINGCHANGE,
+                WPARAM(0),
+                LPARAM(env.as_ptr() as isize),
+                SMTO_ABORTIFHUNG,
+                BROADCAST_TIMEOUT_MS,
+                Some(&mut result),
+            )
+        };
+        if sent.0 == 0 {
+            let err = std::io::Error::last_os_error();
+            log_event(&format!("Environment change broadcast failed: {err}"));
+            false
+        } else {
+            log_event("Broadcast environment change");
+            true
+        }
+    }
+
+    fn to_wide(s: &str) -> Vec<u16> {
+        OsStr::new(s).encode_wide().chain(Some(0)).collect()
+    }
+
+    fn watch_environment_keys(stop: HANDLE, changed: Arc<AtomicBool>, ctx: egui::Context) {
+        let mut watches = Vec::new();
+        for (root, subkey) in [
+            (HKEY_CURRENT_USER, USER_ENV_KEY),
+            (HKEY_LOCAL_MACHINE, SYSTEM_ENV_KEY),
+        ] {
+            let Ok(key) = open_env_subkey(root, subkey, KEY_NOTIFY) else {
+                continue;
+            };
+            let Ok(event) = (unsafe { CreateEventW(None, FALSE, FALSE, PCWSTR::null()) }) else {
+                continue;
+            };
+            watches.push((key, event));
+        }
+
+        let arm = |idx: usize| {
+            let (key, event) = &watches[idx];
+            unsafe {
+                let _ = RegNotifyChangeKeyValue(
+                    windows::Win32::System::Registry::HKEY(key.raw_handle() as _),
+                    FALSE,
+                    REG_NOTIFY_CHANGE_LAST_SET,
+                    *event,
+                    TRUE,
+                );
             }
-            if do_dedupe {
-                self.apply_dedupe(is_system);
+        };
+
+        let handles = Some(stop)
+            .into_iter()
+            .chain(watches.iter().map(|(_, event)| *event))
+            .collect::<Vec<_>>();
+        for idx in 0..watches.len() {
+            arm(idx);
+        }
+
+        // Index of a fired key event within `watches`, or None when stopping.
+        let wait = |timeout: u32| -> Option<Option<usize>> {
+            let result = unsafe { WaitForMultipleObjects(&handles, FALSE, timeout) };
+            if result == WAIT_TIMEOUT {
+                return Some(None);
             }
-            if do_sort {
-                self.apply_sort(is_system);
+            let idx = result.0.wrapping_sub(WAIT_OBJECT_0.0) as usize;
+            if idx == 0 || idx >= handles.len() {
+                None
+            } else {
+                Some(Some(idx - 1))
             }
-            if do_expand {
-                self.open_expanded_dialog(is_system);
+        };
+
+        'outer: while let Some(Some(fired)) = wait(INFINITE) {
+            arm(fired);
+            // Installers often touch the key several times; wait for a quiet period.
+            loop {
+                match wait(WATCH_DEBOUNCE.as_millis() as u32) {
+                    Some(Some(fired)) => arm(fired),
+                    Some(None) => break,
+                    None => break 'outer,
+                }
             }
-            if do_save {
-                self.save_one(is_system);
+            changed.store(true, Ordering::Release);
+            ctx.request_repaint();
+        }
+
+        for (_, event) in watches {
+            unsafe {
+                let _ = CloseHandle(event);
             }
         }
+    }
+
+    fn env_key(is_system: bool) -> (HKEY, &'static str) {
+        if is_system {
+            (HKEY_LOCAL_MACHINE, SYSTEM_ENV_KEY)
+        } else if HIVE_LOADED.load(Ordering::Relaxed) {
+            (HKEY_USERS, HIVE_ENV_KEY)
+        } else {
+            (HKEY_CURRENT_USER, USER_ENV_KEY)
+        }
+    }
+
+    fn env_key_path(is_system: bool) -> String {
+        let (root, subkey) = env_key(is_system);
+        let root = if root == HKEY_LOCAL_MACHINE {
+            "HKEY_LOCAL_MACHINE"
+        } else if root == HKEY_USERS {
+            "HKEY_USERS"
+        } else {
+            "HKEY_CURRENT_USER"
+        };
+        format!(r"{root}\{subkey}")
+    }
+
+    fn load_store(is_system: bool, var_name: &str) -> PathStore {
+        let backend = path_backend(is_system, var_name);
+        let mut store = read_store(backend.as_ref(), is_system, var_name);
+        if !backend.is_registry() {
+            store.var_names = vec![var_name.to_string()];
+            return store;
+        }
+        store.var_names = list_env_values(is_system);
+        store.last_write = key_last_write(is_system);
+        if !store.var_names.iter().any(|n| n.eq_ignore_ascii_case(var_name)) {
+            store.var_names.push(var_name.to_string());
+            sort_case_insensitive(&mut store.var_names);
+        }
+        store
+    }
 
-        fn draw_add_dialog(&mut self, ctx: &egui::Context) {
-            if !self.add_dialog.open {
-                return;
-            }
+    /// A store holding what `backend` has for the variable; empty if it cannot be read.
+    fn read_store(backend: &dyn PathBackend, is_system: bool, var_name: &str) -> PathStore {
+        let (raw, vtype) = backend
+            .read(is_system, var_name)
+            .unwrap_or_else(|_| (String::new(), REG_SZ));
+        PathStore::new(var_name, raw, vtype)
+    }
 
-            let mut open = self.add_dialog.open;
-            let title = if self.add_dialog.is_system {
-                "Add System PATH Entry"
-            } else {
-                "Add User PATH Entry"
-            };
+    /// Writes the store's enabled entries through `backend` and marks them saved. Backups,
+    /// the broadcast and the process environment are left to the caller.
+    fn save_store(
+        backend: &dyn PathBackend,
+        is_system: bool,
+        store: &mut PathStore,
+    ) -> Result<(), Box<dyn Error>> {
+        let vtype = store.write_type();
+        backend.write(is_system, &store.var_name, &store.raw_preview(), vtype.clone())?;
+        store.reg_type = vtype;
+        store.mark_saved();
+        Ok(())
+    }
 
-            egui::Window::new(title)
-                .open(&mut open)
-                .collapsible(false)
-                .resizable(false)
-                .default_width(640.0)
-                .show(ctx, |ui| {
-                    ui.label("Entry (supports %VAR% tokens, e.g. %SystemRoot%\\System32)");
-                    ui.add_space(6.0);
-                    ui.add(
-                        TextEdit::singleline(&mut self.add_dialog.input)
-                            .desired_width(f32::INFINITY)
-                            .hint_text(r"C:\Tools\bin"),
-                    );
+    /// The `--file` path when it stands in for this scope and variable.
+    fn file_backed(is_system: bool, var_name: &str) -> Option<&'static Path> {
+        let file = PATH_FILE.get()?;
+        (!is_system && var_name.eq_ignore_ascii_case("Path")).then_some(file.as_path())
+    }
 
-                    ui.add_space(8.0);
-                    ui.horizontal(|ui| {
-                        if ui.button("Browse...").clicked() {
-                            if let Some(folder) = FileDialog::new().pick_folder() {
-                                self.add_dialog.input = folder.display().to_string();
-                            }
-                        }
+    /// Where a scope's variable is read from and saved to.
+    trait PathBackend {
+        fn read(&self, is_system: bool, name: &str) -> Result<(String, RegType), Box<dyn Error>>;
 
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("Add").clicked() {
-                                let v = self.add_dialog.input.trim().to_string();
-                                if !v.is_empty() {
-                                    self.store_mut(self.add_dialog.is_system).parts.push(v);
-                                    self.status = format!(
-                                        "Added entry to {}",
-                                        Self::panel_title(self.add_dialog.is_system)
-                                    );
-                                }
-                                self.add_dialog.input.clear();
-                                self.add_dialog.open = false;
-                            }
-                            if ui.button("Cancel").clicked() {
-                                self.add_dialog.open = false;
-                                self.add_dialog.input.clear();
-                            }
-                        });
-                    });
-                });
+        fn write(
+            &self,
+            is_system: bool,
+            name: &str,
+            value: &str,
+            vtype: RegType,
+        ) -> Result<(), Box<dyn Error>>;
 
-            self.add_dialog.open = open;
+        /// False for stand-ins that need no backup, broadcast or environment update on save.
+        fn is_registry(&self) -> bool {
+            true
         }
+    }
 
-        fn draw_expanded_dialog(&mut self, ctx: &egui::Context) {
-            if !self.expanded_dialog.open {
-                return;
-            }
+    /// The Environment keys, including a mounted NTUSER.DAT for the User scope.
+    struct RegistryBackend;
 
-            let mut open = self.expanded_dialog.open;
-            let is_system = self.expanded_dialog.is_system;
-            let title = if is_system {
-                "Expanded System PATH"
+    impl PathBackend for RegistryBackend {
+        fn read(&self, is_system: bool, name: &str) -> Result<(String, RegType), Box<dyn Error>> {
+            let (root, subkey) = env_key(is_system);
+            read_reg_value(root, subkey, name)
+        }
+
+        fn write(
+            &self,
+            is_system: bool,
+            name: &str,
+            value: &str,
+            vtype: RegType,
+        ) -> Result<(), Box<dyn Error>> {
+            let (root, subkey) = env_key(is_system);
+            write_reg_value(root, subkey, name, value, vtype)
+        }
+    }
+
+    /// A plain-text PATH file given with `--file`.
+    struct FileBackend(&'static Path);
+
+    impl PathBackend for FileBackend {
+        fn read(&self, _is_system: bool, _name: &str) -> Result<(String, RegType), Box<dyn Error>> {
+            let text = match fs::read(self.0) {
+                Ok(bytes) => decode_text_file(&bytes),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(err) => return Err(err.into()),
+            };
+            // Either a single PATH string or one entry per line.
+            let raw = if text.trim().contains('\n') {
+                join_path(&parse_text_entries(&text))
             } else {
-                "Expanded User PATH"
+                text.trim().to_string()
             };
-            let content = self
-                .store(is_system)
-                .parts
-                .iter()
-                .map(|p| format!("{p}\n    -> {}", expand_env_vars(p)))
-                .collect::<Vec<_>>()
-                .join("\n\n");
+            Ok((raw, REG_EXPAND_SZ))
+        }
 
-            let mut content_mut = content;
-            egui::Window::new(title)
-                .open(&mut open)
-                .collapsible(false)
-                .resizable(true)
-                .default_size([980.0, 420.0])
-                .show(ctx, |ui| {
-                    ui.add(
-                        TextEdit::multiline(&mut content_mut)
-                            .desired_width(f32::INFINITY)
-                            .desired_rows(24)
-                            .interactive(false),
-                    );
-                });
+        fn write(
+            &self,
+            _is_system: bool,
+            _name: &str,
+            value: &str,
+            _vtype: RegType,
+        ) -> Result<(), Box<dyn Error>> {
+            fs::write(self.0, value)?;
+            Ok(())
+        }
 
-            self.expanded_dialog.open = open;
+        fn is_registry(&self) -> bool {
+            false
         }
+    }
 
-        fn restart_elevated(&mut self) {
-            match restart_as_admin() {
-                Ok(()) => {
-                    process::exit(0);
-                }
-                Err(err) => {
-                    MessageDialog::new()
-                        .set_level(MessageLevel::Error)
-                        .set_title("Failed to restart as Administrator")
-                        .set_description(err.to_string())
-                        .set_buttons(MessageButtons::Ok)
-                        .show();
-                }
-            }
+    fn path_backend(is_system: bool, var_name: &str) -> Box<dyn PathBackend> {
+        match file_backed(is_system, var_name) {
+            Some(file) => Box::new(FileBackend(file)),
+            None => Box::new(RegistryBackend),
         }
     }
 
-    impl eframe::App for PathEditorApp {
-        fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-            egui::TopBottomPanel::top("header").show(ctx, |ui| {
-                ui.horizontal(|ui| {
-                    ui.heading("PATH Editor Native");
-                    ui.separator();
-                    ui.label("Directly edits registry PATH values (User and System).");
-                    if !self.is_admin {
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui
-                                .button(
-                                    RichText::new("Restart as Admin")
-                                        .color(Color32::WHITE)
-                                        .strong(),
-                                )
-                                .clicked()
-                            {
-                                self.restart_elevated();
-                            }
-                        });
-                    }
-                });
-            });
+    fn list_env_values(is_system: bool) -> Vec<String> {
+        let (root, subkey) = env_key(is_system);
+        let Ok(key) = open_env_subkey(root, subkey, KEY_READ) else {
+            return Vec::new();
+        };
+        let mut names = key
+            .enum_values()
+            .filter_map(|value| value.ok().map(|(name, _)| name))
+            .collect::<Vec<_>>();
+        sort_case_insensitive(&mut names);
+        names
+    }
 
-            egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
-                ui.horizontal(|ui| {
-                    ui.label(RichText::new(&self.status).small());
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui
-                            .button(RichText::new("Save ALL").strong().color(Color32::WHITE))
-                            .clicked()
-                        {
-                            self.save_all();
-                        }
-                    });
-                });
-            });
+    /// Opens an environment key in the 64-bit registry view so a 32-bit build never edits the
+    /// redirected WOW6432Node copy.
+    fn open_env_subkey(root: HKEY, subkey: &str, access: u32) -> std::io::Result<RegKey> {
+        RegKey::predef(root).open_subkey_with_flags(subkey, access | KEY_WOW64_64KEY)
+    }
 
-            egui::CentralPanel::default().show(ctx, |ui| {
-                ui.columns(2, |cols| {
-                    self.draw_panel(&mut cols[0], false);
-                    self.draw_panel(&mut cols[1], true);
-                });
-            });
+    /// Local time the Environment key was last written, as "YYYY-MM-DD HH:MM". Any value
+    /// under the key counts, not just the one being edited.
+    fn key_last_write(is_system: bool) -> Option<String> {
+        let (root, subkey) = env_key(is_system);
+        let key = open_env_subkey(root, subkey, KEY_READ).ok()?;
+        let mut written = FILETIME::default();
+        let result = unsafe {
+            RegQueryInfoKeyW(
+                windows::Win32::System::Registry::HKEY(key.raw_handle() as _),
+                PWSTR::null(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(&mut written),
+            )
+        };
+        if result != ERROR_SUCCESS {
+            return None;
+        }
 
-            self.draw_add_dialog(ctx);
-            self.draw_expanded_dialog(ctx);
+        let mut utc = SYSTEMTIME::default();
+        let mut local = SYSTEMTIME::default();
+        unsafe {
+            FileTimeToSystemTime(&written, &mut utc).ok()?;
+            SystemTimeToTzSpecificLocalTime(None, &utc, &mut local).ok()?;
         }
+        Some(format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            local.wYear, local.wMonth, local.wDay, local.wHour, local.wMinute
+        ))
     }
 
-    fn apply_style(ctx: &egui::Context) {
-        let mut style = (*ctx.style()).clone();
-        style.spacing.item_spacing = egui::vec2(8.0, 8.0);
-        style.spacing.button_padding = egui::vec2(12.0, 8.0);
-        style.visuals = egui::Visuals::dark();
-        style.visuals.window_fill = Color32::from_rgb(20, 24, 30);
-        style.visuals.panel_fill = Color32::from_rgb(17, 20, 26);
-        style.visuals.widgets.active.bg_fill = Color32::from_rgb(0, 120, 212);
-        style.visuals.widgets.hovered.bg_fill = Color32::from_rgb(0, 96, 172);
-        style.visuals.widgets.inactive.bg_fill = Color32::from_rgb(37, 44, 54);
-        style.visuals.hyperlink_color = Color32::from_rgb(0, 153, 255);
-        ctx.set_style(style);
+    fn read_clipboard_text() -> Option<String> {
+        unsafe {
+            OpenClipboard(HWND::default()).ok()?;
+            let text = GetClipboardData(CF_UNICODETEXT).ok().and_then(|handle| {
+                let memory = HGLOBAL(handle.0);
+                let ptr = GlobalLock(memory) as *const u16;
+                if ptr.is_null() {
+                    return None;
+                }
+                let mut len = 0;
+                while *ptr.add(len) != 0 {
+                    len += 1;
+                }
+                let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+                let _ = GlobalUnlock(memory);
+                Some(text)
+            });
+            let _ = CloseClipboard();
+            text
+        }
     }
 
-    fn split_path(path: &str) -> Vec<String> {
-        path.split(';')
-            .map(|p| p.trim())
-            .filter(|p| !p.is_empty())
-            .map(ToOwned::to_owned)
-            .collect()
+    fn read_reg_value(
+        root: HKEY,
+        subkey: &str,
+        name: &str,
+    ) -> Result<(String, RegType), Box<dyn Error>> {
+        let key = open_env_subkey(root, subkey, KEY_READ)
+            .inspect_err(|err| log_event(&format!("Failed to open {subkey} for reading: {err}")))?;
+        match key.get_raw_value(name) {
+            Ok(raw) => Ok((decode_utf16_reg(&raw.bytes), raw.vtype)),
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    log_event(&format!("Failed to read {subkey}\\{name}: {err}"));
+                }
+                Ok((String::new(), REG_SZ))
+            }
+        }
     }
 
-    fn join_path(parts: &[String]) -> String {
-        parts.join(";")
+    fn write_reg_value(
+        root: HKEY,
+        subkey: &str,
+        name: &str,
+        value: &str,
+        vtype: RegType,
+    ) -> Result<(), Box<dyn Error>> {
+        let key = open_env_subkey(root, subkey, KEY_READ | KEY_SET_VALUE)
+            .inspect_err(|err| log_event(&format!("Failed to open {subkey} for writing: {err}")))?;
+        if let Ok(current) = key.get_raw_value(name) {
+            if reg_value_unchanged(&current.bytes, &current.vtype, value, &vtype) {
+                return Ok(());
+            }
+        }
+        let raw = RegValue {
+            bytes: encode_utf16_reg(value),
+            vtype,
+        };
+        key.set_raw_value(name, &raw)
+            .inspect_err(|err| log_event(&format!("Failed to write {subkey}\\{name}: {err}")))?;
+        Ok(())
     }
 
-    fn expand_env_vars(input: &str) -> String {
-        let chars: Vec<char> = input.chars().collect();
-        let mut out = String::with_capacity(input.len());
-        let mut i = 0;
+    /// Whether writing `value` as `vtype` would leave the stored value as it is. Such a write
+    /// is skipped so the exact bytes (extra NULs, odd UTF-16) survive a read/modify/write cycle
+    /// that did not touch them. Tokens are never expanded either way.
+    fn reg_value_unchanged(bytes: &[u8], current: &RegType, value: &str, vtype: &RegType) -> bool {
+        current == vtype && decode_utf16_reg(bytes) == value
+    }
 
-        while i < chars.len() {
-            if chars[i] == '%' {
-                let mut j = i + 1;
-                while j < chars.len() && chars[j] != '%' {
-                    j += 1;
-                }
-                if j < chars.len() && j > i + 1 {
-                    let name: String = chars[i + 1..j].iter().collect();
-                    match env::var(&name) {
-                        Ok(value) => out.push_str(&value),
-                        Err(_) => {
-                            out.push('%');
-                            out.push_str(&name);
-                            out.push('%');
-                        }
-                    }
-                    i = j + 1;
-                    continue;
-                }
-            }
+    fn build_reg_file(sections: &[(String, String, String, RegType)]) -> String {
+        let mut out = String::new();
+        out.push_str(REG_FILE_HEADER);
+        out.push_str("\r\n");
+        for (key_path, name, value, vtype) in sections {
+            out.push_str(&format!("\r\n[{key_path}]\r\n"));
+            out.push_str(&format_reg_value(name, value, vtype));
+            out.push_str("\r\n");
+        }
+        out
+    }
 
-            out.push(chars[i]);
-            i += 1;
+    fn format_reg_value(name: &str, value: &str, vtype: &RegType) -> String {
+        let prefix = format!("\"{}\"=", escape_reg_string(name));
+        if *vtype != REG_EXPAND_SZ {
+            return format!("{prefix}\"{}\"", escape_reg_string(value));
         }
 
+        // regedit writes REG_EXPAND_SZ as UTF-16LE hex bytes wrapped to ~80 columns.
+        let mut out = format!("{prefix}hex(2):");
+        let mut line_len = out.len();
+        let bytes = encode_utf16_reg(value);
+        for (i, byte) in bytes.iter().enumerate() {
+            let last = i + 1 == bytes.len();
+            let token = if last {
+                format!("{byte:02x}")
+            } else {
+                format!("{byte:02x},")
+            };
+            if line_len + token.len() > 77 {
+                out.push_str("\\\r\n  ");
+                line_len = 2;
+            }
+            out.push_str(&token);
+            line_len += token.len();
+        }
         out
     }
 
-    fn normalize_for_compare(path: &str) -> String {
-        let mut normalized = expand_env_vars(path).replace('/', "\\").trim().to_lowercase();
-        while normalized.ends_with('\\') {
-            normalized.pop();
+    fn escape_reg_string(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    fn decode_text_file(bytes: &[u8]) -> String {
+        if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+            decode_utf16_reg(rest)
+        } else {
+            let rest = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+            String::from_utf8_lossy(rest).into_owned()
+        }
+    }
+
+    fn read_import_file(source: &Path, names: &[String]) -> Result<Vec<ImportedSection>, String> {
+        let bytes = fs::read(source).map_err(|err| err.to_string())?;
+        let text = decode_text_file(&bytes);
+        let is_reg = source
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("reg"));
+        if is_reg {
+            parse_reg_file(&text, names)
+        } else {
+            Ok(vec![ImportedSection {
+                key: tr(Text::TextFileSource).to_string(),
+                name: tr(Text::OneEntryPerLine).to_string(),
+                parts: parse_text_entries(&text),
+            }])
         }
-        normalized
     }
 
-    fn dedupe(parts: &[String]) -> Vec<String> {
+    fn compare_entries(left: &[String], right: &[String]) -> Comparison {
+        let left_keys = left.iter().map(|p| normalize_for_compare(p)).collect::<HashSet<_>>();
+        let right_keys = right.iter().map(|p| normalize_for_compare(p)).collect::<HashSet<_>>();
+
+        let only = |parts: &[String], other: &HashSet<String>| {
+            let mut seen = HashSet::new();
+            parts
+                .iter()
+                .filter(|part| {
+                    let key = normalize_for_compare(part);
+                    !other.contains(&key) && seen.insert(key)
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let common_keys = |parts: &[String], other: &HashSet<String>| {
+            let mut seen = HashSet::new();
+            parts
+                .iter()
+                .map(|part| normalize_for_compare(part))
+                .filter(|key| other.contains(key) && seen.insert(key.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let right_order = common_keys(right, &left_keys);
         let mut seen = HashSet::new();
-        let mut out = Vec::with_capacity(parts.len());
-        for part in parts {
-            let key = normalize_for_compare(part);
-            if seen.insert(key) {
-                out.push(part.clone());
-            }
+        let common = left
+            .iter()
+            .filter(|part| {
+                let key = normalize_for_compare(part);
+                right_keys.contains(&key) && seen.insert(key)
+            })
+            .enumerate()
+            .map(|(idx, part)| {
+                let key = normalize_for_compare(part);
+                let right_idx = right_order.iter().position(|k| *k == key).unwrap_or(idx);
+                (part.clone(), idx, right_idx)
+            })
+            .collect();
+
+        Comparison {
+            only_left: only(left, &right_keys),
+            only_right: only(right, &left_keys),
+            common,
         }
-        out
     }
 
-    fn sort_case_insensitive(parts: &mut [String]) {
-        parts.sort_by_cached_key(|p| p.to_lowercase());
+    fn parse_text_entries(text: &str) -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()
     }
 
-    fn has_env_token(value: &str) -> bool {
-        let chars: Vec<char> = value.chars().collect();
-        let mut i = 0;
-        while i < chars.len() {
-            if chars[i] == '%' {
-                let mut j = i + 1;
-                while j < chars.len() && chars[j] != '%' {
-                    j += 1;
-                }
-                if j < chars.len() && j > i + 1 {
-                    return true;
+    fn parse_reg_file(text: &str, names: &[String]) -> Result<Vec<ImportedSection>, String> {
+        let mut lines = Vec::new();
+        let mut pending = String::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(head) = line.strip_suffix('\\') {
+                pending.push_str(head);
+                continue;
+            }
+            pending.push_str(line);
+            lines.push(std::mem::take(&mut pending));
+        }
+        if !pending.is_empty() {
+            lines.push(pending);
+        }
+
+        let header = lines.first().map(String::as_str).unwrap_or_default();
+        if header != REG_FILE_HEADER && header != "REGEDIT4" {
+            return Err(tr(Text::NotRegExport).to_string());
+        }
+
+        let mut sections = Vec::new();
+        let mut current_key: Option<String> = None;
+        for (line_no, line) in lines.iter().enumerate().skip(1) {
+            if let Some(key) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                current_key = Some(key.to_string());
+                continue;
+            }
+            let Some(key) = current_key.as_ref() else {
+                continue;
+            };
+            if !key.to_lowercase().ends_with("\\environment") {
+                continue;
+            }
+            let Some((name, data)) = line.split_once('=') else {
+                continue;
+            };
+            let name = name.trim().trim_matches('"');
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+
+            let value = parse_reg_data(data.trim())
+                .map_err(|err| trf(Text::LineError, &[&(line_no + 1), &err]))?;
+            sections.push(ImportedSection {
+                key: key.clone(),
+                name: name.to_string(),
+                parts: split_path(&value),
+            });
+        }
+
+        if sections.is_empty() {
+            return Err(trf(Text::NoValueFound, &[&names.join(tr(Text::Or))]));
+        }
+        Ok(sections)
+    }
+
+    fn parse_reg_data(data: &str) -> Result<String, String> {
+        if let Some(quoted) = data.strip_prefix('"') {
+            let mut out = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => match chars.next() {
+                        Some(escaped) => out.push(escaped),
+                        None => return Err(tr(Text::UnterminatedEscape).to_string()),
+                    },
+                    '"' => return Ok(out),
+                    _ => out.push(c),
                 }
             }
-            i += 1;
+            return Err(tr(Text::UnterminatedString).to_string());
         }
-        false
+
+        if let Some(hex) = data.strip_prefix("hex(2):") {
+            let mut bytes = Vec::new();
+            for token in hex.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                let byte = u8::from_str_radix(token, 16)
+                    .map_err(|_| trf(Text::InvalidHexByte, &[&token]))?;
+                bytes.push(byte);
+            }
+            if bytes.len() % 2 != 0 {
+                return Err(tr(Text::OddHexLength).to_string());
+            }
+            return Ok(decode_utf16_reg(&bytes));
+        }
+
+        Err(trf(Text::UnsupportedType, &[&data]))
     }
 
-    fn is_admin() -> bool {
-        unsafe { IsUserAnAdmin().as_bool() }
+    fn encode_reg_file(content: &str) -> Vec<u8> {
+        // Version 5.00 .reg files are UTF-16LE with a BOM.
+        let mut out = vec![0xFF, 0xFE];
+        out.extend(content.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        out
     }
 
-    fn restart_as_admin() -> Result<(), Box<dyn Error>> {
-        let exe = env::current_exe()?;
-        let exe_str = exe.to_string_lossy().to_string();
-        let args = env::args()
-            .skip(1)
-            .map(|a| quote_arg(&a))
-            .collect::<Vec<_>>()
-            .join(" ");
+    fn script_key_path(is_system: bool) -> String {
+        if is_system {
+            format!(r"HKLM\{SYSTEM_ENV_KEY}")
+        } else {
+            format!(r"HKCU\{USER_ENV_KEY}")
+        }
+    }
 
-        let op = to_wide("runas");
-        let exe_w = to_wide(&exe_str);
-        let args_w = to_wide(&args);
+    /// Quotes `value` for a `reg add` argument inside a .bat file. `%` is doubled so tokens stay
+    /// literal, and trailing backslashes are doubled so they don't escape the closing quote.
+    /// Carets need no escaping inside the quotes.
+    fn batch_quote(value: &str) -> String {
+        let body = value.trim_end_matches('\\');
+        let trailing = value.len() - body.len();
+        format!("\"{}{}\"", body.replace('%', "%%"), "\\".repeat(trailing * 2))
+    }
 
-        let result = unsafe {
-            ShellExecuteW(
-                None,
-                PCWSTR(op.as_ptr()),
-                PCWSTR(exe_w.as_ptr()),
-                if args.is_empty() {
-                    PCWSTR::null()
-                } else {
-                    PCWSTR(args_w.as_ptr())
-                },
-                PCWSTR::null(),
-                SW_SHOW,
-            )
-        };
+    /// A single-quoted PowerShell string; backticks and `$` are literal there, only `'` doubles.
+    fn powershell_quote(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
 
-        if result.0 as isize <= 32 {
-            Err(format!("ShellExecuteW failed with code {}", result.0 as isize).into())
-        } else {
-            Ok(())
+    /// A .bat script that writes `parts` to the scope's variable with `reg add`. Fails when an
+    /// entry holds `"`, which can't be passed to `reg add` this way.
+    fn to_batch(
+        parts: &[String],
+        name: &str,
+        is_system: bool,
+        vtype: &RegType,
+    ) -> Result<String, String> {
+        if parts.iter().any(|part| part.contains('"')) {
+            return Err(tr(Text::BatchQuotes).to_string());
         }
+        let scope = if is_system { "System" } else { "User" };
+        let kind = if *vtype == REG_EXPAND_SZ { "REG_EXPAND_SZ" } else { "REG_SZ" };
+        Ok([
+            "@echo off".to_string(),
+            "chcp 65001 >nul".to_string(),
+            format!(
+                "rem Sets the {scope} {} variable exported by Path Editor.",
+                name.replace('%', "%%")
+            ),
+            format!(
+                "reg add {} /v {} /t {kind} /d {} /f",
+                batch_quote(&script_key_path(is_system)),
+                batch_quote(name),
+                batch_quote(&join_path(parts))
+            ),
+            String::new(),
+        ]
+        .join("\r\n"))
     }
 
-    fn quote_arg(arg: &str) -> String {
-        if arg.contains(' ') || arg.contains('"') {
-            format!("\"{}\"", arg.replace('"', "\\\""))
-        } else {
-            arg.to_string()
-        }
+    /// A .ps1 script that writes `parts` to the scope's variable, one entry per line.
+    fn to_powershell(parts: &[String], name: &str, is_system: bool, vtype: &RegType) -> String {
+        let scope = if is_system { "System" } else { "User" };
+        let kind = if *vtype == REG_EXPAND_SZ { "ExpandString" } else { "String" };
+        let mut lines = vec![
+            format!("# Sets the {scope} {name} variable exported by Path Editor."),
+            "$value = @(".to_string(),
+        ];
+        lines.extend(parts.iter().map(|part| format!("    {}", powershell_quote(part))));
+        lines.push(") -join ';'".to_string());
+        // HKCU\Environment -> HKCU:\Environment for the registry provider.
+        let key = script_key_path(is_system);
+        let (hive, rest) = key.split_at(4);
+        lines.push(format!(
+            "Set-ItemProperty -LiteralPath {} -Name {} -Value $value -Type {kind}",
+            powershell_quote(&format!("{hive}:{rest}")),
+            powershell_quote(name)
+        ));
+        lines.push(String::new());
+        lines.join("\r\n")
     }
 
-    fn broadcast_env_change() {
-        let env = to_wide("Environment");
-        let mut result = 0usize;
-        unsafe {
-            let _ = SendMessageTimeoutW(
-                HWND_BROADCAST,
-                WM_SETTINGCHANGE,
-                WPARAM(0),
-                LPARAM(env.as_ptr() as isize),
-                SMTO_ABORTIFHUNG,
-                2000,
-                Some(&mut result),
-            );
+    /// A one-line PowerShell command that appends `entry` to the variable. The session form
+    /// only changes the current console, so `%VAR%` tokens are expanded for it; the persistent
+    /// form keeps them and targets the scope through `[Environment]`.
+    fn powershell_snippet(entry: &str, name: &str, is_system: bool, persistent: bool) -> String {
+        if !persistent {
+            let value = powershell_quote(&format!(";{}", expand_env_vars(entry)));
+            let simple = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            return if simple {
+                format!("$env:{name} += {value}")
+            } else {
+                format!("${{env:{name}}} += {value}")
+            };
         }
+        let target = powershell_quote(if is_system { "Machine" } else { "User" });
+        let name = powershell_quote(name);
+        format!(
+            "[Environment]::SetEnvironmentVariable({name}, \
+             [Environment]::GetEnvironmentVariable({name}, {target}) + {}, {target})",
+            powershell_quote(&format!(";{entry}"))
+        )
     }
 
-    fn to_wide(s: &str) -> Vec<u16> {
-        OsStr::new(s).encode_wide().chain(Some(0)).collect()
+    fn delete_reg_value(root: HKEY, subkey: &str, name: &str) -> Result<(), Box<dyn Error>> {
+        let key = open_env_subkey(root, subkey, KEY_SET_VALUE)
+            .inspect_err(|err| log_event(&format!("Failed to open {subkey} for writing: {err}")))?;
+        key.delete_value(name)
+            .inspect_err(|err| log_event(&format!("Failed to delete {subkey}\\{name}: {err}")))?;
+        Ok(())
     }
 
-    fn read_reg_value(
-        root: HKEY,
-        subkey: &str,
+    fn read_env_values(is_system: bool) -> Vec<EnvVarRow> {
+        let (root, subkey) = env_key(is_system);
+        let Ok(key) = open_env_subkey(root, subkey, KEY_READ) else {
+            return Vec::new();
+        };
+        let mut rows = key
+            .enum_values()
+            .filter_map(Result::ok)
+            .map(|(name, raw)| {
+                let value = if raw.vtype == REG_SZ || raw.vtype == REG_EXPAND_SZ {
+                    decode_utf16_reg(&raw.bytes)
+                } else if raw.vtype == REG_DWORD && raw.bytes.len() >= 4 {
+                    u32::from_le_bytes([raw.bytes[0], raw.bytes[1], raw.bytes[2], raw.bytes[3]])
+                        .to_string()
+                } else {
+                    format!("<{} bytes>", raw.bytes.len())
+                };
+                EnvVarRow {
+                    is_system,
+                    name,
+                    vtype: raw.vtype,
+                    value,
+                }
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by_cached_key(|row| row.name.to_lowercase());
+        rows
+    }
+
+    fn describe_write(
+        is_system: bool,
         name: &str,
-    ) -> Result<(String, RegType), Box<dyn Error>> {
-        let key = RegKey::predef(root).open_subkey_with_flags(subkey, KEY_READ)?;
-        match key.get_raw_value(name) {
-            Ok(raw) => Ok((decode_utf16_reg(&raw.bytes), raw.vtype)),
-            Err(_) => Ok((String::new(), REG_SZ)),
+        value: Option<&str>,
+        vtype: &RegType,
+    ) -> String {
+        let key = env_key_path(is_system);
+        match value {
+            Some(value) => format!("SET {key}\\{name} [{}] = {value}", reg_type_name(vtype)),
+            None => format!("DELETE {key}\\{name}"),
         }
     }
 
-    fn write_reg_value(
-        root: HKEY,
-        subkey: &str,
-        name: &str,
-        value: &str,
-        vtype: RegType,
-    ) -> Result<(), Box<dyn Error>> {
-        let key = RegKey::predef(root).open_subkey_with_flags(subkey, KEY_SET_VALUE)?;
-        let raw = RegValue {
-            bytes: encode_utf16_reg(value),
-            vtype,
-        };
-        key.set_raw_value(name, &raw)?;
-        Ok(())
+    fn reg_type_from_name(name: &str) -> Option<RegType> {
+        match name {
+            "REG_SZ" => Some(REG_SZ),
+            "REG_EXPAND_SZ" => Some(REG_EXPAND_SZ),
+            _ => None,
+        }
+    }
+
+    fn reg_type_name(vtype: &RegType) -> String {
+        match *vtype {
+            REG_SZ => "REG_SZ".to_string(),
+            REG_EXPAND_SZ => "REG_EXPAND_SZ".to_string(),
+            REG_DWORD => "REG_DWORD".to_string(),
+            ref other => format!("{other:?}"),
+        }
     }
 
     fn decode_utf16_reg(bytes: &[u8]) -> String {
@@ -906,7 +12176,40 @@ mod app {
             utf16.pop();
         }
 
-        String::from_utf16_lossy(&utf16)
+        String::from_utf16(&utf16).unwrap_or_else(|_| {
+            log_event("Registry value holds invalid UTF-16; unpaired surrogates were replaced");
+            String::from_utf16_lossy(&utf16)
+        })
+    }
+
+    /// 1204 -> "1,204".
+    fn group_thousands(n: usize) -> String {
+        let separator = thousands_separator();
+        let digits = n.to_string();
+        let mut out = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                out.push_str(separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+
+    /// The user's digit grouping symbol from the regional settings, read once.
+    fn thousands_separator() -> &'static str {
+        static SEPARATOR: OnceLock<String> = OnceLock::new();
+        SEPARATOR.get_or_init(|| {
+            let mut buffer = [0u16; 8];
+            let len = unsafe {
+                GetLocaleInfoEx(LOCALE_NAME_USER_DEFAULT, LOCALE_STHOUSAND, Some(&mut buffer))
+            };
+            // The returned length counts the terminating NUL.
+            match len {
+                2.. => String::from_utf16_lossy(&buffer[..len as usize - 1]),
+                _ => ",".to_string(),
+            }
+        })
     }
 
     fn encode_utf16_reg(value: &str) -> Vec<u8> {
@@ -922,16 +12225,636 @@ mod app {
         matches!(
             MessageDialog::new()
                 .set_level(MessageLevel::Info)
-                .set_title("Confirm")
-                .set_description("Apply PATH changes?")
+                .set_title(tr(Text::ConfirmTitle))
+                .set_description(tr(Text::ConfirmBody))
                 .set_buttons(MessageButtons::OkCancel)
                 .show(),
             MessageDialogResult::Ok
         )
     }
+
+    // These need the Windows build; the registry-free helpers are tested in `entries`.
+    #[cfg(test)]
+    mod tests {
+        use std::cell::RefCell;
+
+        use super::*;
+        use crate::entries::{expand_env_vars_once, EXPAND_PASSES};
+
+        fn parts(items: &[&str]) -> Vec<String> {
+            items.iter().map(|item| item.to_string()).collect()
+        }
+
+        #[test]
+        fn ascending_and_descending_sorts_ignore_case() {
+            let mut list = parts(&["b", "C", "a"]);
+            sort_case_insensitive(&mut list);
+            assert_eq!(list, ["a", "b", "C"]);
+            sort_descending(&mut list);
+            assert_eq!(list, ["C", "b", "a"]);
+        }
+
+        #[test]
+        fn length_sort_breaks_ties_alphabetically() {
+            let mut list = parts(&["ccc", "bb", "a", "ab"]);
+            sort_by_length(&mut list);
+            assert_eq!(list, ["a", "ab", "bb", "ccc"]);
+        }
+
+        #[test]
+        fn natural_sort_orders_numbers_by_value() {
+            let mut list = parts(&["tool-v10", "tool-v2", "Tool-v1", "tool-v002a"]);
+            sort_natural(&mut list);
+            assert_eq!(list, ["Tool-v1", "tool-v2", "tool-v002a", "tool-v10"]);
+            assert_eq!(natural_cmp("v2", "v10"), CmpOrdering::Less);
+            assert_eq!(natural_cmp("V07", "v7"), CmpOrdering::Equal);
+        }
+
+        #[test]
+        fn replace_swaps_drive_letters_literally() {
+            let list = parts(&[r"D:\tools", r"d:\bin", r"C:\x"]);
+            let (updated, changed) =
+                replace_entries(&list, None, r"D:\", r"E:\", true, false).unwrap();
+            assert_eq!(updated, [r"E:\tools", r"E:\bin", r"C:\x"]);
+            assert_eq!(changed, 2);
+
+            let (updated, changed) =
+                replace_entries(&list, None, r"D:\", r"E:\", false, false).unwrap();
+            assert_eq!(updated, [r"E:\tools", r"d:\bin", r"C:\x"]);
+            assert_eq!(changed, 1);
+        }
+
+        #[test]
+        fn replace_only_touches_the_selection() {
+            let list = parts(&[r"D:\tools", r"D:\bin"]);
+            let selection = BTreeSet::from([1]);
+            let (updated, changed) =
+                replace_entries(&list, Some(&selection), "D:", "E:", false, false).unwrap();
+            assert_eq!(updated, [r"D:\tools", r"E:\bin"]);
+            assert_eq!(changed, 1);
+        }
+
+        #[test]
+        fn replace_expands_groups_only_in_regex_mode() {
+            let list = parts(&[r"D:\tools"]);
+            let (updated, _) = replace_entries(&list, None, r"^(\w):", "$1-", false, true).unwrap();
+            assert_eq!(updated, [r"D-\tools"]);
+            let (updated, _) = replace_entries(&list, None, "D:", "$1", false, false).unwrap();
+            assert_eq!(updated, [r"$1\tools"]);
+            assert!(replace_entries(&list, None, "(", "", false, true).is_err());
+            assert_eq!(replace_entries(&list, None, "", "x", false, false).unwrap().1, 0);
+        }
+
+        #[test]
+        fn filtered_move_up_skips_hidden_entries() {
+            // Only a, b and c match the filter; h1 and h2 are hidden.
+            let mut list = parts(&["a", "h1", "b", "h2", "c"]);
+            let moved = move_entries(&mut list, &BTreeSet::from([2]), &[0, 2, 4], -1);
+            assert_eq!(list, ["b", "h1", "a", "h2", "c"]);
+            assert_eq!(moved, BTreeSet::from([0]));
+        }
+
+        #[test]
+        fn filtered_move_down_keeps_a_selected_block_together() {
+            let mut list = parts(&["a", "h1", "b", "h2", "c"]);
+            let moved = move_entries(&mut list, &BTreeSet::from([0, 2]), &[0, 2, 4], 1);
+            assert_eq!(list, ["c", "h1", "a", "h2", "b"]);
+            assert_eq!(moved, BTreeSet::from([2, 4]));
+        }
+
+        #[test]
+        fn filtered_move_stops_at_the_first_visible_entry() {
+            let mut list = parts(&["h1", "a", "h2", "b"]);
+            let moved = move_entries(&mut list, &BTreeSet::from([1]), &[1, 3], -1);
+            assert_eq!(list, ["h1", "a", "h2", "b"]);
+            assert_eq!(moved, BTreeSet::from([1]));
+        }
+
+        #[test]
+        fn quoted_and_semicolon_entries_are_flagged_and_fixed() {
+            let pasted = r#""C:\Program Files\tool";extra"#;
+            assert_eq!(entry_problem(pasted), Some("contains ';'"));
+            assert_eq!(fix_entry(pasted), [r"C:\Program Files\tool", "extra"]);
+
+            let quoted = r#""C:\Program Files\tool""#;
+            assert_eq!(entry_problem(quoted), Some("contains quotes"));
+            assert_eq!(fix_entry(quoted), [r"C:\Program Files\tool"]);
+
+            assert_eq!(entry_problem(r" C:\Tools"), Some("leading/trailing whitespace"));
+            assert_eq!(entry_problem(r"C:\Program Files\tool"), None);
+        }
+
+        #[test]
+        fn row_analysis_covers_only_the_rows_drawn() {
+            let raw = (0..500).map(|i| format!(r"C:\dir{i}")).collect::<Vec<_>>().join(";");
+            let mut store = PathStore::new("Path", raw, REG_EXPAND_SZ);
+            // One visible window of rows, drawn over several frames.
+            for _ in 0..3 {
+                for idx in 100..120 {
+                    store.entry_info(idx);
+                }
+            }
+            assert_eq!(store.info_cache.len(), 20);
+        }
+
+        #[test]
+        fn derived_cache_is_rebuilt_only_when_entries_change() {
+            let mut store = PathStore::new("Path", r"C:\a;C:\b".to_string(), REG_EXPAND_SZ);
+            let first = Arc::clone(&store.derived().keys);
+            assert!(Arc::ptr_eq(&first, &store.derived().keys));
+
+            // Filtering and selecting leave the entries alone.
+            store.filter = "a".to_string();
+            store.selected.insert(0);
+            assert!(Arc::ptr_eq(&first, &store.derived().keys));
+
+            store.checkpoint();
+            store.parts.push(r"C:\c".to_string());
+            let second = Arc::clone(&store.derived().keys);
+            assert!(!Arc::ptr_eq(&first, &second));
+            assert_eq!(second.len(), 3);
+
+            let bytes = store.derived().bytes;
+            store.toggle_enabled(2);
+            assert!(store.derived().bytes < bytes);
+
+            assert!(store.undo());
+            assert_eq!(store.derived().bytes, bytes);
+            assert!(store.undo());
+            assert_eq!(store.derived().keys.len(), 2);
+        }
+
+        fn selected_entries(store: &PathStore) -> Vec<&str> {
+            store.selected.iter().map(|&idx| store.parts[idx].as_str()).collect()
+        }
+
+        #[test]
+        fn selection_follows_entries_through_a_sort() {
+            let mut store = PathStore::new("Path", r"C:\c;C:\a;C:\b".to_string(), REG_EXPAND_SZ);
+            store.selected = BTreeSet::from([0, 2]);
+            store.cursor = Some(2);
+            let keep = store.selection_keys();
+            sort_case_insensitive(&mut store.parts);
+            store.reselect(&keep);
+            assert_eq!(selected_entries(&store), [r"C:\b", r"C:\c"]);
+            assert_eq!(store.cursor, Some(1));
+        }
+
+        #[test]
+        fn selection_follows_the_kept_entry_through_dedupe() {
+            let mut store =
+                PathStore::new("Path", r"C:\a;C:\b;c:\A\;C:\d".to_string(), REG_EXPAND_SZ);
+            // The dropped duplicate was selected; the entry that survives stands in for it.
+            store.selected = BTreeSet::from([2, 3]);
+            let keep = store.selection_keys();
+            store.parts = dedupe(&store.parts);
+            store.reselect(&keep);
+            assert_eq!(store.parts, [r"C:\a", r"C:\b", r"C:\d"]);
+            assert_eq!(selected_entries(&store), [r"C:\a", r"C:\d"]);
+            assert_eq!(store.cursor, None);
+        }
+
+        fn disabled_store(value: &str, disabled: &[usize]) -> PathStore {
+            let mut store = PathStore::new("Path", value.to_string(), REG_EXPAND_SZ);
+            for &idx in disabled {
+                store.toggle_enabled(idx);
+            }
+            store
+        }
+
+        #[test]
+        fn a_rewritten_entry_stays_disabled() {
+            let mut store = disabled_store(r"C:/a;C:/b", &[1]);
+            store.edit_entries(|entries| {
+                for (part, _) in entries.iter_mut() {
+                    *part = normalize_slashes(part);
+                }
+            });
+            assert_eq!(store.parts, [r"C:\a", r"C:\b"]);
+            assert_eq!(store.disabled_flags(), [false, true]);
+            assert_eq!(store.enabled_parts(), [r"C:\a"]);
+        }
+
+        #[test]
+        fn removing_an_entry_leaves_its_disabled_twin_disabled() {
+            let mut store = disabled_store(r"C:\a;C:\b;C:\a", &[2]);
+            store.edit_entries(|entries| entries.remove(0));
+            assert_eq!(store.parts, [r"C:\b", r"C:\a"]);
+            assert_eq!(store.disabled_flags(), [false, true]);
+            // No key is left behind to disable a copy added later.
+            store.edit_entries(|entries| entries.push((r"C:\a".to_string(), false)));
+            assert_eq!(store.disabled_flags(), [false, true, false]);
+        }
+
+        #[test]
+        fn dedupe_keeps_an_entry_disabled_only_when_every_copy_was() {
+            let mut store = disabled_store(r"C:\a;C:\b;c:\A\;C:\b\;C:\c", &[1, 2, 3]);
+            store.replace_parts(dedupe(&store.parts));
+            assert_eq!(store.parts, [r"C:\a", r"C:\b", r"C:\c"]);
+            assert_eq!(store.disabled_flags(), [false, true, false]);
+        }
+
+        #[test]
+        fn restored_disabled_entries_match_on_position_and_copies() {
+            let edited = disabled_store(r"C:\a;C:\b;C:\a", &[0]);
+            let remembered = edited.remembered_disabled();
+
+            // The registry holds the enabled twin, which must not swallow the disabled copy.
+            let mut store = PathStore::new("Path", join_path(&edited.enabled_parts()), REG_SZ);
+            store.restore_disabled(remembered.clone());
+            assert_eq!(store.parts, edited.parts);
+            assert_eq!(store.disabled_flags(), [true, false, false]);
+
+            // One copy more than was enabled: it was re-enabled outside the editor.
+            let mut store = PathStore::new("Path", r"C:\a;C:\b;C:\a".to_string(), REG_SZ);
+            store.restore_disabled(remembered);
+            assert_eq!(store.parts, [r"C:\a", r"C:\b", r"C:\a"]);
+            assert_eq!(store.disabled_flags(), [false, false, false]);
+        }
+
+        #[test]
+        fn invalid_entries_are_missing_folders_only() {
+            let existing = env::temp_dir().display().to_string();
+            let missing = env::temp_dir().join("pe-test-missing-folder").display().to_string();
+            let list = vec![
+                existing,
+                missing,
+                r"%PE_TEST_NEVER_DEFINED%\bin".to_string(),
+                r"\\pe-test-server\share".to_string(),
+            ];
+            // Undefined variables are a separate problem, and the share may just be offline.
+            assert_eq!(invalid_entries(&list, true), [1]);
+        }
+
+        fn utf16_bytes(units: &[u16]) -> Vec<u8> {
+            units.iter().flat_map(|u| u.to_le_bytes()).collect()
+        }
+
+        #[test]
+        fn canonical_registry_strings_round_trip_byte_identical() {
+            for value in [
+                r"%SystemRoot%\system32;%SystemRoot%;C:\Tools",
+                r"C:\a;;C:\b; ;C:\c;",
+                r"C:\Program Files\Git\cmd",
+                "",
+            ] {
+                let bytes = encode_utf16_reg(value);
+                assert_eq!(decode_utf16_reg(&bytes), value);
+                assert_eq!(encode_utf16_reg(&decode_utf16_reg(&bytes)), bytes);
+            }
+        }
+
+        #[test]
+        fn unchanged_values_are_not_rewritten() {
+            // Written by other tools: two trailing NULs, odd separators, an expandable token.
+            let mut units = r"%SystemRoot%\system32;;C:\x; ".encode_utf16().collect::<Vec<_>>();
+            units.extend([0, 0]);
+            let stored = utf16_bytes(&units);
+            let read = decode_utf16_reg(&stored);
+            assert_eq!(read, r"%SystemRoot%\system32;;C:\x; ");
+            assert!(reg_value_unchanged(&stored, &REG_EXPAND_SZ, &read, &REG_EXPAND_SZ));
+
+            // A type change or an edit does get written.
+            assert!(!reg_value_unchanged(&stored, &REG_EXPAND_SZ, &read, &REG_SZ));
+            assert!(!reg_value_unchanged(&stored, &REG_EXPAND_SZ, r"C:\x", &REG_EXPAND_SZ));
+        }
+
+        #[test]
+        fn a_store_saves_an_untouched_value_as_read() {
+            let raw = r"%SystemRoot%\system32;%SystemRoot%;C:\Tools".to_string();
+            let store = PathStore::new("Path", raw.clone(), REG_EXPAND_SZ);
+            assert_eq!(store.raw_preview(), raw);
+            assert_eq!(store.write_type(), REG_EXPAND_SZ);
+            assert_eq!(encode_utf16_reg(&store.raw_preview()), encode_utf16_reg(&raw));
+        }
+
+        /// Registry stand-in keyed by scope and variable name.
+        #[derive(Default)]
+        struct MemoryBackend {
+            values: RefCell<HashMap<(bool, String), (String, RegType)>>,
+            fail_writes: bool,
+        }
+
+        impl MemoryBackend {
+            fn with(is_system: bool, name: &str, value: &str, vtype: RegType) -> Self {
+                let backend = Self::default();
+                let key = (is_system, name.to_string());
+                backend.values.borrow_mut().insert(key, (value.to_string(), vtype));
+                backend
+            }
+
+            fn get(&self, is_system: bool, name: &str) -> Option<(String, RegType)> {
+                self.values.borrow().get(&(is_system, name.to_string())).cloned()
+            }
+        }
+
+        impl PathBackend for MemoryBackend {
+            fn read(
+                &self,
+                is_system: bool,
+                name: &str,
+            ) -> Result<(String, RegType), Box<dyn Error>> {
+                Ok(self.get(is_system, name).unwrap_or_else(|| (String::new(), REG_SZ)))
+            }
+
+            fn write(
+                &self,
+                is_system: bool,
+                name: &str,
+                value: &str,
+                vtype: RegType,
+            ) -> Result<(), Box<dyn Error>> {
+                if self.fail_writes {
+                    return Err("access denied".into());
+                }
+                let key = (is_system, name.to_string());
+                self.values.borrow_mut().insert(key, (value.to_string(), vtype));
+                Ok(())
+            }
+
+            fn is_registry(&self) -> bool {
+                false
+            }
+        }
+
+        #[test]
+        fn load_edit_save_reload_through_a_backend() {
+            let backend = MemoryBackend::with(false, "Path", r"C:\b;C:\a;c:\A", REG_EXPAND_SZ);
+            let mut store = read_store(&backend, false, "Path");
+            assert_eq!(store.parts, [r"C:\b", r"C:\a", r"c:\A"]);
+
+            store.checkpoint();
+            store.parts = dedupe(&store.parts);
+            sort_case_insensitive(&mut store.parts);
+            store.parts.push(r"C:\Tools".to_string());
+            store.toggle_enabled(1);
+            assert!(store.is_dirty());
+
+            save_store(&backend, false, &mut store).unwrap();
+            assert!(!store.is_dirty());
+            // Disabled entries stay in the list but are not written.
+            let written = backend.get(false, "Path").unwrap();
+            assert_eq!(written, (r"C:\a;C:\Tools".to_string(), REG_EXPAND_SZ));
+            assert_eq!(backend.get(true, "Path"), None);
+
+            let reloaded = read_store(&backend, false, "Path");
+            assert_eq!(reloaded.parts, [r"C:\a", r"C:\Tools"]);
+            assert!(!reloaded.is_dirty());
+        }
+
+        #[test]
+        fn saving_keeps_the_value_type_unless_tokens_need_expand_sz() {
+            for vtype in [REG_SZ, REG_EXPAND_SZ] {
+                let backend = MemoryBackend::with(true, "Path", r"C:\a", vtype.clone());
+                let mut store = read_store(&backend, true, "Path");
+                store.checkpoint();
+                store.parts.push(r"C:\b".to_string());
+                save_store(&backend, true, &mut store).unwrap();
+                assert_eq!(backend.get(true, "Path").unwrap().1, vtype);
+            }
+
+            let backend = MemoryBackend::with(false, "Path", r"C:\a", REG_SZ);
+            let mut store = read_store(&backend, false, "Path");
+            store.checkpoint();
+            store.parts.push(r"%USERPROFILE%\bin".to_string());
+            save_store(&backend, false, &mut store).unwrap();
+            assert_eq!(backend.get(false, "Path").unwrap().1, REG_EXPAND_SZ);
+            assert_eq!(store.reg_type, REG_EXPAND_SZ);
+        }
+
+        #[test]
+        fn a_failed_write_leaves_the_store_unsaved() {
+            let mut backend = MemoryBackend::with(false, "Path", r"C:\a", REG_SZ);
+            backend.fail_writes = true;
+            let mut store = read_store(&backend, false, "Path");
+            store.checkpoint();
+            store.parts.push(r"%USERPROFILE%\bin".to_string());
+
+            assert!(save_store(&backend, false, &mut store).is_err());
+            assert!(store.is_dirty());
+            assert_eq!(store.saved_parts, [r"C:\a"]);
+            assert_eq!(store.reg_type, REG_SZ);
+            assert_eq!(backend.get(false, "Path"), Some((r"C:\a".to_string(), REG_SZ)));
+        }
+
+        #[test]
+        fn batch_export_escapes_percent_and_trailing_backslashes() {
+            let list = parts(&[
+                r"C:\Program Files\Tool",
+                r"%SystemRoot%\bin",
+                r"C:\it's\`tick",
+                r"C:\end\",
+            ]);
+            let script = to_batch(&list, "Path", false, &REG_EXPAND_SZ).unwrap();
+            let command = script.lines().find(|line| line.starts_with("reg add")).unwrap();
+            assert_eq!(
+                command,
+                concat!(
+                    r#"reg add "HKCU\Environment" /v "Path" /t REG_EXPAND_SZ "#,
+                    r#"/d "C:\Program Files\Tool;%%SystemRoot%%\bin;C:\it's\`tick;C:\end\\" /f"#,
+                )
+            );
+            assert!(script.ends_with("\r\n"));
+        }
+
+        #[test]
+        fn batch_export_rejects_double_quotes() {
+            let list = parts(&[r"C:\Tools", r#"C:\say "hi""#]);
+            assert!(to_batch(&list, "Path", true, &REG_SZ).is_err());
+            assert!(to_powershell(&list, "Path", true, &REG_SZ).contains(r#"'C:\say "hi"'"#));
+        }
+
+        #[test]
+        fn powershell_export_uses_literal_single_quoted_strings() {
+            let list = parts(&[
+                r"C:\Program Files\Tool",
+                r"%SystemRoot%\bin",
+                r"C:\it's",
+                r"C:\`tick$home",
+            ]);
+            let script = to_powershell(&list, "Path", true, &REG_SZ);
+            let lines = script.lines().collect::<Vec<_>>();
+            assert_eq!(
+                lines[1..7],
+                [
+                    "$value = @(",
+                    r"    'C:\Program Files\Tool'",
+                    r"    '%SystemRoot%\bin'",
+                    r"    'C:\it''s'",
+                    r"    'C:\`tick$home'",
+                    ") -join ';'",
+                ]
+            );
+            assert_eq!(
+                lines[7],
+                concat!(
+                    r"Set-ItemProperty -LiteralPath ",
+                    r"'HKLM:\SYSTEM\CurrentControlSet\Control\Session Manager\Environment' ",
+                    r"-Name 'Path' -Value $value -Type String",
+                )
+            );
+        }
+
+        fn test_roots() -> Vec<(EntryCategory, String)> {
+            vec![
+                (EntryCategory::System, r"c:\windows".to_string()),
+                (EntryCategory::ProgramFiles, r"c:\program files".to_string()),
+                (EntryCategory::UserProfile, r"c:\users\me".to_string()),
+            ]
+        }
+
+        #[test]
+        fn priority_sort_puts_windows_first_and_keeps_tier_order() {
+            env::set_var("PE_TEST_WINDIR", r"C:\Windows");
+            let mut list = parts(&[
+                r"D:\tools",
+                r"C:\Users\me\bin",
+                r"\\srv\share",
+                r"C:\Program Files\Git\cmd",
+                r"C:\Windows\System32",
+                r"C:\WindowsApps",
+                r"%PE_TEST_WINDIR%\System32\Wbem",
+                r"C:\Program Files\nodejs",
+                r"c:\windows",
+            ]);
+            sort_by_priority(&mut list, &test_roots());
+            assert_eq!(
+                list,
+                [
+                    r"C:\Windows\System32",
+                    r"%PE_TEST_WINDIR%\System32\Wbem",
+                    r"c:\windows",
+                    r"C:\Program Files\Git\cmd",
+                    r"C:\Program Files\nodejs",
+                    r"C:\Users\me\bin",
+                    r"D:\tools",
+                    r"\\srv\share",
+                    r"C:\WindowsApps",
+                ]
+            );
+        }
+
+        #[test]
+        fn system_folders_are_detected_from_the_environment() {
+            let roots = category_roots();
+            for entry in [r"%SystemRoot%\System32", "%SystemRoot%", r"%SystemRoot%\System32\"] {
+                let expanded = expand_env_vars(entry);
+                assert!(classify_path(&expanded, &roots) == EntryCategory::System, "{entry}");
+            }
+            let other = classify_path(r"C:\WindowsApps", &test_roots());
+            assert!(other == EntryCategory::Other);
+        }
+
+        #[test]
+        fn unc_paths_are_recognized_in_either_slash_form() {
+            for path in [r"\\server\share", r"\\server\share\", "//server/share", r"\\server"] {
+                assert!(is_unc(path), "{path}");
+            }
+            for path in [r"\\?\UNC\server\share", r"\\?\C:\Tools", r"\\.\pipe\x", r"C:\Tools"] {
+                assert!(!is_unc(path), "{path}");
+            }
+            assert_eq!(normalize_slashes("//server/share/bin"), r"\\server\share\bin");
+        }
+
+        #[test]
+        fn unc_paths_need_a_server_and_a_share() {
+            for path in [r"\\server\share", r"\\server\share\", "//server/share", r"\\?\UNC\s\x"] {
+                assert!(!is_malformed_unc(path), "{path}");
+                assert_eq!(entry_problem(path), None, "{path}");
+            }
+            for path in [r"\\server", r"\\server\", "//server", r"\\\share", r"\\"] {
+                assert!(is_malformed_unc(path), "{path}");
+                assert_eq!(entry_problem(path), Some(r"UNC path needs \\server\share"), "{path}");
+            }
+        }
+
+        #[test]
+        fn trailing_slash_cleanup_keeps_the_unc_prefix() {
+            assert_eq!(trim_trailing_slashes(r"\\server\share\"), r"\\server\share");
+            assert_eq!(trim_trailing_slashes(r"\\server\share"), r"\\server\share");
+            assert_eq!(trim_trailing_slashes(r"\\"), r"\\");
+            assert_eq!(trim_trailing_slashes(r"C:\"), r"C:\");
+            assert_eq!(trim_trailing_slashes(r"C:\Tools\\"), r"C:\Tools");
+        }
+
+        #[test]
+        fn list_reads_its_scope_from_the_flag_or_the_argument() {
+            for command in [&["--list", "--scope", "system"][..], &["--list", "system"]] {
+                assert!(matches!(
+                    parse_cli(&parts(command)),
+                    Ok(CliCommand::List(true))
+                ));
+            }
+            assert!(matches!(
+                parse_cli(&parts(&["--list"])),
+                Ok(CliCommand::List(false))
+            ));
+            assert!(parse_cli(&parts(&["--list", "user", "--scope", "system"])).is_err());
+        }
+
+        #[test]
+        fn the_helper_takes_the_editor_language() {
+            let command = parts(&["--apply-system", "req.json", "--sha256", "00", "--language", "de"]);
+            assert!(matches!(
+                parse_cli(&command),
+                Ok(CliCommand::ApplySystem(_, _, Language::German))
+            ));
+            let command = parts(&["--apply-system", "req.json", "--sha256", "00"]);
+            assert!(matches!(
+                parse_cli(&command),
+                Ok(CliCommand::ApplySystem(_, _, Language::English))
+            ));
+        }
+
+        #[test]
+        fn nested_variables_expand_fully() {
+            env::set_var("PE_TEST_NEST_A", r"%PE_TEST_NEST_B%\x");
+            env::set_var("PE_TEST_NEST_B", r"C:\base");
+            assert_eq!(expand_env_vars(r"%PE_TEST_NEST_A%\bin"), r"C:\base\x\bin");
+            assert_eq!(expand_env_vars_once(r"%PE_TEST_NEST_A%\bin"), r"%PE_TEST_NEST_B%\x\bin");
+            assert_eq!(entry_problem(r"%PE_TEST_NEST_A%\bin"), None);
+        }
+
+        #[test]
+        fn undefined_variables_inside_a_value_are_reported() {
+            env::set_var("PE_TEST_OUTER", r"%PE_TEST_NEVER_SET_INNER%\x");
+            let info = EntryInfo::compute(r"%PE_TEST_OUTER%\bin");
+            assert_eq!(info.expanded, r"%PE_TEST_NEVER_SET_INNER%\x\bin");
+            assert_eq!(info.unresolved, ["PE_TEST_NEVER_SET_INNER"]);
+        }
+
+        #[test]
+        fn self_referential_variables_stop_and_are_flagged() {
+            env::set_var("PE_TEST_SELF", "%PE_TEST_SELF%");
+            assert_eq!(expand_env_vars(r"%PE_TEST_SELF%\bin"), r"%PE_TEST_SELF%\bin");
+            assert_eq!(entry_problem(r"%PE_TEST_SELF%\bin"), Some("variables do not fully expand"));
+
+            // Grows on every pass, so only the pass limit ends it.
+            env::set_var("PE_TEST_GROW", r"%PE_TEST_GROW%\x");
+            let expected = format!("%PE_TEST_GROW%{}", r"\x".repeat(EXPAND_PASSES));
+            assert_eq!(expand_env_vars("%PE_TEST_GROW%"), expected);
+        }
+
+        #[test]
+        fn expansion_stops_at_the_pass_limit() {
+            // PE_TEST_CHAIN1 -> PE_TEST_CHAIN2 -> ... -> PE_TEST_CHAIN6 -> C:\end
+            for i in 1..6 {
+                env::set_var(format!("PE_TEST_CHAIN{i}"), format!("%PE_TEST_CHAIN{}%", i + 1));
+            }
+            env::set_var("PE_TEST_CHAIN6", r"C:\end");
+            assert_eq!(EXPAND_PASSES, 5);
+            assert_eq!(expand_env_vars("%PE_TEST_CHAIN2%"), r"C:\end");
+            assert_eq!(expand_env_vars("%PE_TEST_CHAIN1%"), "%PE_TEST_CHAIN6%");
+            assert_eq!(entry_problem("%PE_TEST_CHAIN1%"), Some("variables do not fully expand"));
+        }
+    }
 }
 
 #[cfg(target_os = "windows")]
 fn main() -> eframe::Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Some(code) = app::run_cli(&args) {
+        std::process::exit(code);
+    }
     app::run()
 }